@@ -119,3 +119,18 @@ impl From<[u8; 32]> for CompressedPoint {
 
 // `CompressedPoint` から直接 `[u8; 32]` への `From` を実装していない理由:
 // `CompressedPoint::to_bytes` のドキュメントを正とするためです。
+
+// monerod の JSON と同様に、16 進文字列としてシリアライズします。
+#[cfg(feature = "serde")]
+impl serde::Serialize for CompressedPoint {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    monero_io::serde_hex::serialize(self.0, serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CompressedPoint {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    monero_io::serde_hex::deserialize_array(deserializer).map(Self)
+  }
+}
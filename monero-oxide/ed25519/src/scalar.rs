@@ -128,3 +128,26 @@ impl From<Scalar> for [u8; 32] {
     scalar.0
   }
 }
+
+// monerod の JSON と同様に、16 進文字列としてシリアライズします。
+#[cfg(feature = "serde")]
+impl serde::Serialize for Scalar {
+  fn serialize<S: serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> core::result::Result<S::Ok, S::Error> {
+    monero_io::serde_hex::serialize(self.0, serializer)
+  }
+}
+
+// 非正準なスカラーはエラーとなります。
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Scalar {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> core::result::Result<Self, D::Error> {
+    let bytes: [u8; 32] = monero_io::serde_hex::deserialize_array(deserializer)?;
+    Scalar::read(&mut bytes.as_slice())
+      .map_err(|_| serde::de::Error::custom("non-canonical scalar"))
+  }
+}
@@ -11,6 +11,12 @@ use std_shims::io::{self, Read, Write};
 mod varint;
 pub use varint::*;
 
+/// バイト列を 16 進文字列として `serde` でシリアライズするための補助関数。
+///
+/// `#[serde(with = "monero_io::serde_hex")]` として利用できる。
+#[cfg(feature = "serde")]
+pub mod serde_hex;
+
 /// 1 バイトを書き込む補助関数。
 ///
 /// 汎用的なシリアライザ内でのビルディングブロックとして使用される。
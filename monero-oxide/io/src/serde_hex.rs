@@ -0,0 +1,26 @@
+// バイト列を 16 進文字列として `serde` でシリアライズするための補助関数群。
+// monerod の JSON（`as_json` 等）はキー・スカラー・バイナリ列を 16 進文字列で表現します。
+use std_shims::{vec::Vec, string::String};
+
+use serde::{Serializer, Deserialize, Deserializer, de::Error};
+
+/// バイト列を 16 進文字列としてシリアライズする。
+pub fn serialize<S: Serializer>(bytes: impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(&hex::encode(bytes))
+}
+
+/// 16 進文字列を任意長のバイト列としてデシリアライズする。
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+  hex::decode(String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// 16 進文字列を固定長のバイト配列としてデシリアライズする。
+pub fn deserialize_array<'de, D: Deserializer<'de>, const N: usize>(
+  deserializer: D,
+) -> Result<[u8; N], D::Error> {
+  let bytes = deserialize(deserializer)?;
+  let len = bytes.len();
+  bytes
+    .try_into()
+    .map_err(|_| D::Error::invalid_length(len, &"a hex string of the expected length"))
+}
@@ -0,0 +1,123 @@
+// AES のラウンド関数（CryptoNight と Grøstl で共有）
+
+/// Multiply an element of GF(2**8) by two.
+pub(crate) const fn xtime(a: u8) -> u8 {
+  (a << 1) ^ (if (a & 0x80) == 0 { 0 } else { 0x1b })
+}
+
+/// Multiply two elements of GF(2**8), as defined by AES (modulo x**8 + x**4 + x**3 + x + 1).
+pub(crate) const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+  let mut res = 0;
+  while b != 0 {
+    if (b & 1) == 1 {
+      res ^= a;
+    }
+    a = xtime(a);
+    b >>= 1;
+  }
+  res
+}
+
+const fn sbox() -> [u8; 256] {
+  let mut res = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    #[allow(clippy::cast_possible_truncation)]
+    let x = i as u8;
+    // The multiplicative inverse, calculated as x**254, with 0 mapping to 0
+    let mut inverse = 1;
+    let mut j = 0;
+    while j < 254 {
+      inverse = gf_mul(inverse, x);
+      j += 1;
+    }
+    res[i] = inverse ^
+      inverse.rotate_left(1) ^
+      inverse.rotate_left(2) ^
+      inverse.rotate_left(3) ^
+      inverse.rotate_left(4) ^
+      0x63;
+    i += 1;
+  }
+  res
+}
+
+/// The AES S-box.
+pub(crate) const SBOX: [u8; 256] = sbox();
+
+/// The first ten round keys of the AES-256 key schedule, as used by CryptoNight.
+pub(crate) type RoundKeys = [[u8; 16]; 10];
+
+/// Expand a 256-bit key into the round keys used by CryptoNight.
+pub(crate) fn expand_key(key: &[u8; 32]) -> RoundKeys {
+  let mut words = [[0; 4]; 40];
+  for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+    word.copy_from_slice(chunk);
+  }
+
+  let mut rcon = 1;
+  for i in 8 .. words.len() {
+    let mut temp = words[i - 1];
+    if (i % 8) == 0 {
+      temp.rotate_left(1);
+      for byte in &mut temp {
+        *byte = SBOX[usize::from(*byte)];
+      }
+      temp[0] ^= rcon;
+      rcon = xtime(rcon);
+    } else if (i % 8) == 4 {
+      for byte in &mut temp {
+        *byte = SBOX[usize::from(*byte)];
+      }
+    }
+    for j in 0 .. 4 {
+      words[i][j] = words[i - 8][j] ^ temp[j];
+    }
+  }
+
+  let mut keys = [[0; 16]; 10];
+  for (key, words) in keys.iter_mut().zip(words.chunks_exact(4)) {
+    for (chunk, word) in key.chunks_exact_mut(4).zip(words) {
+      chunk.copy_from_slice(word);
+    }
+  }
+  keys
+}
+
+/// A single AES encryption round (SubBytes, ShiftRows, MixColumns, AddRoundKey).
+///
+/// This is equivalent to the `AESENC` instruction.
+pub(crate) fn round(block: &mut [u8; 16], key: &[u8; 16]) {
+  // SubBytes and ShiftRows, with the state stored column-major
+  let mut shifted = [0; 16];
+  for column in 0 .. 4 {
+    for row in 0 .. 4 {
+      shifted[(column * 4) + row] = SBOX[usize::from(block[(((column + row) % 4) * 4) + row])];
+    }
+  }
+
+  // MixColumns and AddRoundKey
+  for column in 0 .. 4 {
+    let a = &shifted[(column * 4) .. ((column + 1) * 4)];
+    let doubled = [xtime(a[0]), xtime(a[1]), xtime(a[2]), xtime(a[3])];
+    for row in 0 .. 4 {
+      // 2 * a[row] + 3 * a[row + 1] + a[row + 2] + a[row + 3]
+      block[(column * 4) + row] = doubled[row] ^
+        doubled[(row + 1) % 4] ^
+        a[(row + 1) % 4] ^
+        a[(row + 2) % 4] ^
+        a[(row + 3) % 4] ^
+        key[(column * 4) + row];
+    }
+  }
+}
+
+/// Apply all ten rounds of CryptoNight's AES pseudo-round to a block.
+///
+/// This differs from AES encryption as there's no initial AddRoundKey and the final round
+/// includes MixColumns.
+pub(crate) fn pseudo_round(block: &mut [u8; 16], keys: &RoundKeys) {
+  for key in keys {
+    round(block, key);
+  }
+}
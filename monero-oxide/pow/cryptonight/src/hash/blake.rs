@@ -0,0 +1,107 @@
+// BLAKE-256（SHA-3 最終候補版、14 ラウンド）
+
+const IV: [u32; 8] =
+  [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+const CONSTANTS: [u32; 16] = [
+  0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344, 0xa4093822, 0x299f31d0, 0x082efa98, 0xec4e6c89,
+  0x452821e6, 0x38d01377, 0xbe5466cf, 0x34e90c6c, 0xc0ac29b7, 0xc97c50dd, 0x3f84d5b5, 0xb5470917,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+  [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+  [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+  [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+  [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+  [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+  [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+  [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+  [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+  [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+  [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+const ROUNDS: usize = 14;
+
+fn compress(h: &mut [u32; 8], block: &[u8; 64], counter: u64) {
+  let mut m = [0; 16];
+  for (m, chunk) in m.iter_mut().zip(block.chunks_exact(4)) {
+    *m = u32::from_be_bytes(chunk.try_into().unwrap());
+  }
+
+  #[allow(clippy::cast_possible_truncation)]
+  let (t0, t1) = (counter as u32, (counter >> 32) as u32);
+  let mut v = [0; 16];
+  v[.. 8].copy_from_slice(h);
+  v[8 .. 12].copy_from_slice(&CONSTANTS[.. 4]);
+  v[12] = t0 ^ CONSTANTS[4];
+  v[13] = t0 ^ CONSTANTS[5];
+  v[14] = t1 ^ CONSTANTS[6];
+  v[15] = t1 ^ CONSTANTS[7];
+
+  for round in 0 .. ROUNDS {
+    let sigma = &SIGMA[round % 10];
+    let mut g = |i: usize, a: usize, b: usize, c: usize, d: usize| {
+      let (x, y) = (sigma[2 * i], sigma[(2 * i) + 1]);
+      v[a] = v[a].wrapping_add(v[b]).wrapping_add(m[x] ^ CONSTANTS[y]);
+      v[d] = (v[d] ^ v[a]).rotate_right(16);
+      v[c] = v[c].wrapping_add(v[d]);
+      v[b] = (v[b] ^ v[c]).rotate_right(12);
+      v[a] = v[a].wrapping_add(v[b]).wrapping_add(m[y] ^ CONSTANTS[x]);
+      v[d] = (v[d] ^ v[a]).rotate_right(8);
+      v[c] = v[c].wrapping_add(v[d]);
+      v[b] = (v[b] ^ v[c]).rotate_right(7);
+    };
+    g(0, 0, 4, 8, 12);
+    g(1, 1, 5, 9, 13);
+    g(2, 2, 6, 10, 14);
+    g(3, 3, 7, 11, 15);
+    g(4, 0, 5, 10, 15);
+    g(5, 1, 6, 11, 12);
+    g(6, 2, 7, 8, 13);
+    g(7, 3, 4, 9, 14);
+  }
+
+  for i in 0 .. 8 {
+    h[i] ^= v[i] ^ v[i + 8];
+  }
+}
+
+/// BLAKE-256, with a zero salt.
+pub(crate) fn blake256(data: &[u8]) -> [u8; 32] {
+  let mut h = IV;
+
+  let bits = u64::try_from(data.len()).expect("hashing more than 2**64 bytes") * 8;
+  // Every full block is compressed, even if it's the last block of data
+  let full = data.len() - (data.len() % 64);
+  let mut counter = 0;
+  for block in data[.. full].chunks_exact(64) {
+    counter += 512;
+    compress(&mut h, block.try_into().unwrap(), counter);
+  }
+
+  let remaining = &data[full ..];
+  let mut block = [0; 64];
+  block[.. remaining.len()].copy_from_slice(remaining);
+  block[remaining.len()] = 0x80;
+  // The counter is only incremented by the amount of message bits within a block, with it set to
+  // zero for blocks which solely consist of padding
+  let counter = if remaining.is_empty() { 0 } else { bits };
+  if remaining.len() >= 56 {
+    compress(&mut h, &block, counter);
+    block = [0; 64];
+    block[55] = 1;
+    block[56 ..].copy_from_slice(&bits.to_be_bytes());
+    compress(&mut h, &block, 0);
+  } else {
+    block[55] |= 1;
+    block[56 ..].copy_from_slice(&bits.to_be_bytes());
+    compress(&mut h, &block, counter);
+  }
+
+  let mut res = [0; 32];
+  for (chunk, h) in res.chunks_exact_mut(4).zip(h) {
+    chunk.copy_from_slice(&h.to_be_bytes());
+  }
+  res
+}
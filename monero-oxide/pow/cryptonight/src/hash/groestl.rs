@@ -0,0 +1,98 @@
+// Grøstl-256
+
+use crate::aes::{SBOX, gf_mul};
+
+const ROUNDS: u8 = 10;
+
+// The state is a 8x8 matrix of bytes, stored column-major
+type State = [u8; 64];
+
+const MIX: [u8; 8] = [2, 2, 3, 4, 5, 3, 5, 7];
+
+fn permutation(state: &mut State, q: bool) {
+  let shifts: [usize; 8] = if q { [1, 3, 5, 7, 0, 2, 4, 6] } else { [0, 1, 2, 3, 4, 5, 6, 7] };
+
+  for round in 0 .. ROUNDS {
+    // AddRoundConstant
+    for column in 0 .. 8u8 {
+      let constant = (column << 4) ^ round;
+      let column = usize::from(column);
+      if q {
+        for row in 0 .. 7 {
+          state[(column * 8) + row] ^= 0xff;
+        }
+        state[(column * 8) + 7] ^= 0xff ^ constant;
+      } else {
+        state[column * 8] ^= constant;
+      }
+    }
+
+    // SubBytes and ShiftBytes
+    let mut shifted = [0; 64];
+    for column in 0 .. 8 {
+      for (row, shift) in shifts.iter().enumerate() {
+        shifted[(column * 8) + row] = SBOX[usize::from(state[(((column + shift) % 8) * 8) + row])];
+      }
+    }
+
+    // MixBytes
+    for column in 0 .. 8 {
+      let a = &shifted[(column * 8) .. ((column + 1) * 8)];
+      for row in 0 .. 8 {
+        let mut res = 0;
+        for (k, a) in a.iter().enumerate() {
+          res ^= gf_mul(*a, MIX[(k + 8 - row) % 8]);
+        }
+        state[(column * 8) + row] = res;
+      }
+    }
+  }
+}
+
+fn compress(h: &mut State, block: &[u8; 64]) {
+  let mut p = *h;
+  for (p, m) in p.iter_mut().zip(block) {
+    *p ^= m;
+  }
+  permutation(&mut p, false);
+
+  let mut q = *block;
+  permutation(&mut q, true);
+
+  for ((h, p), q) in h.iter_mut().zip(p).zip(q) {
+    *h ^= p ^ q;
+  }
+}
+
+/// Grøstl-256.
+pub(crate) fn groestl256(data: &[u8]) -> [u8; 32] {
+  let mut h = [0; 64];
+  // The initial value encodes the output length in bits
+  h[62] = 1;
+
+  let full = data.len() - (data.len() % 64);
+  for block in data[.. full].chunks_exact(64) {
+    compress(&mut h, block.try_into().unwrap());
+  }
+
+  // Pad with a set bit, zeroes, and the total amount of blocks as a 64-bit big-endian integer
+  let remaining = &data[full ..];
+  let mut padding = [0; 128];
+  padding[.. remaining.len()].copy_from_slice(remaining);
+  padding[remaining.len()] = 0x80;
+  let padding_len = if remaining.len() < 56 { 64 } else { 128 };
+  let blocks = u64::try_from((full + padding_len) / 64).unwrap();
+  padding[(padding_len - 8) .. padding_len].copy_from_slice(&blocks.to_be_bytes());
+  for block in padding[.. padding_len].chunks_exact(64) {
+    compress(&mut h, block.try_into().unwrap());
+  }
+
+  // Output transformation
+  let mut p = h;
+  permutation(&mut p, false);
+  let mut res = [0; 32];
+  for (i, res) in res.iter_mut().enumerate() {
+    *res = p[32 + i] ^ h[32 + i];
+  }
+  res
+}
@@ -0,0 +1,148 @@
+// JH-256（リファレンス実装に準拠した 4 ビット要素単位の実装）
+
+const SBOXES: [[u8; 16]; 2] = [
+  [9, 0, 4, 11, 13, 12, 3, 15, 1, 10, 2, 6, 7, 5, 8, 14],
+  [3, 12, 6, 13, 5, 7, 1, 9, 15, 2, 0, 4, 11, 10, 14, 8],
+];
+
+// The first round constant, the fractional part of the square root of two
+const INITIAL_ROUND_CONSTANT: [u8; 32] = [
+  0x6a, 0x09, 0xe6, 0x67, 0xf3, 0xbc, 0xc9, 0x08, 0xb2, 0xfb, 0x13, 0x66, 0xea, 0x95, 0x7d, 0x3e,
+  0x3a, 0xde, 0xc1, 0x75, 0x12, 0x77, 0x50, 0x99, 0xda, 0x2f, 0x59, 0x0b, 0x06, 0x67, 0x32, 0x2a,
+];
+
+const ROUNDS: usize = 42;
+
+// Multiplication by two within GF(2**4)
+fn double(a: u8) -> u8 {
+  ((a << 1) ^ (a >> 3) ^ ((a >> 2) & 2)) & 0xf
+}
+
+// The linear transformation, an MDS code
+fn linear(a: &mut u8, b: &mut u8) {
+  *b ^= double(*a);
+  *a ^= double(*b);
+}
+
+// The permutation layer, shared by the round function and round constant generation
+fn permute(elements: &mut [u8]) {
+  let len = elements.len();
+  for i in (0 .. len).step_by(4) {
+    elements.swap(i + 2, i + 3);
+  }
+
+  let mut permuted = [0; 256];
+  for i in 0 .. (len / 2) {
+    permuted[i] = elements[i << 1];
+    permuted[i + (len / 2)] = elements[(i << 1) + 1];
+  }
+  elements.copy_from_slice(&permuted[.. len]);
+
+  for i in ((len / 2) .. len).step_by(2) {
+    elements.swap(i, i + 1);
+  }
+}
+
+// The round function of R8, with the round constant determining the S-box used for each element
+fn round(state: &mut [u8; 256], constant: &[u8; 64]) {
+  for (i, element) in state.iter_mut().enumerate() {
+    let bit = (constant[i >> 2] >> (3 - (i & 3))) & 1;
+    *element = SBOXES[usize::from(bit)][usize::from(*element)];
+  }
+  for pair in state.chunks_exact_mut(2) {
+    let (a, b) = pair.split_at_mut(1);
+    linear(&mut a[0], &mut b[0]);
+  }
+  permute(state);
+}
+
+// R6 with a round constant of zero, used to derive the next round constant
+fn update_round_constant(constant: &mut [u8; 64]) {
+  for element in constant.iter_mut() {
+    *element = SBOXES[0][usize::from(*element)];
+  }
+  for pair in constant.chunks_exact_mut(2) {
+    let (a, b) = pair.split_at_mut(1);
+    linear(&mut a[0], &mut b[0]);
+  }
+  permute(constant);
+}
+
+fn bit(h: &[u8; 128], i: usize) -> u8 {
+  (h[i >> 3] >> (7 - (i & 7))) & 1
+}
+
+// The bijective function E8
+fn e8(h: &mut [u8; 128]) {
+  let mut constant = [0; 64];
+  for (i, element) in constant.iter_mut().enumerate() {
+    *element = (INITIAL_ROUND_CONSTANT[i >> 1] >> ((1 - (i & 1)) << 2)) & 0xf;
+  }
+
+  // Group the bits of the state into 4-bit elements
+  let mut state = [0; 256];
+  for i in 0 .. 256 {
+    let element =
+      (bit(h, i) << 3) | (bit(h, i + 256) << 2) | (bit(h, i + 512) << 1) | bit(h, i + 768);
+    if i < 128 {
+      state[i << 1] = element;
+    } else {
+      state[((i - 128) << 1) + 1] = element;
+    }
+  }
+
+  for _ in 0 .. ROUNDS {
+    round(&mut state, &constant);
+    update_round_constant(&mut constant);
+  }
+
+  // De-group the elements back into bits
+  *h = [0; 128];
+  for i in 0 .. 256 {
+    let element = if i < 128 { state[i << 1] } else { state[((i - 128) << 1) + 1] };
+    let shift = 7 - (i & 7);
+    h[i >> 3] |= ((element >> 3) & 1) << shift;
+    h[(i + 256) >> 3] |= ((element >> 2) & 1) << shift;
+    h[(i + 512) >> 3] |= ((element >> 1) & 1) << shift;
+    h[(i + 768) >> 3] |= (element & 1) << shift;
+  }
+}
+
+// The compression function F8
+fn compress(h: &mut [u8; 128], block: &[u8; 64]) {
+  for (h, m) in h[.. 64].iter_mut().zip(block) {
+    *h ^= m;
+  }
+  e8(h);
+  for (h, m) in h[64 ..].iter_mut().zip(block) {
+    *h ^= m;
+  }
+}
+
+/// JH-256.
+pub(crate) fn jh256(data: &[u8]) -> [u8; 32] {
+  // The initial value is the compression of a zero block into the output length in bits
+  let mut h = [0; 128];
+  h[0] = 1;
+  compress(&mut h, &[0; 64]);
+
+  let full = data.len() - (data.len() % 64);
+  for block in data[.. full].chunks_exact(64) {
+    compress(&mut h, block.try_into().unwrap());
+  }
+
+  // Pad with a set bit, zeroes, and the length in bits as a 128-bit big-endian integer
+  // At least a full block of padding is always used
+  let remaining = &data[full ..];
+  let mut padding = [0; 128];
+  padding[.. remaining.len()].copy_from_slice(remaining);
+  padding[remaining.len()] = 0x80;
+  let padding_len = if remaining.is_empty() { 64 } else { 128 };
+  let bits = u128::try_from(data.len()).unwrap() * 8;
+  padding[(padding_len - 16) .. padding_len].copy_from_slice(&bits.to_be_bytes());
+  for block in padding[.. padding_len].chunks_exact(64) {
+    compress(&mut h, block.try_into().unwrap());
+  }
+
+  h[96 ..].try_into().unwrap()
+}
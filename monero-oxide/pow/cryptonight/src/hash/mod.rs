@@ -0,0 +1,72 @@
+// CryptoNight が内部で用いるハッシュ関数群
+
+mod blake;
+pub(crate) use blake::blake256;
+mod groestl;
+pub(crate) use groestl::groestl256;
+mod jh;
+pub(crate) use jh::jh256;
+mod skein;
+pub(crate) use skein::skein512_256;
+
+pub(crate) const KECCAK_STATE_LEN: usize = 200;
+const KECCAK_RATE: usize = 136;
+
+fn keccak_lanes(state: &[u8; KECCAK_STATE_LEN]) -> [u64; 25] {
+  let mut lanes = [0; 25];
+  for (lane, chunk) in lanes.iter_mut().zip(state.chunks_exact(8)) {
+    *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+  }
+  lanes
+}
+
+fn keccak_bytes(lanes: [u64; 25]) -> [u8; KECCAK_STATE_LEN] {
+  let mut state = [0; KECCAK_STATE_LEN];
+  for (chunk, lane) in state.chunks_exact_mut(8).zip(lanes) {
+    chunk.copy_from_slice(&lane.to_le_bytes());
+  }
+  state
+}
+
+/// Apply the Keccak-f[1600] permutation to a state.
+pub(crate) fn keccak_permutation(state: &mut [u8; KECCAK_STATE_LEN]) {
+  let mut lanes = keccak_lanes(state);
+  keccak::f1600(&mut lanes);
+  *state = keccak_bytes(lanes);
+}
+
+/// Keccak (with the original padding, as used by Keccak-256), returning the entire state.
+pub(crate) fn keccak1600(data: &[u8]) -> [u8; KECCAK_STATE_LEN] {
+  let mut lanes = [0; 25];
+  let absorb = |lanes: &mut [u64; 25], block: &[u8]| {
+    for (lane, chunk) in lanes.iter_mut().zip(block.chunks_exact(8)) {
+      *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    keccak::f1600(lanes);
+  };
+
+  let full = data.len() - (data.len() % KECCAK_RATE);
+  for block in data[.. full].chunks_exact(KECCAK_RATE) {
+    absorb(&mut lanes, block);
+  }
+
+  let remaining = &data[full ..];
+  let mut block = [0; KECCAK_RATE];
+  block[.. remaining.len()].copy_from_slice(remaining);
+  block[remaining.len()] = 1;
+  block[KECCAK_RATE - 1] |= 0x80;
+  absorb(&mut lanes, &block);
+
+  keccak_bytes(lanes)
+}
+
+/// The final hash, selected by the low two bits of the first byte of the state.
+pub(crate) fn final_hash(state: &[u8; KECCAK_STATE_LEN]) -> [u8; 32] {
+  match state[0] & 3 {
+    0 => blake256(state),
+    1 => groestl256(state),
+    2 => jh256(state),
+    3 => skein512_256(state),
+    _ => unreachable!("value was masked to two bits"),
+  }
+}
@@ -0,0 +1,121 @@
+// Skein-512-256（Skein 1.3）
+
+const KEY_SCHEDULE_PARITY: u64 = 0x1bd11bdaa9fc1a22;
+
+const ROTATIONS: [[u32; 4]; 8] = [
+  [46, 36, 19, 37],
+  [33, 27, 14, 42],
+  [17, 49, 36, 39],
+  [44, 9, 54, 56],
+  [39, 30, 34, 24],
+  [13, 50, 10, 17],
+  [25, 29, 39, 43],
+  [8, 35, 56, 22],
+];
+
+const PERMUTATION: [usize; 8] = [2, 1, 4, 7, 6, 5, 0, 3];
+
+const ROUNDS: usize = 72;
+
+const TYPE_CONFIG: u64 = 4;
+const TYPE_MESSAGE: u64 = 48;
+const TYPE_OUTPUT: u64 = 63;
+const FIRST: u64 = 1 << 62;
+const FINAL: u64 = 1 << 63;
+
+// Threefish-512
+fn threefish(key: &[u64; 8], tweak: [u64; 2], plaintext: [u64; 8]) -> [u64; 8] {
+  let mut k = [0; 9];
+  k[.. 8].copy_from_slice(key);
+  k[8] = key.iter().fold(KEY_SCHEDULE_PARITY, |acc, k| acc ^ k);
+  let t = [tweak[0], tweak[1], tweak[0] ^ tweak[1]];
+
+  let subkey = |s: usize| {
+    let mut subkey = [0u64; 8];
+    for (i, subkey) in subkey.iter_mut().enumerate() {
+      *subkey = k[(s + i) % 9];
+    }
+    subkey[5] = subkey[5].wrapping_add(t[s % 3]);
+    subkey[6] = subkey[6].wrapping_add(t[(s + 1) % 3]);
+    subkey[7] = subkey[7].wrapping_add(u64::try_from(s).unwrap());
+    subkey
+  };
+  let add_subkey = |v: &mut [u64; 8], s: usize| {
+    for (v, k) in v.iter_mut().zip(subkey(s)) {
+      *v = v.wrapping_add(k);
+    }
+  };
+
+  let mut v = plaintext;
+  for d in 0 .. ROUNDS {
+    if (d % 4) == 0 {
+      add_subkey(&mut v, d / 4);
+    }
+
+    let mut f = [0; 8];
+    for (j, rotation) in ROTATIONS[d % 8].iter().enumerate() {
+      let (x0, x1) = (v[2 * j], v[(2 * j) + 1]);
+      f[2 * j] = x0.wrapping_add(x1);
+      f[(2 * j) + 1] = x1.rotate_left(*rotation) ^ f[2 * j];
+    }
+    for (v, i) in v.iter_mut().zip(PERMUTATION) {
+      *v = f[i];
+    }
+  }
+  add_subkey(&mut v, ROUNDS / 4);
+
+  v
+}
+
+fn words(block: &[u8; 64]) -> [u64; 8] {
+  let mut res = [0; 8];
+  for (res, chunk) in res.iter_mut().zip(block.chunks_exact(8)) {
+    *res = u64::from_le_bytes(chunk.try_into().unwrap());
+  }
+  res
+}
+
+// Unique Block Iteration
+fn ubi(g: &mut [u64; 8], message: &[u8], kind: u64) {
+  let blocks = message.len().div_ceil(64).max(1);
+  for i in 0 .. blocks {
+    let start = i * 64;
+    let end = message.len().min(start + 64);
+    let mut block = [0; 64];
+    block[.. (end - start)].copy_from_slice(&message[start .. end]);
+
+    let mut tweak1 = kind << 56;
+    if i == 0 {
+      tweak1 |= FIRST;
+    }
+    if i == (blocks - 1) {
+      tweak1 |= FINAL;
+    }
+
+    let plaintext = words(&block);
+    let ciphertext = threefish(g, [u64::try_from(end).unwrap(), tweak1], plaintext);
+    for ((g, c), p) in g.iter_mut().zip(ciphertext).zip(plaintext) {
+      *g = c ^ p;
+    }
+  }
+}
+
+/// Skein-512-256.
+pub(crate) fn skein512_256(data: &[u8]) -> [u8; 32] {
+  // The configuration block, with the schema identifier, version, and output length in bits
+  let mut config = [0; 32];
+  config[.. 4].copy_from_slice(b"SHA3");
+  config[4 .. 6].copy_from_slice(&1u16.to_le_bytes());
+  config[8 .. 16].copy_from_slice(&256u64.to_le_bytes());
+
+  let mut g = [0; 8];
+  ubi(&mut g, &config, TYPE_CONFIG);
+  ubi(&mut g, data, TYPE_MESSAGE);
+  ubi(&mut g, &0u64.to_le_bytes(), TYPE_OUTPUT);
+
+  let mut res = [0; 32];
+  for (chunk, g) in res.chunks_exact_mut(8).zip(g) {
+    chunk.copy_from_slice(&g.to_le_bytes());
+  }
+  res
+}
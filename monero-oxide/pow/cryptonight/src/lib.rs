@@ -0,0 +1,341 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc = include_str!("../README.md")]
+#![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use std_shims::vec;
+
+use zeroize::Zeroizing;
+
+use monero_oxide::{block::Block, difficulty::check_hash};
+
+mod aes;
+mod hash;
+use hash::{KECCAK_STATE_LEN, keccak1600, keccak_permutation, final_hash};
+mod random_math;
+use random_math::Program;
+
+#[cfg(test)]
+mod tests;
+
+// The size of the scratchpad
+const MEMORY: usize = 1 << 21;
+// The amount of iterations of the main loop, each performing two memory accesses
+const ITERATIONS: usize = 1 << 19;
+const BLOCKS: usize = MEMORY / 16;
+
+// The proof-of-work hash of block #202,612, which is hard-coded due to a historical bug
+const POW_HASH_202612: [u8; 32] =
+  hex_literal::hex!("84f64766475d51837ac9efbef1926486e58563c95a19fef4aec3254f03000000");
+
+/// An error when calculating a CryptoNight hash.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum CryptoNightError {
+  /// CryptoNight v1 requires at least 43 bytes of data.
+  #[error("CryptoNight v1 requires at least 43 bytes of data")]
+  InsufficientData,
+  /// The hard fork doesn't use CryptoNight for its proof of work.
+  #[error("hard fork {0} doesn't use CryptoNight")]
+  UnsupportedHardFork(u8),
+}
+
+/// A variant of CryptoNight.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variant {
+  /// The original CryptoNight.
+  V0,
+  /// CryptoNight v1 (also known as CryptoNight v7), introduced with hard fork 7.
+  V1,
+  /// CryptoNight v2 (also known as CryptoNight v8), introduced with hard fork 8.
+  V2,
+  /// CryptoNight R, introduced with hard fork 10, which is dependent on the block's height.
+  R {
+    /// The height of the block being hashed.
+    height: u64,
+  },
+}
+
+impl Variant {
+  /// The variant of CryptoNight used for a block with the specified hard fork version and height.
+  ///
+  /// This will return `None` for hard forks which use RandomX.
+  pub fn from_hardfork_version(hardfork_version: u8, height: u64) -> Option<Variant> {
+    match hardfork_version {
+      0 ..= 6 => Some(Variant::V0),
+      7 => Some(Variant::V1),
+      8 | 9 => Some(Variant::V2),
+      10 | 11 => Some(Variant::R { height }),
+      _ => None,
+    }
+  }
+
+  fn v1(self) -> bool {
+    self == Variant::V1
+  }
+
+  fn v2_or_later(self) -> bool {
+    matches!(self, Variant::V2 | Variant::R { .. })
+  }
+}
+
+fn words(block: [u8; 16]) -> [u64; 2] {
+  [
+    u64::from_le_bytes(block[.. 8].try_into().unwrap()),
+    u64::from_le_bytes(block[8 ..].try_into().unwrap()),
+  ]
+}
+
+fn from_words(words: [u64; 2]) -> [u8; 16] {
+  let mut res = [0; 16];
+  res[.. 8].copy_from_slice(&words[0].to_le_bytes());
+  res[8 ..].copy_from_slice(&words[1].to_le_bytes());
+  res
+}
+
+fn xor(a: [u8; 16], b: [u8; 16]) -> [u8; 16] {
+  let mut res = a;
+  for (res, b) in res.iter_mut().zip(b) {
+    *res ^= b;
+  }
+  res
+}
+
+fn add(a: [u8; 16], b: [u8; 16]) -> [u8; 16] {
+  let (a, b) = (words(a), words(b));
+  from_words([a[0].wrapping_add(b[0]), a[1].wrapping_add(b[1])])
+}
+
+// The index of the scratchpad block addressed by a value
+fn index(value: [u8; 16]) -> usize {
+  usize::try_from(words(value)[0] >> 4).unwrap() & (BLOCKS - 1)
+}
+
+fn block(state: &[u8; KECCAK_STATE_LEN], offset: usize) -> [u8; 16] {
+  state[offset .. (offset + 16)].try_into().unwrap()
+}
+
+// The shuffle added by v2, which mixes the neighboring blocks of the accessed block
+fn shuffle_add(
+  variant: Variant,
+  scratchpad: &mut [[u8; 16]],
+  i: usize,
+  out: &mut [u8; 16],
+  a: [u8; 16],
+  b: [[u8; 16]; 2],
+) {
+  if !variant.v2_or_later() {
+    return;
+  }
+
+  let chunk1 = scratchpad[i ^ 1];
+  let chunk2 = scratchpad[i ^ 2];
+  let chunk3 = scratchpad[i ^ 3];
+  scratchpad[i ^ 1] = add(chunk3, b[1]);
+  scratchpad[i ^ 2] = add(chunk1, b[0]);
+  scratchpad[i ^ 3] = add(chunk2, a);
+
+  if matches!(variant, Variant::R { .. }) {
+    *out = xor(xor(*out, chunk3), xor(chunk1, chunk2));
+  }
+}
+
+// The integer square root used by v2, `floor(sqrt(2**64 + n) * 2 - 2**33)`
+fn v2_sqrt(mut n: u64) -> u64 {
+  let mut r = 1 << 63;
+  let mut bit = 1 << 60;
+  while bit != 0 {
+    if n >= (r + bit) {
+      n -= r + bit;
+      r += bit * 2;
+    }
+    r >>= 1;
+    bit >>= 2;
+  }
+  // This yields `floor(sqrt(2**64 + n) * 2)`, which is always at least 2**33
+  ((r * 2) + u64::from(n > r)) - (1 << 33)
+}
+
+/// Calculate the CryptoNight hash of some data.
+pub fn cryptonight(variant: Variant, data: &[u8]) -> Result<[u8; 32], CryptoNightError> {
+  let mut state = keccak1600(data);
+
+  // v1 tweaks the stored values with the state and the data's bytes where the nonce would be
+  let tweak1_2 = if variant.v1() {
+    let nonce = data.get(35 .. 43).ok_or(CryptoNightError::InsufficientData)?;
+    u64::from_le_bytes(state[192 ..].try_into().unwrap()) ^
+      u64::from_le_bytes(nonce.try_into().unwrap())
+  } else {
+    0
+  };
+
+  // Initialize the scratchpad by repeatedly encrypting part of the state
+  let mut scratchpad = vec![[0; 16]; BLOCKS];
+  {
+    let keys = aes::expand_key(state[.. 32].try_into().unwrap());
+    let mut text: [[u8; 16]; 8] = core::array::from_fn(|i| block(&state, 64 + (i * 16)));
+    for chunk in scratchpad.chunks_exact_mut(8) {
+      for block in &mut text {
+        aes::pseudo_round(block, &keys);
+      }
+      chunk.copy_from_slice(&text);
+    }
+  }
+
+  let mut a = xor(block(&state, 0), block(&state, 32));
+  let mut b = [xor(block(&state, 16), block(&state, 48)), [0; 16]];
+
+  let mut division_result = 0;
+  let mut sqrt_result = 0;
+  if variant.v2_or_later() {
+    b[1] = xor(block(&state, 64), block(&state, 80));
+    division_result = u64::from_le_bytes(state[96 .. 104].try_into().unwrap());
+    sqrt_result = u64::from_le_bytes(state[104 .. 112].try_into().unwrap());
+  }
+
+  let mut registers = [0u32; 9];
+  let program = if let Variant::R { height } = variant {
+    for (register, chunk) in registers.iter_mut().zip(state[96 .. 112].chunks_exact(4)) {
+      *register = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Some(Program::new(height))
+  } else {
+    None
+  };
+
+  for _ in 0 .. ITERATIONS {
+    // The first access, an AES round
+    let i = index(a);
+    let mut c1 = scratchpad[i];
+    aes::round(&mut c1, &a);
+    shuffle_add(variant, &mut scratchpad, i, &mut c1, a, b);
+    scratchpad[i] = xor(c1, b[0]);
+    if variant.v1() {
+      let tmp = scratchpad[i][11];
+      let index = (((tmp >> 3) & 6) | (tmp & 1)) << 1;
+      #[allow(clippy::cast_possible_truncation)]
+      let tweak = ((0x75310u32 >> index) & 0x30) as u8;
+      scratchpad[i][11] = tmp ^ tweak;
+    }
+
+    // The second access, a multiplication
+    let i = index(c1);
+    let mut c = words(scratchpad[i]);
+    let mut a1 = a;
+    let c1_words = words(c1);
+
+    if variant == Variant::V2 {
+      c[0] ^= division_result ^ (sqrt_result << 32);
+      let dividend = c1_words[1];
+      #[allow(clippy::cast_possible_truncation)]
+      let divisor = (c1_words[0].wrapping_add(sqrt_result << 1) as u32) | 0x80000001;
+      let divisor = u64::from(divisor);
+      division_result = (dividend / divisor) & u64::from(u32::MAX);
+      division_result += (dividend % divisor) << 32;
+      sqrt_result = v2_sqrt(c1_words[0].wrapping_add(division_result));
+    }
+
+    if let Some(program) = &program {
+      #[allow(clippy::cast_possible_truncation)]
+      {
+        c[0] ^= u64::from(registers[0].wrapping_add(registers[1])) |
+          (u64::from(registers[2].wrapping_add(registers[3])) << 32);
+
+        let a1_words = words(a1);
+        registers[4] = a1_words[0] as u32;
+        registers[5] = a1_words[1] as u32;
+        registers[6] = words(b[0])[0] as u32;
+        registers[7] = words(b[1])[0] as u32;
+        registers[8] = words(b[1])[1] as u32;
+      }
+
+      program.execute(&mut registers);
+
+      let a1_words = words(a1);
+      a1 = from_words([
+        a1_words[0] ^ (u64::from(registers[2]) | (u64::from(registers[3]) << 32)),
+        a1_words[1] ^ (u64::from(registers[0]) | (u64::from(registers[1]) << 32)),
+      ]);
+    }
+
+    let product = u128::from(c1_words[0]) * u128::from(c[0]);
+    #[allow(clippy::cast_possible_truncation)]
+    let mut d = from_words([(product >> 64) as u64, product as u64]);
+    if variant == Variant::V2 {
+      scratchpad[i ^ 1] = xor(scratchpad[i ^ 1], d);
+      d = xor(d, scratchpad[i ^ 2]);
+    }
+    shuffle_add(variant, &mut scratchpad, i, &mut c1, a, b);
+
+    a1 = add(a1, d);
+    let c = from_words(c);
+    let mut stored = a1;
+    a1 = xor(a1, c);
+    if variant.v1() {
+      let stored_words = words(stored);
+      stored = from_words([stored_words[0], stored_words[1] ^ tweak1_2]);
+    }
+    scratchpad[i] = stored;
+
+    if variant.v2_or_later() {
+      b[1] = b[0];
+    }
+    b[0] = c1;
+    a = a1;
+  }
+
+  // Fold the scratchpad back into the state
+  {
+    let keys = aes::expand_key(state[32 .. 64].try_into().unwrap());
+    let mut text: [[u8; 16]; 8] = core::array::from_fn(|i| block(&state, 64 + (i * 16)));
+    for chunk in scratchpad.chunks_exact(8) {
+      for (block, scratch) in text.iter_mut().zip(chunk) {
+        *block = xor(*block, *scratch);
+        aes::pseudo_round(block, &keys);
+      }
+    }
+    for (i, block) in text.iter().enumerate() {
+      state[(64 + (i * 16)) .. (64 + ((i + 1) * 16))].copy_from_slice(block);
+    }
+  }
+
+  keccak_permutation(&mut state);
+  Ok(final_hash(&state))
+}
+
+/// Calculate the proof-of-work hash for a block using CryptoNight.
+///
+/// This will error if the block's hard fork uses RandomX.
+pub fn block_pow_hash(block: &Block) -> Result<[u8; 32], CryptoNightError> {
+  let height = block.number();
+  if height == 202612 {
+    return Ok(POW_HASH_202612);
+  }
+
+  let hardfork_version = block.header.hardfork_version;
+  let variant = Variant::from_hardfork_version(
+    hardfork_version,
+    u64::try_from(height).expect("block number exceeded 2**64"),
+  )
+  .ok_or(CryptoNightError::UnsupportedHardFork(hardfork_version))?;
+  cryptonight(variant, &block.serialize_pow_hash())
+}
+
+/// Check a block's proof of work, as calculated with CryptoNight, meets the specified difficulty.
+///
+/// This will error if the block's hard fork uses RandomX.
+pub fn verify_block_pow(block: &Block, difficulty: u128) -> Result<bool, CryptoNightError> {
+  Ok(check_hash(&block_pow_hash(block)?, difficulty))
+}
+
+/// Derive the key used to encrypt a wallet2 `.keys` file from its password.
+///
+/// This iterates CryptoNight (v0) over the password for the specified amount of rounds, with
+/// wallet2 defaulting to a single round.
+pub fn keys_file_kdf(password: &[u8], kdf_rounds: u64) -> Zeroizing<[u8; 32]> {
+  let mut key =
+    Zeroizing::new(cryptonight(Variant::V0, password).expect("v0 accepted any amount of data"));
+  for _ in 1 .. kdf_rounds {
+    *key = cryptonight(Variant::V0, key.as_ref()).expect("v0 accepted any amount of data");
+  }
+  key
+}
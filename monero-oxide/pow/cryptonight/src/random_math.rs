@@ -0,0 +1,296 @@
+// CN/R（CryptonightR）のランダム数学プログラムの生成と実行
+
+use std_shims::vec::Vec;
+
+use crate::hash::blake256;
+
+// The minimal latency of the generated code, equivalent to 15 multiplications
+const TOTAL_LATENCY: usize = 15 * 3;
+const NUM_INSTRUCTIONS_MIN: usize = 60;
+const NUM_INSTRUCTIONS_MAX: usize = 70;
+// The amount of ALUs available for multiplication and in total
+const ALU_COUNT_MUL: usize = 1;
+const ALU_COUNT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Opcode {
+  Mul,
+  Add,
+  Sub,
+  Ror,
+  Rol,
+  Xor,
+}
+
+impl Opcode {
+  fn latency(self) -> usize {
+    match self {
+      Opcode::Mul => 3,
+      Opcode::Add | Opcode::Ror | Opcode::Rol => 2,
+      Opcode::Sub | Opcode::Xor => 1,
+    }
+  }
+
+  fn asic_latency(self) -> usize {
+    match self {
+      Opcode::Mul => 3,
+      Opcode::Add | Opcode::Sub | Opcode::Ror | Opcode::Rol | Opcode::Xor => 1,
+    }
+  }
+
+  fn alus(self) -> usize {
+    if self == Opcode::Mul {
+      ALU_COUNT_MUL
+    } else {
+      ALU_COUNT
+    }
+  }
+
+  fn is_rotation(self) -> bool {
+    matches!(self, Opcode::Ror | Opcode::Rol)
+  }
+
+  fn index(self) -> u32 {
+    match self {
+      Opcode::Mul => 0,
+      Opcode::Add => 1,
+      Opcode::Sub => 2,
+      Opcode::Ror => 3,
+      Opcode::Rol => 4,
+      Opcode::Xor => 5,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Instruction {
+  opcode: Opcode,
+  dst: usize,
+  src: usize,
+  constant: u32,
+}
+
+// A deterministic stream of bytes, derived from the block height by iterated BLAKE-256
+struct Data {
+  data: [u8; 32],
+  index: usize,
+}
+
+impl Data {
+  fn new(height: u64) -> Self {
+    let mut data = [0; 32];
+    data[.. 8].copy_from_slice(&height.to_le_bytes());
+    data[20] = 0xda;
+    // Start past the end so the data is hashed before it's used
+    Data { data, index: 32 }
+  }
+
+  fn ensure(&mut self, bytes: usize) {
+    if (self.index + bytes) > self.data.len() {
+      self.data = blake256(&self.data);
+      self.index = 0;
+    }
+  }
+
+  fn byte(&mut self) -> u8 {
+    self.ensure(1);
+    let res = self.data[self.index];
+    self.index += 1;
+    res
+  }
+
+  fn u32(&mut self) -> u32 {
+    self.ensure(4);
+    let res = u32::from_le_bytes(self.data[self.index .. (self.index + 4)].try_into().unwrap());
+    self.index += 4;
+    res
+  }
+}
+
+/// A randomly generated program, as executed within every iteration of CN/R's main loop.
+#[derive(Clone, Debug)]
+pub(crate) struct Program(Vec<Instruction>);
+
+impl Program {
+  /// Generate the program for a block height.
+  pub(crate) fn new(height: u64) -> Program {
+    let mut data = Data::new(height);
+
+    loop {
+      let mut code = Vec::with_capacity(NUM_INSTRUCTIONS_MAX);
+      let mut latency = [0; 9];
+      let mut asic_latency = [0; 9];
+
+      // The current value, opcode, and source value of each register, used to reject code which
+      // could be trivially optimized
+      // Registers R4-R8 are constant and treated as having the same value
+      let mut inst_data: [u32; 9] = [0, 1, 2, 3, 0xffffff, 0xffffff, 0xffffff, 0xffffff, 0xffffff];
+
+      let mut alu_busy = [[false; ALU_COUNT]; TOTAL_LATENCY + 1];
+      let mut rotated = [false; 4];
+      let mut rotate_count = 0;
+
+      let mut num_retries = 0;
+      let mut total_iterations = 0;
+      let mut r8_used = false;
+
+      // Generate code achieving the minimal latency for all four variable registers
+      while latency[.. 4].iter().any(|latency| *latency < TOTAL_LATENCY) && (num_retries < 64) {
+        // Fail-safe to guarantee termination
+        total_iterations += 1;
+        if total_iterations > 256 {
+          break;
+        }
+
+        let c = data.byte();
+        let opcode = match c & 0b111 {
+          0 ..= 2 => Opcode::Mul,
+          3 => Opcode::Add,
+          4 => Opcode::Sub,
+          5 => {
+            if data.byte() < 0x80 {
+              Opcode::Ror
+            } else {
+              Opcode::Rol
+            }
+          }
+          _ => Opcode::Xor,
+        };
+
+        let dst = usize::from((c >> 3) & 0b11);
+        let mut src = usize::from(c >> 5);
+
+        // Don't perform ADD/SUB/XOR with the same register
+        if matches!(opcode, Opcode::Add | Opcode::Sub | Opcode::Xor) && (dst == src) {
+          src = 8;
+        }
+
+        // Don't rotate the same destination twice, as that's equivalent to a single rotation
+        if opcode.is_rotation() && rotated[dst] {
+          continue;
+        }
+
+        // Don't perform the same instruction (except MUL) with the same source value twice
+        if (opcode != Opcode::Mul) &&
+          ((inst_data[dst] & 0xffff00) ==
+            ((opcode.index() << 8) + ((inst_data[src] & 255) << 16)))
+        {
+          continue;
+        }
+
+        // Find which ALU is available (and when) for this instruction
+        let mut next_latency = latency[dst].max(latency[src]);
+        let mut alu = None;
+        while next_latency < TOTAL_LATENCY {
+          for i in (0 .. opcode.alus()).rev() {
+            if alu_busy[next_latency][i] {
+              continue;
+            }
+            // ADD is implemented as two 1-cycle instructions, so check the next cycle as well
+            if (opcode == Opcode::Add) && alu_busy[next_latency + 1][i] {
+              continue;
+            }
+            // A rotation can only start when the previous rotation has finished
+            if opcode.is_rotation() && (next_latency < (rotate_count * opcode.latency())) {
+              continue;
+            }
+            alu = Some(i);
+            break;
+          }
+          if alu.is_some() {
+            break;
+          }
+          next_latency += 1;
+        }
+
+        // Don't generate instructions which leave a register unchanged for more than 7 cycles
+        if next_latency > (latency[dst] + 7) {
+          continue;
+        }
+
+        next_latency += opcode.latency();
+
+        if next_latency <= TOTAL_LATENCY {
+          let alu = alu.expect("instruction was scheduled without an ALU");
+          if opcode.is_rotation() {
+            rotate_count += 1;
+          }
+
+          // ALUs are fully pipelined, so they're only marked busy for the cycle they start in
+          alu_busy[next_latency - opcode.latency()][alu] = true;
+          latency[dst] = next_latency;
+
+          asic_latency[dst] = asic_latency[dst].max(asic_latency[src]) + opcode.asic_latency();
+
+          rotated[dst] = opcode.is_rotation();
+
+          inst_data[dst] = u32::try_from(code.len()).unwrap() +
+            (opcode.index() << 8) +
+            ((inst_data[src] & 255) << 16);
+
+          if src == 8 {
+            r8_used = true;
+          }
+
+          let mut constant = 0;
+          if opcode == Opcode::Add {
+            // ADD is two 1-cycle instructions, so the ALU is also busy for the following cycle
+            alu_busy[next_latency - opcode.latency() + 1][alu] = true;
+            constant = data.u32();
+          }
+
+          code.push(Instruction { opcode, dst, src, constant });
+          if code.len() >= NUM_INSTRUCTIONS_MIN {
+            break;
+          }
+        } else {
+          num_retries += 1;
+        }
+      }
+
+      // Add MUL and ROR instructions until at least one register achieves the minimal latency for
+      // an ASIC, which is presumed to be able to extract as much parallelism as possible
+      let prev_code_len = code.len();
+      while (code.len() < NUM_INSTRUCTIONS_MAX) &&
+        asic_latency[.. 4].iter().all(|latency| *latency < TOTAL_LATENCY)
+      {
+        let mut min = 0;
+        let mut max = 0;
+        for i in 1 .. 4 {
+          if asic_latency[i] < asic_latency[min] {
+            min = i;
+          }
+          if asic_latency[i] > asic_latency[max] {
+            max = i;
+          }
+        }
+
+        let opcode = [Opcode::Ror, Opcode::Mul, Opcode::Mul][(code.len() - prev_code_len) % 3];
+        latency[min] = latency[max] + opcode.latency();
+        asic_latency[min] = asic_latency[max] + opcode.asic_latency();
+
+        code.push(Instruction { opcode, dst: min, src: max, constant: 0 });
+      }
+
+      if r8_used && (NUM_INSTRUCTIONS_MIN ..= NUM_INSTRUCTIONS_MAX).contains(&code.len()) {
+        return Program(code);
+      }
+    }
+  }
+
+  /// Execute the program over the registers.
+  pub(crate) fn execute(&self, registers: &mut [u32; 9]) {
+    for instruction in &self.0 {
+      let src = registers[instruction.src];
+      let dst = &mut registers[instruction.dst];
+      match instruction.opcode {
+        Opcode::Mul => *dst = dst.wrapping_mul(src),
+        Opcode::Add => *dst = dst.wrapping_add(src).wrapping_add(instruction.constant),
+        Opcode::Sub => *dst = dst.wrapping_sub(src),
+        Opcode::Ror => *dst = dst.rotate_right(src % 32),
+        Opcode::Rol => *dst = dst.rotate_left(src % 32),
+        Opcode::Xor => *dst ^= src,
+      }
+    }
+  }
+}
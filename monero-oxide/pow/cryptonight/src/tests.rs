@@ -0,0 +1,259 @@
+use hex_literal::hex;
+
+use monero_oxide::{
+  transaction::{Input, Timelock, TransactionPrefix, Transaction},
+  block::{BlockHeader, Block},
+};
+
+use crate::{*, hash::*};
+
+#[test]
+fn hashes() {
+  assert_eq!(
+    blake256(&[]),
+    hex!("716f6e863f744b9ac22c97ec7b76ea5f5908bc5b2f67c61510bfc4751384ea7a")
+  );
+  assert_eq!(
+    blake256(&[0]),
+    hex!("0ce8d4ef4dd7cd8d62dfded9d4edb0a774ae6a41929a74da23109e8f11139c87")
+  );
+  assert_eq!(
+    blake256(&[0; 72]),
+    hex!("d419bad32d504fb7d44d460c42c5593fe544fa4c135dec31e21bd9abdcc22d41")
+  );
+
+  assert_eq!(
+    groestl256(&[]),
+    hex!("1a52d11d550039be16107f9c58db9ebcc417f16f736adb2502567119f0083467")
+  );
+  assert_eq!(
+    groestl256(b"The quick brown fox jumps over the lazy dog"),
+    hex!("8c7ad62eb26a21297bc39c2d7293b4bd4d3399fa8afab29e970471739e28b301")
+  );
+
+  assert_eq!(jh256(&[]), hex!("46e64619c18bb0a92a5e87185a47eef83ca747b8fcc8e1412921357e326df434"));
+
+  assert_eq!(
+    skein512_256(&[]),
+    hex!("39ccc4554a8b31853b9de7a1fe638a24cce6b35a55f2431009e18780335d2621")
+  );
+}
+
+#[test]
+fn test_v2_sqrt() {
+  fn reference(n: u64) -> u64 {
+    // floor(sqrt(4 * (2**64 + n))), via a binary search
+    let square = 4 * ((1u128 << 64) + u128::from(n));
+    let (mut low, mut high) = (0u128, 1u128 << 34);
+    while (low + 1) < high {
+      let mid = (low + high) / 2;
+      if (mid * mid) <= square {
+        low = mid;
+      } else {
+        high = mid;
+      }
+    }
+    u64::try_from(low - (1 << 33)).unwrap()
+  }
+
+  let mut n = 0x0123456789abcdefu64;
+  for i in 0 .. 10_000 {
+    assert_eq!(v2_sqrt(i), reference(i));
+    assert_eq!(v2_sqrt(u64::MAX - i), reference(u64::MAX - i));
+    n = n.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    assert_eq!(v2_sqrt(n), reference(n));
+  }
+}
+
+// Vectors from Monero's `tests/hash`
+#[test]
+fn v0() {
+  for (data, hash) in [
+    (
+      &b"This is a test"[..],
+      hex!("a084f01d1437a09c6985401b60d43554ae105802c5f5d8a9b3253649c0be6605"),
+    ),
+    (
+      b"de omnibus dubitandum",
+      hex!("2f8e3df40bd11f9ac90c743ca8e32bb391da4fb98612aa3b6cdc639ee00b31f5"),
+    ),
+    (
+      b"abundans cautela non nocet",
+      hex!("722fa8ccd594d40e4a41f3822734304c8d5eff7e1b528408e2229da38ba553c4"),
+    ),
+    (b"caveat emptor", hex!("bbec2cacf69866a8e740380fe7b818fc78f8571221742d729d9d02d7f8989b87")),
+    (
+      b"ex nihilo nihil fit",
+      hex!("b1257de4efc5ce28c6b40ceb1c6c8f812a64634eb3e81c5220bee9b2b76a6f05"),
+    ),
+  ] {
+    assert_eq!(cryptonight(Variant::V0, data).unwrap(), hash);
+  }
+}
+
+#[test]
+fn v1() {
+  for (data, hash) in [
+    (&[0; 43][..], hex!("b5a7f63abb94d07d1a6445c36c07c7e8327fe61b1647e391b4c7edae5de57a3d")),
+    (
+      &hex!(
+        "8519e039172b0d70e5ca7b3383d6b3167315a422747b73f019cf9528f0fde341"
+        "fd0f2a63030ba6450525cf6de31837669af6f1df8131faf50aaab8d3a7405589"
+      ),
+      hex!("5bb40c5880cef2f739bdb6aaaf16161eaae55530e7b10d7ea996b751a299e949"),
+    ),
+    (
+      &hex!(
+        "37a636d7dafdf259b7287eddca2f58099e98619d2f99bdb8969d7b14498102cc"
+        "065201c8be90bd777323f449848b215d2977c92c4c1c2da36ab46b2e389689ed"
+        "97c18fec08cd3b03235c5e4c62a37ad88c7b67932495a71090e85dd4020a9300"
+      ),
+      hex!("613e638505ba1fd05f428d5c9f8e08f8165614342dac419adc6a47dce257eb3e"),
+    ),
+    (
+      &hex!(
+        "38274c97c45a172cfc97679870422e3a1ab0784960c60514d816271415c306ee"
+        "3a3ed1a77e31f6a885c3cb"
+      ),
+      hex!("ed082e49dbd5bbe34a3726a0d1dad981146062b39d36d62c71eb1ed8ab49459b"),
+    ),
+  ] {
+    assert_eq!(cryptonight(Variant::V1, data).unwrap(), hash);
+  }
+
+  assert_eq!(cryptonight(Variant::V1, &[0; 42]), Err(CryptoNightError::InsufficientData));
+}
+
+const LOREM_IPSUM: [&[u8]; 10] = [
+  b"This is a test This is a test This is a test",
+  b"Lorem ipsum dolor sit amet, consectetur adipiscing",
+  b"elit, sed do eiusmod tempor incididunt ut labore",
+  b"et dolore magna aliqua. Ut enim ad minim veniam,",
+  b"quis nostrud exercitation ullamco laboris nisi",
+  b"ut aliquip ex ea commodo consequat. Duis aute",
+  b"irure dolor in reprehenderit in voluptate velit",
+  b"esse cillum dolore eu fugiat nulla pariatur.",
+  b"Excepteur sint occaecat cupidatat non proident,",
+  b"sunt in culpa qui officia deserunt mollit anim id est laborum.",
+];
+
+#[test]
+fn v2() {
+  for (data, hash) in LOREM_IPSUM.into_iter().zip([
+    hex!("353fdc068fd47b03c04b9431e005e00b68c2168a3cc7335c8b9b308156591a4f"),
+    hex!("72f134fc50880c330fe65a2cb7896d59b2e708a0221c6a9da3f69b3a702d8682"),
+    hex!("410919660ec540fc49d8695ff01f974226a2a28dbbac82949c12f541b9a62d2f"),
+    hex!("4472fecfeb371e8b7942ce0378c0ba5e6d0c6361b669c587807365c787ae652d"),
+    hex!("577568395203f1f1225f2982b637f7d5e61b47a0f546ba16d46020b471b74076"),
+    hex!("f6fd7efe95a5c6c4bb46d9b429e3faf65b1ce439e116742d42b928e61de52385"),
+    hex!("422f8cfe8060cf6c3d9fd66f68e3c9977adb683aea2788029308bbe9bc50d728"),
+    hex!("512e62c8c8c833cfbd9d361442cb00d63c0a3fd8964cfd2fedc17c7c25ec2d4b"),
+    hex!("12a794c1aa13d561c9c6111cee631ca9d0a321718d67d3416add9de1693ba41e"),
+    hex!("2659ff95fc74b6215c1dc741e85b7a9710101b30620212f80eb59c3c55993f9d"),
+  ]) {
+    assert_eq!(cryptonight(Variant::V2, data).unwrap(), hash);
+  }
+}
+
+#[test]
+fn r() {
+  for ((data, hash), height) in LOREM_IPSUM
+    .into_iter()
+    .zip([
+      hex!("f759588ad57e758467295443a9bd71490abff8e9dad1b95b6bf2f5d0d78387bc"),
+      hex!("5bb833deca2bdd7252a9ccd7b4ce0b6a4854515794b56c207262f7a5b9bdb566"),
+      hex!("1ee6728da60fbd8d7d55b2b1ade487a3cf52a2c3ac6f520db12c27d8921f6cab"),
+      hex!("6969fe2ddfb758438d48049f302fc2108a4fcc93e37669170e6db4b0b9b4c4cb"),
+      hex!("7f3048b4e90d0cbe7a57c0394f37338a01fae3adfdc0e5126d863a895eb04e02"),
+      hex!("1d290443a4b542af04a82f6b2494a6ee7f20f2754c58e0849032483a56e8e2ef"),
+      hex!("c43cc6567436a86afbd6aa9eaa7c276e9806830334b614b2bee23cc76634f6fd"),
+      hex!("87be2479c0c4e8edfdfaa5603e93f4265b3f8224c1c5946feb424819d18990a4"),
+      hex!("dd9d6a6d8e47465cceac0877ef889b93e7eba979557e3935d7f86dce11b070f3"),
+      hex!("75c6f2ae49a20521de97285b431e717125847fb8935ed84a61e7f8d36a2c3d8e"),
+    ])
+    .zip(1806260 ..)
+  {
+    assert_eq!(cryptonight(Variant::R { height }, data).unwrap(), hash);
+  }
+}
+
+#[test]
+fn variants() {
+  assert_eq!(Variant::from_hardfork_version(1, 0), Some(Variant::V0));
+  assert_eq!(Variant::from_hardfork_version(6, 0), Some(Variant::V0));
+  assert_eq!(Variant::from_hardfork_version(7, 0), Some(Variant::V1));
+  assert_eq!(Variant::from_hardfork_version(8, 0), Some(Variant::V2));
+  assert_eq!(Variant::from_hardfork_version(9, 0), Some(Variant::V2));
+  assert_eq!(Variant::from_hardfork_version(10, 1), Some(Variant::R { height: 1 }));
+  assert_eq!(Variant::from_hardfork_version(11, 2), Some(Variant::R { height: 2 }));
+  assert_eq!(Variant::from_hardfork_version(12, 0), None);
+}
+
+fn test_block(hardfork_version: u8, number: usize) -> Block {
+  Block::new(
+    BlockHeader {
+      hardfork_version,
+      hardfork_signal: hardfork_version,
+      timestamp: 0,
+      previous: [0; 32],
+      nonce: 0,
+    },
+    Transaction::V1 {
+      prefix: TransactionPrefix {
+        additional_timelock: Timelock::Block(number + 60),
+        inputs: vec![Input::Gen(number)],
+        outputs: vec![],
+        extra: vec![],
+      },
+      signatures: vec![],
+    },
+    vec![],
+  )
+  .unwrap()
+}
+
+#[test]
+fn block_pow() {
+  let block = test_block(1, 1);
+  assert_eq!(
+    block_pow_hash(&block).unwrap(),
+    cryptonight(Variant::V0, &block.serialize_pow_hash()).unwrap()
+  );
+
+  assert_eq!(block_pow_hash(&test_block(1, 202612)).unwrap(), POW_HASH_202612);
+
+  assert_eq!(block_pow_hash(&test_block(16, 1)), Err(CryptoNightError::UnsupportedHardFork(16)));
+  assert_eq!(
+    verify_block_pow(&test_block(16, 1), 1),
+    Err(CryptoNightError::UnsupportedHardFork(16))
+  );
+}
+
+#[test]
+fn difficulty() {
+  assert!(check_hash(&[0xff; 32], 1));
+  assert!(!check_hash(&[0xff; 32], 2));
+
+  // 2**255 may be multiplied by 1, yet not 2
+  let mut hash = [0; 32];
+  hash[31] = 0x80;
+  assert!(check_hash(&hash, 1));
+  assert!(!check_hash(&hash, 2));
+
+  // 2**128 may be multiplied by up to 2**128 - 1, yet 2**129 may not be
+  let mut hash = [0; 32];
+  hash[16] = 1;
+  assert!(check_hash(&hash, u128::MAX));
+  hash[16] = 2;
+  assert!(!check_hash(&hash, u128::MAX));
+  assert!(check_hash(&hash, u128::MAX >> 1));
+
+  assert!(check_hash(&[0; 32], u128::MAX));
+}
+
+#[test]
+fn kdf() {
+  let once = cryptonight(Variant::V0, b"password").unwrap();
+  assert_eq!(*keys_file_kdf(b"password", 1), once);
+  assert_eq!(*keys_file_kdf(b"password", 2), cryptonight(Variant::V0, &once).unwrap());
+}
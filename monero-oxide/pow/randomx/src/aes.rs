@@ -0,0 +1,240 @@
+// AES のラウンド関数と、RandomX の AES ベースのジェネレータおよびハッシュ
+
+use hex_literal::hex;
+
+/// Multiply an element of GF(2**8) by two.
+const fn xtime(a: u8) -> u8 {
+  (a << 1) ^ (if (a & 0x80) == 0 { 0 } else { 0x1b })
+}
+
+/// Multiply two elements of GF(2**8), as defined by AES (modulo x**8 + x**4 + x**3 + x + 1).
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+  let mut res = 0;
+  while b != 0 {
+    if (b & 1) == 1 {
+      res ^= a;
+    }
+    a = xtime(a);
+    b >>= 1;
+  }
+  res
+}
+
+const fn sbox() -> [u8; 256] {
+  let mut res = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    #[allow(clippy::cast_possible_truncation)]
+    let x = i as u8;
+    // The multiplicative inverse, calculated as x**254, with 0 mapping to 0
+    let mut inverse = 1;
+    let mut j = 0;
+    while j < 254 {
+      inverse = gf_mul(inverse, x);
+      j += 1;
+    }
+    res[i] = inverse ^
+      inverse.rotate_left(1) ^
+      inverse.rotate_left(2) ^
+      inverse.rotate_left(3) ^
+      inverse.rotate_left(4) ^
+      0x63;
+    i += 1;
+  }
+  res
+}
+
+const SBOX: [u8; 256] = sbox();
+
+const fn inverse_sbox() -> [u8; 256] {
+  let mut res = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    #[allow(clippy::cast_possible_truncation)]
+    {
+      res[SBOX[i] as usize] = i as u8;
+    }
+    i += 1;
+  }
+  res
+}
+
+const INVERSE_SBOX: [u8; 256] = inverse_sbox();
+
+// A table of multiplications by a constant, used by InvMixColumns
+const fn mul_table(by: u8) -> [u8; 256] {
+  let mut res = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    #[allow(clippy::cast_possible_truncation)]
+    {
+      res[i] = gf_mul(i as u8, by);
+    }
+    i += 1;
+  }
+  res
+}
+
+const MUL_9: [u8; 256] = mul_table(9);
+const MUL_11: [u8; 256] = mul_table(11);
+const MUL_13: [u8; 256] = mul_table(13);
+const MUL_14: [u8; 256] = mul_table(14);
+
+/// A single AES encryption round (SubBytes, ShiftRows, MixColumns, AddRoundKey).
+///
+/// This is equivalent to the `AESENC` instruction.
+fn enc(block: &mut [u8; 16], key: &[u8; 16]) {
+  // SubBytes and ShiftRows, with the state stored column-major
+  let mut shifted = [0; 16];
+  for column in 0 .. 4 {
+    for row in 0 .. 4 {
+      shifted[(column * 4) + row] = SBOX[usize::from(block[(((column + row) % 4) * 4) + row])];
+    }
+  }
+
+  // MixColumns and AddRoundKey
+  for column in 0 .. 4 {
+    let a = &shifted[(column * 4) .. ((column + 1) * 4)];
+    let doubled = [xtime(a[0]), xtime(a[1]), xtime(a[2]), xtime(a[3])];
+    for row in 0 .. 4 {
+      // 2 * a[row] + 3 * a[row + 1] + a[row + 2] + a[row + 3]
+      block[(column * 4) + row] = doubled[row] ^
+        doubled[(row + 1) % 4] ^
+        a[(row + 1) % 4] ^
+        a[(row + 2) % 4] ^
+        a[(row + 3) % 4] ^
+        key[(column * 4) + row];
+    }
+  }
+}
+
+/// A single AES decryption round (InvShiftRows, InvSubBytes, InvMixColumns, AddRoundKey).
+///
+/// This is equivalent to the `AESDEC` instruction.
+fn dec(block: &mut [u8; 16], key: &[u8; 16]) {
+  // InvShiftRows and InvSubBytes
+  let mut shifted = [0; 16];
+  for column in 0 .. 4 {
+    for row in 0 .. 4 {
+      shifted[(column * 4) + row] =
+        INVERSE_SBOX[usize::from(block[(((column + 4 - row) % 4) * 4) + row])];
+    }
+  }
+
+  // InvMixColumns and AddRoundKey
+  for column in 0 .. 4 {
+    let a = &shifted[(column * 4) .. ((column + 1) * 4)];
+    for row in 0 .. 4 {
+      // 14 * a[row] + 11 * a[row + 1] + 13 * a[row + 2] + 9 * a[row + 3]
+      block[(column * 4) + row] = MUL_14[usize::from(a[row])] ^
+        MUL_11[usize::from(a[(row + 1) % 4])] ^
+        MUL_13[usize::from(a[(row + 2) % 4])] ^
+        MUL_9[usize::from(a[(row + 3) % 4])] ^
+        key[(column * 4) + row];
+    }
+  }
+}
+
+/// The state of the AES-based generators and hash, as four columns of 16 bytes each.
+pub(crate) type State = [[u8; 16]; 4];
+
+pub(crate) fn state(bytes: &[u8; 64]) -> State {
+  core::array::from_fn(|i| bytes[(i * 16) .. ((i + 1) * 16)].try_into().unwrap())
+}
+
+pub(crate) fn state_bytes(state: &State) -> [u8; 64] {
+  let mut res = [0; 64];
+  for (chunk, column) in res.chunks_exact_mut(16).zip(state) {
+    chunk.copy_from_slice(column);
+  }
+  res
+}
+
+// Blake2b-512("RandomX AesGenerator1R keys")
+pub(crate) const GENERATOR_1R_KEYS: [[u8; 16]; 4] = [
+  hex!("53a5ac6d096671622b55b5db1749f4b4"),
+  hex!("07af7c6d0d716a8478d325174edca10d"),
+  hex!("f162123fc67e949f4f79c0f445e3203e"),
+  hex!("3581ef6a7c31bab1884c311654911649"),
+];
+
+// Blake2b-512("RandomX AesGenerator4R keys 0-3") || Blake2b-512("RandomX AesGenerator4R keys 4-7")
+pub(crate) const GENERATOR_4R_KEYS: [[u8; 16]; 8] = [
+  hex!("ddaa2164db3d83d12b6d542f3fd2e599"),
+  hex!("50340eb2553f91b6539df706e5cddfa5"),
+  hex!("04d93e5caf7b5e519f67a40abf021c17"),
+  hex!("63376285085d8fe7853767cd91d2ded8"),
+  hex!("736f82b5a6a7d6e36d8b513db4ff9e22"),
+  hex!("f36b56c7d9b3109c4e4d02e9d2b772b2"),
+  hex!("e7c973f28ba365f70a66a92ba7ef3bf6"),
+  hex!("09d67c7ade395891fdd1060c2d76b0c0"),
+];
+
+// Blake2b-512("RandomX AesHash1R state")
+pub(crate) const HASH_1R_STATE: [[u8; 16]; 4] = [
+  hex!("0d2cb592de56a89f47db82ccad3a98d7"),
+  hex!("6e998d3398b7c7155a129ef55780e7ac"),
+  hex!("1700776ad0c762ae6b507950e47ca0e8"),
+  hex!("0c240a638d82ad070500a1794849997e"),
+];
+
+// Blake2b-256("RandomX AesHash1R xkeys")
+pub(crate) const HASH_1R_XKEYS: [[u8; 16]; 2] =
+  [hex!("8983faf69f94248bbf56dc9001028906"), hex!("d163b2613ce0f451c64310ee9bf918ed")];
+
+/// AesGenerator1R, filling the output with pseudo-random data.
+///
+/// The state is updated in-place, as the final state is used to seed the first program.
+pub(crate) fn generator_1r(state: &mut State, out: &mut [u64]) {
+  let keys = &GENERATOR_1R_KEYS;
+  for chunk in out.chunks_exact_mut(8) {
+    dec(&mut state[0], &keys[0]);
+    enc(&mut state[1], &keys[1]);
+    dec(&mut state[2], &keys[2]);
+    enc(&mut state[3], &keys[3]);
+
+    for (words, column) in chunk.chunks_exact_mut(2).zip(state.iter()) {
+      words[0] = u64::from_le_bytes(column[.. 8].try_into().unwrap());
+      words[1] = u64::from_le_bytes(column[8 ..].try_into().unwrap());
+    }
+  }
+}
+
+/// AesGenerator4R, filling the output with pseudo-random data.
+pub(crate) fn generator_4r(mut state: State, out: &mut [u8]) {
+  let keys = &GENERATOR_4R_KEYS;
+  for chunk in out.chunks_exact_mut(64) {
+    for i in 0 .. 4 {
+      dec(&mut state[0], &keys[i]);
+      enc(&mut state[1], &keys[i]);
+      dec(&mut state[2], &keys[i + 4]);
+      enc(&mut state[3], &keys[i + 4]);
+    }
+    chunk.copy_from_slice(&state_bytes(&state));
+  }
+}
+
+/// AesHash1R, hashing the (64-byte aligned) input to 64 bytes.
+pub(crate) fn hash_1r(input: &[u64]) -> State {
+  let mut state = HASH_1R_STATE;
+  for chunk in input.chunks_exact(8) {
+    let mut columns = [[0; 16]; 4];
+    for (column, words) in columns.iter_mut().zip(chunk.chunks_exact(2)) {
+      column[.. 8].copy_from_slice(&words[0].to_le_bytes());
+      column[8 ..].copy_from_slice(&words[1].to_le_bytes());
+    }
+    enc(&mut state[0], &columns[0]);
+    dec(&mut state[1], &columns[1]);
+    enc(&mut state[2], &columns[2]);
+    dec(&mut state[3], &columns[3]);
+  }
+
+  // Two extra rounds to achieve full diffusion
+  for key in &HASH_1R_XKEYS {
+    enc(&mut state[0], key);
+    dec(&mut state[1], key);
+    enc(&mut state[2], key);
+    dec(&mut state[3], key);
+  }
+  state
+}
@@ -0,0 +1,135 @@
+// 丸めモードを指定した IEEE 754 倍精度演算
+//
+// RandomX は 4 つの丸めモードすべてで加減乗除と平方根を用いる。Rust からは浮動小数点環境を
+// 変更できないため、最近接丸めの結果と誤差項（の符号）から指定された丸めの結果を導出する。
+
+use core::cmp::Ordering;
+
+/// An IEEE 754 rounding mode, as selected by `CFROUND`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum RoundingMode {
+  Nearest,
+  Down,
+  Up,
+  TowardZero,
+}
+
+impl RoundingMode {
+  /// The rounding mode with the specified `MXCSR.RC` value.
+  pub(crate) fn from_bits(bits: u64) -> RoundingMode {
+    match bits % 4 {
+      0 => RoundingMode::Nearest,
+      1 => RoundingMode::Down,
+      2 => RoundingMode::Up,
+      _ => RoundingMode::TowardZero,
+    }
+  }
+}
+
+const SIGN: u64 = 1 << 63;
+
+fn next_up(x: f64) -> f64 {
+  let bits = x.to_bits();
+  if (bits & !SIGN) == 0 {
+    // The smallest positive subnormal
+    f64::from_bits(1)
+  } else if (bits & SIGN) == 0 {
+    f64::from_bits(bits + 1)
+  } else {
+    f64::from_bits(bits - 1)
+  }
+}
+
+fn next_down(x: f64) -> f64 {
+  -next_up(-x)
+}
+
+fn is_zero(x: f64) -> bool {
+  (x.to_bits() & !SIGN) == 0
+}
+
+// Round the result of an operation on finite operands, performed with round-to-nearest, given how
+// the exact result compares to it
+fn round(result: f64, exact: Ordering, mode: RoundingMode) -> f64 {
+  // Rounding to nearest overflows to infinity, while directed rounding may saturate
+  if result.is_infinite() {
+    let saturate = match mode {
+      RoundingMode::Nearest => false,
+      RoundingMode::Down => result.is_sign_positive(),
+      RoundingMode::Up => result.is_sign_negative(),
+      RoundingMode::TowardZero => true,
+    };
+    return if !saturate {
+      result
+    } else if result.is_sign_positive() {
+      f64::MAX
+    } else {
+      f64::MIN
+    };
+  }
+
+  match (mode, exact) {
+    (RoundingMode::Down, Ordering::Less) => next_down(result),
+    (RoundingMode::Up, Ordering::Greater) => next_up(result),
+    (RoundingMode::TowardZero, Ordering::Less) if result.is_sign_positive() => next_down(result),
+    (RoundingMode::TowardZero, Ordering::Greater) if result.is_sign_negative() => next_up(result),
+    _ => result,
+  }
+}
+
+// The sign of an error term
+fn sign(error: f64) -> Ordering {
+  error.partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+}
+
+pub(crate) fn add(a: f64, b: f64, mode: RoundingMode) -> f64 {
+  let sum = a + b;
+  if (mode == RoundingMode::Nearest) || !(a.is_finite() && b.is_finite()) {
+    return sum;
+  }
+
+  // An exact sum of zero is positive, unless rounding down (or both operands were negative)
+  if is_zero(sum) {
+    if (mode == RoundingMode::Down) && (a.is_sign_negative() || b.is_sign_negative()) {
+      return -0.0;
+    }
+    return sum;
+  }
+
+  // TwoSum, calculating the exact error of the sum
+  let b_virtual = sum - a;
+  let a_virtual = sum - b_virtual;
+  let error = (a - a_virtual) + (b - b_virtual);
+  round(sum, sign(error), mode)
+}
+
+pub(crate) fn sub(a: f64, b: f64, mode: RoundingMode) -> f64 {
+  add(a, -b, mode)
+}
+
+pub(crate) fn mul(a: f64, b: f64, mode: RoundingMode) -> f64 {
+  let product = a * b;
+  if (mode == RoundingMode::Nearest) || !(a.is_finite() && b.is_finite()) {
+    return product;
+  }
+  round(product, sign(libm::fma(a, b, -product)), mode)
+}
+
+pub(crate) fn div(a: f64, b: f64, mode: RoundingMode) -> f64 {
+  let quotient = a / b;
+  if (mode == RoundingMode::Nearest) || !(a.is_finite() && b.is_finite()) {
+    return quotient;
+  }
+  // a - (quotient * b), which has the sign of the error when the divisor is positive
+  let remainder = libm::fma(-quotient, b, a);
+  let exact = if b.is_sign_negative() { sign(remainder).reverse() } else { sign(remainder) };
+  round(quotient, exact, mode)
+}
+
+pub(crate) fn sqrt(a: f64, mode: RoundingMode) -> f64 {
+  let root = libm::sqrt(a);
+  if (mode == RoundingMode::Nearest) || !a.is_finite() {
+    return root;
+  }
+  round(root, sign(libm::fma(-root, root, a)), mode)
+}
@@ -0,0 +1,192 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc = include_str!("../README.md")]
+#![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use std_shims::{vec, vec::Vec};
+
+use blake2::{
+  digest::{Digest, consts::U32},
+  Blake2b, Blake2b512,
+};
+
+use monero_oxide::{block::Block, difficulty::check_hash};
+
+mod aes;
+mod float;
+mod superscalar;
+use superscalar::{Blake2Generator, Program};
+mod vm;
+use vm::{PROGRAM_BYTES, SCRATCHPAD_L3, Vm};
+
+#[cfg(test)]
+mod tests;
+
+// The parameters for the Argon2d instance used to fill the cache
+const ARGON_MEMORY: u32 = 256 * 1024;
+const ARGON_ITERATIONS: u32 = 3;
+const ARGON_LANES: u32 = 1;
+const ARGON_SALT: &[u8] = b"RandomX\x03";
+
+// The amount of SuperscalarHash programs, and accordingly cache accesses, per dataset item
+const CACHE_ACCESSES: usize = 8;
+const CACHE_LINE_SIZE: usize = 64;
+// The amount of cache lines within the cache
+const CACHE_LINES: usize = (ARGON_MEMORY as usize * 1024) / CACHE_LINE_SIZE;
+// The amount of programs executed per hash
+const PROGRAM_COUNT: usize = 8;
+
+// The constants used to initialize the registers when calculating a dataset item
+const SUPERSCALAR_MUL_0: u64 = 6364136223846793005;
+const SUPERSCALAR_ADD: [u64; 7] = [
+  9298411001130361340,
+  12065312585734608966,
+  9306329213124626780,
+  5281919268842080866,
+  10536153434571861004,
+  3398623926847679864,
+  9549104520008361294,
+];
+
+// The seed block changes every 2048 blocks, with a lag of 64 blocks
+const SEEDHASH_EPOCH_BLOCKS: usize = 2048;
+const SEEDHASH_EPOCH_LAG: usize = 64;
+
+/// The first hard fork to use RandomX for its proof of work.
+pub const RANDOMX_HARDFORK_VERSION: u8 = 12;
+
+/// An error when calculating a RandomX hash.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum RandomXError {
+  /// The hard fork doesn't use RandomX for its proof of work.
+  #[error("hard fork {0} doesn't use RandomX")]
+  UnsupportedHardFork(u8),
+}
+
+/// The number of the block whose hash seeds RandomX for the block with the specified number.
+///
+/// The seed is the hash of this block, as provided to [`Cache::new`].
+pub fn seed_height(number: usize) -> usize {
+  if number <= (SEEDHASH_EPOCH_BLOCKS + SEEDHASH_EPOCH_LAG) {
+    0
+  } else {
+    (number - SEEDHASH_EPOCH_LAG - 1) & !(SEEDHASH_EPOCH_BLOCKS - 1)
+  }
+}
+
+/// A RandomX cache, which is sufficient to calculate hashes in light mode.
+///
+/// This requires 256 MiB of memory and takes a notable amount of time to create. It only needs to
+/// be created once per seed, which changes every 2048 blocks.
+pub struct Cache {
+  memory: Vec<argon2::Block>,
+  programs: [Program; CACHE_ACCESSES],
+}
+
+impl core::fmt::Debug for Cache {
+  fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    fmt.debug_struct("Cache").finish_non_exhaustive()
+  }
+}
+
+impl Cache {
+  /// Create the cache for the specified key.
+  ///
+  /// For Monero, the key is the hash of the block at the [`seed_height`].
+  pub fn new(key: &[u8]) -> Cache {
+    let argon2 = argon2::Argon2::new(
+      argon2::Algorithm::Argon2d,
+      argon2::Version::V0x13,
+      argon2::Params::new(ARGON_MEMORY, ARGON_ITERATIONS, ARGON_LANES, None)
+        .expect("RandomX's Argon2 parameters were invalid"),
+    );
+    let mut memory = vec![argon2::Block::new(); usize::try_from(ARGON_MEMORY).unwrap()];
+    argon2.fill_memory(key, ARGON_SALT, &mut memory).expect("couldn't fill the RandomX cache");
+
+    let mut generator = Blake2Generator::new(key);
+    let programs = core::array::from_fn(|_| Program::new(&mut generator));
+
+    Cache { memory, programs }
+  }
+
+  // A 64-byte line of the cache
+  fn line(&self, index: usize) -> &[u64] {
+    const LINES_PER_BLOCK: usize = argon2::Block::SIZE / CACHE_LINE_SIZE;
+    let words = CACHE_LINE_SIZE / 8;
+    let offset = (index % LINES_PER_BLOCK) * words;
+    &self.memory[index / LINES_PER_BLOCK].as_ref()[offset .. (offset + words)]
+  }
+
+  // Calculate an item of the dataset, as light mode does on-demand
+  fn dataset_item(&self, item: u64) -> [u64; 8] {
+    let first = (item + 1).wrapping_mul(SUPERSCALAR_MUL_0);
+    let mut registers = [first; 8];
+    for (register, add) in registers[1 ..].iter_mut().zip(SUPERSCALAR_ADD) {
+      *register ^= add;
+    }
+
+    let mut register_value = item;
+    for program in &self.programs {
+      let line = usize::try_from(register_value % u64::try_from(CACHE_LINES).unwrap()).unwrap();
+      program.execute(&mut registers);
+      for (register, word) in registers.iter_mut().zip(self.line(line)) {
+        *register ^= word;
+      }
+      register_value = registers[program.address_register()];
+    }
+    registers
+  }
+
+  /// Calculate the RandomX hash of the input.
+  pub fn hash(&self, input: &[u8]) -> [u8; 32] {
+    let mut seed = aes::state(&Blake2b512::digest(input).into());
+
+    let mut scratchpad = vec![0; SCRATCHPAD_L3 / 8];
+    aes::generator_1r(&mut seed, &mut scratchpad);
+
+    let mut vm = Vm::new(self, &mut scratchpad);
+    for i in 0 .. PROGRAM_COUNT {
+      let mut program = [0; PROGRAM_BYTES];
+      aes::generator_4r(seed, &mut program);
+      vm.run(&program);
+
+      // Seed the next program with the hash of the register file
+      if i != (PROGRAM_COUNT - 1) {
+        seed = aes::state(&Blake2b512::digest(vm.registers.serialize()).into());
+      }
+    }
+
+    let a = aes::hash_1r(vm.scratchpad);
+    for (a, column) in vm.registers.a.iter_mut().zip(a) {
+      *a = [
+        f64::from_bits(u64::from_le_bytes(column[.. 8].try_into().unwrap())),
+        f64::from_bits(u64::from_le_bytes(column[8 ..].try_into().unwrap())),
+      ];
+    }
+    Blake2b::<U32>::digest(vm.registers.serialize()).into()
+  }
+}
+
+/// Calculate the proof-of-work hash for a block using RandomX.
+///
+/// The cache MUST be for the hash of the block at the [`seed_height`] of this block. This will
+/// error if the block's hard fork doesn't use RandomX.
+pub fn block_pow_hash(cache: &Cache, block: &Block) -> Result<[u8; 32], RandomXError> {
+  let hardfork_version = block.header.hardfork_version;
+  if hardfork_version < RANDOMX_HARDFORK_VERSION {
+    Err(RandomXError::UnsupportedHardFork(hardfork_version))?;
+  }
+  Ok(cache.hash(&block.serialize_pow_hash()))
+}
+
+/// Check a block's proof of work, as calculated with RandomX, meets the specified difficulty.
+///
+/// The cache MUST be for the hash of the block at the [`seed_height`] of this block. This will
+/// error if the block's hard fork doesn't use RandomX.
+pub fn verify_block_pow(
+  cache: &Cache,
+  block: &Block,
+  difficulty: u128,
+) -> Result<bool, RandomXError> {
+  Ok(check_hash(&block_pow_hash(cache, block)?, difficulty))
+}
@@ -0,0 +1,689 @@
+// SuperscalarHash：キャッシュからデータセットの項目を導出するランダムプログラムの生成と実行
+
+use std_shims::vec::Vec;
+
+use blake2::{Digest, Blake2b512};
+
+// The target latency of a program, in cycles
+const LATENCY: usize = 170;
+const CYCLE_MAP_SIZE: usize = LATENCY + 4;
+const LOOK_FORWARD_CYCLES: usize = 4;
+const MAX_THROWAWAY_COUNT: usize = 256;
+// The maximum amount of instructions within a program
+const MAX_SIZE: usize = (3 * LATENCY) + 2;
+// The register which can't be the destination of `IADD_RS`, due to a limitation of x86's `lea`
+const REGISTER_NEEDS_DISPLACEMENT: usize = 5;
+
+/// A deterministic stream of bytes, derived from the cache's key by iterated Blake2b.
+pub(crate) struct Blake2Generator {
+  data: [u8; 64],
+  index: usize,
+}
+
+impl Blake2Generator {
+  pub(crate) fn new(seed: &[u8]) -> Self {
+    let mut data = [0; 64];
+    // The seed is truncated to 60 bytes, with the remaining four bytes being a (zero) nonce
+    let len = seed.len().min(60);
+    data[.. len].copy_from_slice(&seed[.. len]);
+    // Start past the end so the data is hashed before it's used
+    Blake2Generator { data, index: 64 }
+  }
+
+  fn ensure(&mut self, bytes: usize) {
+    if (self.index + bytes) > self.data.len() {
+      self.data = Blake2b512::digest(self.data).into();
+      self.index = 0;
+    }
+  }
+
+  fn byte(&mut self) -> u8 {
+    self.ensure(1);
+    let res = self.data[self.index];
+    self.index += 1;
+    res
+  }
+
+  fn u32(&mut self) -> u32 {
+    self.ensure(4);
+    let res = u32::from_le_bytes(self.data[self.index .. (self.index + 4)].try_into().unwrap());
+    self.index += 4;
+    res
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Opcode {
+  ISubR,
+  IXorR,
+  IAddRs,
+  IMulR,
+  IRorC,
+  IAddC7,
+  IXorC7,
+  IAddC8,
+  IXorC8,
+  IAddC9,
+  IXorC9,
+  IMulhR,
+  ISMulhR,
+  IMulRcp,
+}
+
+impl Opcode {
+  fn is_multiplication(self) -> bool {
+    matches!(self, Opcode::IMulR | Opcode::IMulhR | Opcode::ISMulhR | Opcode::IMulRcp)
+  }
+}
+
+// Execution ports, as a bitmask
+const P0: u8 = 1;
+const P1: u8 = 2;
+const P5: u8 = 4;
+const P01: u8 = P0 | P1;
+const P05: u8 = P0 | P5;
+const P015: u8 = P0 | P1 | P5;
+
+/// A macro-op, as output by an x86 decoder, consisting of up to two micro-ops.
+///
+/// Micro-ops are solely represented by the execution ports they may be scheduled to, with zero
+/// ports denoting a micro-op which is eliminated (a register-to-register move).
+#[derive(Clone, Copy, Debug)]
+struct MacroOp {
+  latency: usize,
+  uop1: u8,
+  uop2: u8,
+  // If this macro-op depends on the result of the prior macro-op
+  dependent: bool,
+}
+
+impl MacroOp {
+  const fn new(latency: usize, uop1: u8, uop2: u8) -> Self {
+    MacroOp { latency, uop1, uop2, dependent: false }
+  }
+}
+
+const ADD_RR: MacroOp = MacroOp::new(1, P015, 0);
+const LEA_SIB: MacroOp = MacroOp::new(1, P01, 0);
+const IMUL_RR: MacroOp = MacroOp::new(3, P1, 0);
+const ROR_RI: MacroOp = MacroOp::new(1, P05, 0);
+const ADD_RI: MacroOp = MacroOp::new(1, P015, 0);
+const MOV_RR: MacroOp = MacroOp::new(0, 0, 0);
+const MUL_R: MacroOp = MacroOp::new(4, P1, P5);
+const MOV_RI64: MacroOp = MacroOp::new(1, P015, 0);
+
+/// The macro-ops an instruction decodes to, and which macro-ops read and write its registers.
+struct InstructionInfo {
+  ops: &'static [MacroOp],
+  result_op: usize,
+  dst_op: usize,
+  src_op: Option<usize>,
+}
+
+impl Opcode {
+  fn info(self) -> &'static InstructionInfo {
+    const fn single(op: &'static [MacroOp], src_op: Option<usize>) -> InstructionInfo {
+      InstructionInfo { ops: op, result_op: 0, dst_op: 0, src_op }
+    }
+    const REGISTER_OP: InstructionInfo = single(&[ADD_RR], Some(0));
+    const IADD_RS: InstructionInfo = single(&[LEA_SIB], Some(0));
+    const IMUL_R: InstructionInfo = single(&[IMUL_RR], Some(0));
+    const IROR_C: InstructionInfo = single(&[ROR_RI], None);
+    const IMMEDIATE_OP: InstructionInfo = single(&[ADD_RI], None);
+    const IMULH_R: InstructionInfo =
+      InstructionInfo { ops: &[MOV_RR, MUL_R, MOV_RR], result_op: 1, dst_op: 0, src_op: Some(1) };
+    const IMUL_RCP: InstructionInfo = InstructionInfo {
+      ops: &[MOV_RI64, MacroOp { dependent: true, ..IMUL_RR }],
+      result_op: 1,
+      dst_op: 1,
+      src_op: None,
+    };
+
+    match self {
+      Opcode::ISubR | Opcode::IXorR => &REGISTER_OP,
+      Opcode::IAddRs => &IADD_RS,
+      Opcode::IMulR => &IMUL_R,
+      Opcode::IRorC => &IROR_C,
+      Opcode::IAddC7 |
+      Opcode::IXorC7 |
+      Opcode::IAddC8 |
+      Opcode::IXorC8 |
+      Opcode::IAddC9 |
+      Opcode::IXorC9 => &IMMEDIATE_OP,
+      Opcode::IMulhR | Opcode::ISMulhR => &IMULH_R,
+      Opcode::IMulRcp => &IMUL_RCP,
+    }
+  }
+}
+
+/// A configuration of how a 16-byte window of x86 code is split into instructions.
+struct DecoderBuffer {
+  index: usize,
+  slots: &'static [usize],
+}
+
+const DECODE_BUFFER_484: DecoderBuffer = DecoderBuffer { index: 0, slots: &[4, 8, 4] };
+const DECODE_BUFFER_7333: DecoderBuffer = DecoderBuffer { index: 1, slots: &[7, 3, 3, 3] };
+const DECODE_BUFFER_3733: DecoderBuffer = DecoderBuffer { index: 2, slots: &[3, 7, 3, 3] };
+const DECODE_BUFFER_493: DecoderBuffer = DecoderBuffer { index: 3, slots: &[4, 9, 3] };
+const DECODE_BUFFER_4444: DecoderBuffer = DecoderBuffer { index: 4, slots: &[4, 4, 4, 4] };
+const DECODE_BUFFER_3310: DecoderBuffer = DecoderBuffer { index: 5, slots: &[3, 3, 10] };
+
+const DECODE_BUFFERS: [&DecoderBuffer; 4] =
+  [&DECODE_BUFFER_484, &DECODE_BUFFER_7333, &DECODE_BUFFER_3733, &DECODE_BUFFER_493];
+
+impl DecoderBuffer {
+  fn next(
+    current: Option<Opcode>,
+    cycle: usize,
+    mul_count: usize,
+    generator: &mut Blake2Generator,
+  ) -> &'static DecoderBuffer {
+    // A full 128-bit multiplication decodes to two micro-ops, requiring a 3-3-10 configuration
+    if matches!(current, Some(Opcode::IMulhR | Opcode::ISMulhR)) {
+      return &DECODE_BUFFER_3310;
+    }
+
+    // Saturate the multiplication port if there are fewer multiplications than cycles
+    if mul_count < (cycle + 1) {
+      return &DECODE_BUFFER_4444;
+    }
+
+    // `IMUL_RCP` requires the next buffer to begin with a 4-byte slot for the multiplication
+    if current == Some(Opcode::IMulRcp) {
+      return if (generator.byte() & 1) == 1 { &DECODE_BUFFER_484 } else { &DECODE_BUFFER_493 };
+    }
+
+    DECODE_BUFFERS[usize::from(generator.byte() & 3)]
+  }
+}
+
+// Group parameters are either a register or this value, which denotes a constant. Other values
+// are sampled at random by the multiplication instructions which allow reusing their operands.
+const CONSTANT_GROUP_PARAMETER: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+struct RegisterInfo {
+  // The cycle the register's value will be ready at
+  latency: usize,
+  // The last operation applied to the register, and its source
+  last_op_group: Option<Opcode>,
+  last_op_par: u32,
+}
+
+// An instruction currently being scheduled
+struct PendingInstruction {
+  opcode: Option<Opcode>,
+  src: Option<usize>,
+  dst: usize,
+  modifier: u8,
+  imm32: u32,
+  group: Opcode,
+  group_par: u32,
+  can_reuse: bool,
+  group_par_is_source: bool,
+}
+
+fn select_register(available: &[usize], generator: &mut Blake2Generator) -> Option<usize> {
+  match available.len() {
+    0 => None,
+    1 => Some(available[0]),
+    len => Some(available[usize::try_from(generator.u32()).unwrap() % len]),
+  }
+}
+
+impl PendingInstruction {
+  fn ops(&self) -> &'static [MacroOp] {
+    self.opcode.map_or(&[], |opcode| opcode.info().ops)
+  }
+
+  fn create_for_slot(
+    &mut self,
+    generator: &mut Blake2Generator,
+    slot: usize,
+    buffer: usize,
+    last: bool,
+  ) {
+    let opcode = match slot {
+      // The last slot may also be used for a full 128-bit multiplication
+      3 if last => [Opcode::ISubR, Opcode::IXorR, Opcode::IMulhR, Opcode::ISMulhR]
+        [usize::from(generator.byte() & 3)],
+      3 => [Opcode::ISubR, Opcode::IXorR][usize::from(generator.byte() & 1)],
+      // The 4-4-4-4 buffer is used to issue multiplications
+      4 if (buffer == DECODE_BUFFER_4444.index) && !last => Opcode::IMulR,
+      4 => [Opcode::IRorC, Opcode::IAddRs][usize::from(generator.byte() & 1)],
+      7 => [Opcode::IXorC7, Opcode::IAddC7][usize::from(generator.byte() & 1)],
+      8 => [Opcode::IXorC8, Opcode::IAddC8][usize::from(generator.byte() & 1)],
+      9 => [Opcode::IXorC9, Opcode::IAddC9][usize::from(generator.byte() & 1)],
+      10 => Opcode::IMulRcp,
+      _ => unreachable!("decoder buffer had a slot of unknown size"),
+    };
+    self.create(opcode, generator);
+  }
+
+  fn create(&mut self, opcode: Opcode, generator: &mut Blake2Generator) {
+    self.opcode = Some(opcode);
+    self.src = None;
+    self.can_reuse = false;
+    self.group_par_is_source = false;
+    self.modifier = 0;
+    self.imm32 = 0;
+
+    match opcode {
+      // `ISUB_R` is intentionally within the same group as `IADD_RS`
+      Opcode::ISubR => {
+        self.group = Opcode::IAddRs;
+        self.group_par_is_source = true;
+      }
+      Opcode::IXorR | Opcode::IMulR => {
+        self.group = opcode;
+        self.group_par_is_source = true;
+      }
+      Opcode::IAddRs => {
+        self.modifier = generator.byte();
+        self.group = Opcode::IAddRs;
+        self.group_par_is_source = true;
+      }
+      Opcode::IRorC => {
+        while self.imm32 == 0 {
+          self.imm32 = u32::from(generator.byte() & 63);
+        }
+        self.group = Opcode::IRorC;
+        self.group_par = CONSTANT_GROUP_PARAMETER;
+      }
+      Opcode::IAddC7 | Opcode::IAddC8 | Opcode::IAddC9 => {
+        self.imm32 = generator.u32();
+        self.group = Opcode::IAddC7;
+        self.group_par = CONSTANT_GROUP_PARAMETER;
+      }
+      Opcode::IXorC7 | Opcode::IXorC8 | Opcode::IXorC9 => {
+        self.imm32 = generator.u32();
+        self.group = Opcode::IXorC7;
+        self.group_par = CONSTANT_GROUP_PARAMETER;
+      }
+      Opcode::IMulhR | Opcode::ISMulhR => {
+        self.can_reuse = true;
+        self.group = opcode;
+        self.group_par = generator.u32();
+      }
+      Opcode::IMulRcp => {
+        // The divisor may not be zero nor a power of two
+        while self.imm32.count_ones() <= 1 {
+          self.imm32 = generator.u32();
+        }
+        self.group = Opcode::IMulRcp;
+        self.group_par = CONSTANT_GROUP_PARAMETER;
+      }
+    }
+  }
+
+  fn select_source(
+    &mut self,
+    cycle: usize,
+    registers: &[RegisterInfo; 8],
+    generator: &mut Blake2Generator,
+  ) -> bool {
+    let mut available = Vec::with_capacity(8);
+    for (i, register) in registers.iter().enumerate() {
+      if register.latency <= cycle {
+        available.push(i);
+      }
+    }
+
+    // If there are only two registers available for `IADD_RS`, and one can't be the destination,
+    // select it as the source
+    if (available.len() == 2) &&
+      (self.opcode == Some(Opcode::IAddRs)) &&
+      available.contains(&REGISTER_NEEDS_DISPLACEMENT)
+    {
+      self.src = Some(REGISTER_NEEDS_DISPLACEMENT);
+      self.group_par = u32::try_from(REGISTER_NEEDS_DISPLACEMENT).unwrap();
+      return true;
+    }
+
+    let Some(src) = select_register(&available, generator) else { return false };
+    self.src = Some(src);
+    if self.group_par_is_source {
+      self.group_par = u32::try_from(src).unwrap();
+    }
+    true
+  }
+
+  fn select_destination(
+    &mut self,
+    cycle: usize,
+    allow_chained_mul: bool,
+    registers: &[RegisterInfo; 8],
+    generator: &mut Blake2Generator,
+  ) -> bool {
+    let mut available = Vec::with_capacity(8);
+    for (i, register) in registers.iter().enumerate() {
+      // The value must be ready
+      if register.latency > cycle {
+        continue;
+      }
+      // The destination may only be the source if the instruction allows it
+      if !self.can_reuse && (Some(i) == self.src) {
+        continue;
+      }
+      // A register may not be multiplied twice in a row, unless explicitly allowed
+      if !allow_chained_mul &&
+        (self.group == Opcode::IMulR) &&
+        (register.last_op_group == Some(Opcode::IMulR))
+      {
+        continue;
+      }
+      // The same operation may not be applied with the same source twice in a row
+      if (register.last_op_group == Some(self.group)) && (register.last_op_par == self.group_par) {
+        continue;
+      }
+      if (self.opcode == Some(Opcode::IAddRs)) && (i == REGISTER_NEEDS_DISPLACEMENT) {
+        continue;
+      }
+      available.push(i);
+    }
+
+    let Some(dst) = select_register(&available, generator) else { return false };
+    self.dst = dst;
+    true
+  }
+}
+
+fn schedule_uop(
+  commit: bool,
+  uop: u8,
+  port_busy: &mut [[bool; 3]; CYCLE_MAP_SIZE],
+  mut cycle: usize,
+) -> Option<usize> {
+  // Ports are checked in the order P5, P0, P1 to not overload P1 (used for multiplication)
+  while cycle < CYCLE_MAP_SIZE {
+    for (port, i) in [(P5, 2), (P0, 0), (P1, 1)] {
+      if ((uop & port) != 0) && !port_busy[cycle][i] {
+        if commit {
+          port_busy[cycle][i] = true;
+        }
+        return Some(cycle);
+      }
+    }
+    cycle += 1;
+  }
+  None
+}
+
+fn schedule_mop(
+  commit: bool,
+  mop: MacroOp,
+  port_busy: &mut [[bool; 3]; CYCLE_MAP_SIZE],
+  mut cycle: usize,
+  dep_cycle: usize,
+) -> Option<usize> {
+  if mop.dependent {
+    cycle = cycle.max(dep_cycle);
+  }
+
+  // Eliminated macro-ops don't require an execution port
+  if mop.uop1 == 0 {
+    return Some(cycle);
+  }
+  if mop.uop2 == 0 {
+    return schedule_uop(commit, mop.uop1, port_busy, cycle);
+  }
+
+  // Macro-ops with two micro-ops are scheduled by requiring both execute in the same cycle
+  while cycle < CYCLE_MAP_SIZE {
+    let cycle1 = schedule_uop(false, mop.uop1, port_busy, cycle);
+    let cycle2 = schedule_uop(false, mop.uop2, port_busy, cycle);
+    if cycle1.is_some() && (cycle1 == cycle2) {
+      if commit {
+        schedule_uop(true, mop.uop1, port_busy, cycle);
+        schedule_uop(true, mop.uop2, port_busy, cycle);
+      }
+      return cycle1;
+    }
+    cycle += 1;
+  }
+  None
+}
+
+/// Calculate `floor(2**x / divisor)`, for the largest `x` such that the result fits in 64 bits.
+pub(crate) fn reciprocal(divisor: u64) -> u64 {
+  let bits = 64 - divisor.leading_zeros();
+  let quotient = (1u128 << (63 + bits)) / u128::from(divisor);
+  u64::try_from(quotient).expect("reciprocal of a power of two")
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+  ISubR,
+  IXorR,
+  IAddRs(u32),
+  IMulR,
+  IRorC(u32),
+  IAddC(u64),
+  IXorC(u64),
+  IMulhR,
+  ISMulhR,
+  IMulRcp(u64),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Instruction {
+  operation: Operation,
+  dst: usize,
+  src: usize,
+}
+
+/// A SuperscalarHash program.
+#[derive(Clone, Debug)]
+pub(crate) struct Program {
+  instructions: Vec<Instruction>,
+  address_register: usize,
+}
+
+impl Program {
+  /// Generate a program from the generator.
+  pub(crate) fn new(generator: &mut Blake2Generator) -> Program {
+    let mut port_busy = [[false; 3]; CYCLE_MAP_SIZE];
+    let mut registers =
+      [RegisterInfo { latency: 0, last_op_group: None, last_op_par: CONSTANT_GROUP_PARAMETER }; 8];
+
+    let mut current = PendingInstruction {
+      opcode: None,
+      src: None,
+      dst: 0,
+      modifier: 0,
+      imm32: 0,
+      group: Opcode::ISubR,
+      group_par: CONSTANT_GROUP_PARAMETER,
+      can_reuse: false,
+      group_par_is_source: false,
+    };
+    let mut instructions = Vec::with_capacity(MAX_SIZE);
+    let mut macro_op_index = 0;
+    let mut cycle = 0;
+    let mut dep_cycle = 0;
+    let mut ports_saturated = false;
+    let mut mul_count = 0;
+    let mut throw_away_count = 0;
+
+    // Decode instructions until the target latency is reached or an execution port is saturated
+    let mut decode_cycle = 0;
+    while (decode_cycle < LATENCY) && !ports_saturated && (instructions.len() < MAX_SIZE) {
+      let buffer = DecoderBuffer::next(current.opcode, decode_cycle, mul_count, generator);
+
+      let mut buffer_index = 0;
+      while buffer_index < buffer.slots.len() {
+        let top_cycle = cycle;
+
+        // If all macro-ops of the current instruction have been issued, create a new instruction
+        if macro_op_index >= current.ops().len() {
+          if ports_saturated || (instructions.len() >= MAX_SIZE) {
+            break;
+          }
+          current.create_for_slot(
+            generator,
+            buffer.slots[buffer_index],
+            buffer.index,
+            (buffer_index + 1) == buffer.slots.len(),
+          );
+          macro_op_index = 0;
+        }
+        let opcode = current.opcode.unwrap();
+        let info = opcode.info();
+        let mop = info.ops[macro_op_index];
+
+        // The earliest cycle this macro-op can be scheduled for execution
+        let Some(mut schedule_cycle) = schedule_mop(false, mop, &mut port_busy, cycle, dep_cycle)
+        else {
+          ports_saturated = true;
+          break;
+        };
+
+        // Find a source register which will be ready when this instruction executes, looking
+        // forward a few cycles if necessary
+        if Some(macro_op_index) == info.src_op {
+          let mut forward = 0;
+          while (forward < LOOK_FORWARD_CYCLES) &&
+            !current.select_source(schedule_cycle, &registers, generator)
+          {
+            schedule_cycle += 1;
+            cycle += 1;
+            forward += 1;
+          }
+          // If no register was found, throw the instruction away and try another one
+          if forward == LOOK_FORWARD_CYCLES {
+            if throw_away_count < MAX_THROWAWAY_COUNT {
+              throw_away_count += 1;
+              macro_op_index = info.ops.len();
+              continue;
+            }
+            current.opcode = None;
+            break;
+          }
+        }
+
+        // Find a destination register, in the same manner
+        if macro_op_index == info.dst_op {
+          let mut forward = 0;
+          while (forward < LOOK_FORWARD_CYCLES) &&
+            !current.select_destination(
+              schedule_cycle,
+              throw_away_count > 0,
+              &registers,
+              generator,
+            )
+          {
+            schedule_cycle += 1;
+            cycle += 1;
+            forward += 1;
+          }
+          if forward == LOOK_FORWARD_CYCLES {
+            if throw_away_count < MAX_THROWAWAY_COUNT {
+              throw_away_count += 1;
+              macro_op_index = info.ops.len();
+              continue;
+            }
+            current.opcode = None;
+            break;
+          }
+        }
+        throw_away_count = 0;
+
+        // Schedule the macro-op now that its operands are known to be available
+        let Some(schedule_cycle) =
+          schedule_mop(true, mop, &mut port_busy, schedule_cycle, schedule_cycle)
+        else {
+          ports_saturated = true;
+          break;
+        };
+        dep_cycle = schedule_cycle + mop.latency;
+
+        if macro_op_index == info.result_op {
+          let register = &mut registers[current.dst];
+          register.latency = dep_cycle;
+          register.last_op_group = Some(current.group);
+          register.last_op_par = current.group_par;
+        }
+        buffer_index += 1;
+        macro_op_index += 1;
+
+        if schedule_cycle >= LATENCY {
+          ports_saturated = true;
+        }
+        cycle = top_cycle;
+
+        // Once all macro-ops of the instruction have been issued, add it to the program
+        if macro_op_index >= info.ops.len() {
+          let dst = current.dst;
+          let src = current.src.unwrap_or(dst);
+          let imm32 = current.imm32;
+          let sign_extended = u64::from(imm32) | (if (imm32 >> 31) == 1 { !0 << 32 } else { 0 });
+          let operation = match opcode {
+            Opcode::ISubR => Operation::ISubR,
+            Opcode::IXorR => Operation::IXorR,
+            Opcode::IAddRs => Operation::IAddRs(u32::from((current.modifier >> 2) % 4)),
+            Opcode::IMulR => Operation::IMulR,
+            Opcode::IRorC => Operation::IRorC(imm32),
+            Opcode::IAddC7 | Opcode::IAddC8 | Opcode::IAddC9 => Operation::IAddC(sign_extended),
+            Opcode::IXorC7 | Opcode::IXorC8 | Opcode::IXorC9 => Operation::IXorC(sign_extended),
+            Opcode::IMulhR => Operation::IMulhR,
+            Opcode::ISMulhR => Operation::ISMulhR,
+            Opcode::IMulRcp => Operation::IMulRcp(reciprocal(u64::from(imm32))),
+          };
+          instructions.push(Instruction { operation, dst, src });
+          if opcode.is_multiplication() {
+            mul_count += 1;
+          }
+        }
+      }
+      cycle += 1;
+      decode_cycle += 1;
+    }
+
+    // The address register is the register with the highest latency on an ASIC, which is assumed
+    // to have a latency of one cycle for all operations and unlimited parallelism
+    let mut asic_latencies = [0; 8];
+    for instruction in &instructions {
+      let dst = asic_latencies[instruction.dst] + 1;
+      let src =
+        if instruction.dst == instruction.src { 0 } else { asic_latencies[instruction.src] + 1 };
+      asic_latencies[instruction.dst] = dst.max(src);
+    }
+    let mut address_register = 0;
+    for (i, latency) in asic_latencies.iter().enumerate() {
+      if *latency > asic_latencies[address_register] {
+        address_register = i;
+      }
+    }
+
+    Program { instructions, address_register }
+  }
+
+  /// The register whose value selects the next block of the cache to mix in.
+  pub(crate) fn address_register(&self) -> usize {
+    self.address_register
+  }
+
+  /// Execute the program over the registers.
+  #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+  pub(crate) fn execute(&self, registers: &mut [u64; 8]) {
+    for instruction in &self.instructions {
+      let src = registers[instruction.src];
+      let dst = &mut registers[instruction.dst];
+      match instruction.operation {
+        Operation::ISubR => *dst = dst.wrapping_sub(src),
+        Operation::IXorR => *dst ^= src,
+        Operation::IAddRs(shift) => *dst = dst.wrapping_add(src << shift),
+        Operation::IMulR => *dst = dst.wrapping_mul(src),
+        Operation::IRorC(rotation) => *dst = dst.rotate_right(rotation),
+        Operation::IAddC(imm) => *dst = dst.wrapping_add(imm),
+        Operation::IXorC(imm) => *dst ^= imm,
+        Operation::IMulhR => *dst = ((u128::from(*dst) * u128::from(src)) >> 64) as u64,
+        Operation::ISMulhR => {
+          *dst = ((i128::from(*dst as i64) * i128::from(src as i64)) >> 64) as u64;
+        }
+        Operation::IMulRcp(reciprocal) => *dst = dst.wrapping_mul(reciprocal),
+      }
+    }
+  }
+}
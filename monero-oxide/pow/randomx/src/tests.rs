@@ -0,0 +1,199 @@
+use std::sync::LazyLock;
+
+use hex_literal::hex;
+
+use blake2::{
+  digest::{Digest, consts::U32},
+  Blake2b, Blake2b512,
+};
+
+use monero_oxide::{
+  transaction::{Input, Timelock, TransactionPrefix, Transaction},
+  block::{BlockHeader, Block},
+};
+
+use crate::{*, float::RoundingMode};
+
+static CACHE_000: LazyLock<Cache> = LazyLock::new(|| Cache::new(b"test key 000"));
+
+#[test]
+fn aes_constants() {
+  let keys = Blake2b512::digest(b"RandomX AesGenerator1R keys");
+  assert_eq!(aes::GENERATOR_1R_KEYS.as_flattened(), keys.as_slice());
+
+  let mut keys = Blake2b512::digest(b"RandomX AesGenerator4R keys 0-3").to_vec();
+  keys.extend(Blake2b512::digest(b"RandomX AesGenerator4R keys 4-7"));
+  assert_eq!(aes::GENERATOR_4R_KEYS.as_flattened(), keys.as_slice());
+
+  let state = Blake2b512::digest(b"RandomX AesHash1R state");
+  assert_eq!(aes::HASH_1R_STATE.as_flattened(), state.as_slice());
+  let keys = Blake2b::<U32>::digest(b"RandomX AesHash1R xkeys");
+  assert_eq!(aes::HASH_1R_XKEYS.as_flattened(), keys.as_slice());
+}
+
+// Vectors from RandomX's `src/tests/tests.cpp`
+#[test]
+fn reciprocal() {
+  use superscalar::reciprocal;
+  assert_eq!(reciprocal(3), 12297829382473034410);
+  assert_eq!(reciprocal(13), 11351842506898185609);
+  assert_eq!(reciprocal(33), 17887751829051686415);
+  assert_eq!(reciprocal(65537), 18446462603027742720);
+  assert_eq!(reciprocal(15000001), 10316166306300415204);
+  assert_eq!(reciprocal(3845182035), 10302264209224146340);
+  assert_eq!(reciprocal(0xffffffff), 9223372039002259456);
+}
+
+#[test]
+fn rounding() {
+  let bits = |x: f64| x.to_bits();
+  let tiny = f64::from_bits(0x3c30000000000000); // 2**-60
+  let above_one = f64::from_bits(1.0f64.to_bits() + 1);
+  let below_one = f64::from_bits(1.0f64.to_bits() - 1);
+
+  assert_eq!(bits(float::add(1.0, tiny, RoundingMode::Nearest)), bits(1.0));
+  assert_eq!(bits(float::add(1.0, tiny, RoundingMode::Down)), bits(1.0));
+  assert_eq!(bits(float::add(1.0, tiny, RoundingMode::Up)), bits(above_one));
+  assert_eq!(bits(float::add(1.0, tiny, RoundingMode::TowardZero)), bits(1.0));
+  assert_eq!(bits(float::sub(1.0, tiny, RoundingMode::Down)), bits(below_one));
+  assert_eq!(bits(float::sub(1.0, tiny, RoundingMode::TowardZero)), bits(below_one));
+  assert_eq!(bits(float::sub(-1.0, tiny, RoundingMode::Up)), bits(-1.0));
+  assert_eq!(bits(float::sub(-1.0, tiny, RoundingMode::Down)), bits(-above_one));
+
+  // An exact result of zero is only negative when rounding down
+  assert_eq!(bits(float::sub(1.0, 1.0, RoundingMode::Up)), bits(0.0));
+  assert_eq!(bits(float::sub(1.0, 1.0, RoundingMode::Down)), bits(-0.0));
+
+  let third = 1.0 / 3.0;
+  assert_eq!(bits(float::div(1.0, 3.0, RoundingMode::Nearest)), bits(third));
+  assert_eq!(bits(float::div(1.0, 3.0, RoundingMode::Down)), bits(third));
+  assert_eq!(bits(float::div(1.0, 3.0, RoundingMode::Up)), bits(third) + 1);
+  assert_eq!(bits(float::div(-1.0, 3.0, RoundingMode::Down)), bits(-third) + 1);
+  assert_eq!(bits(float::div(-1.0, 3.0, RoundingMode::TowardZero)), bits(-third));
+
+  assert_eq!(bits(float::mul(third, 3.0, RoundingMode::Nearest)), bits(1.0));
+  assert_eq!(bits(float::mul(third, 3.0, RoundingMode::Up)), bits(1.0));
+  assert_eq!(bits(float::mul(third, 3.0, RoundingMode::Down)), bits(below_one));
+
+  let root = 2.0f64.sqrt();
+  assert_eq!(bits(float::sqrt(2.0, RoundingMode::Nearest)), bits(root));
+  // The square root of two is rounded up when rounding to nearest
+  assert_eq!(bits(float::sqrt(2.0, RoundingMode::Down)), bits(root) - 1);
+  assert_eq!(bits(float::sqrt(2.0, RoundingMode::Up)), bits(root));
+  assert_eq!(bits(float::sqrt(4.0, RoundingMode::Up)), bits(2.0));
+}
+
+#[test]
+fn seed() {
+  assert_eq!(seed_height(0), 0);
+  assert_eq!(seed_height(2048), 0);
+  assert_eq!(seed_height(2048 + 64), 0);
+  assert_eq!(seed_height(2048 + 65), 2048);
+  assert_eq!(seed_height(4096 + 64), 2048);
+  assert_eq!(seed_height(4096 + 65), 4096);
+  assert_eq!(seed_height(1978433), 1978368);
+}
+
+// Vectors from RandomX's `src/tests/tests.cpp`
+#[test]
+fn dataset() {
+  assert_eq!(CACHE_000.memory[0].as_ref()[0], 0x191e0e1d23c02186);
+  assert_eq!(CACHE_000.dataset_item(0)[0], 0x680588a85ae222db);
+  assert_eq!(CACHE_000.dataset_item(10000000)[0], 0x7943a1f6186ffb72);
+  assert_eq!(CACHE_000.dataset_item(20000000)[0], 0x9035244d718095e1);
+  assert_eq!(CACHE_000.dataset_item(30000000)[0], 0x145a5091f7853099);
+}
+
+// Vectors from RandomX's `src/tests/tests.cpp`
+#[test]
+fn hash() {
+  for (input, hash) in [
+    (
+      &b"This is a test"[..],
+      hex!("639183aae1bf4c9a35884cb46b09cad9175f04efd7684e7262a0ac1c2f0b4e3f"),
+    ),
+    (
+      b"Lorem ipsum dolor sit amet",
+      hex!("300a0adb47603dedb42228ccb2b211104f4da45af709cd7547cd049e9489c969"),
+    ),
+    (
+      b"sed do eiusmod tempor incididunt ut labore et dolore magna aliqua",
+      hex!("c36d4ed4191e617309867ed66a443be4075014e2b061bcdaf9ce7b721d2b77a8"),
+    ),
+  ] {
+    assert_eq!(CACHE_000.hash(input), hash);
+  }
+
+  let cache = Cache::new(b"test key 001");
+  assert_eq!(
+    cache.hash(b"sed do eiusmod tempor incididunt ut labore et dolore magna aliqua"),
+    hex!("e9ff4503201c0c2cca26d285c93ae883f9b1d30c9eb240b820756f2d5a7905fc")
+  );
+  assert_eq!(
+    cache.hash(&hex!(
+      "0b0b98bea7e805e0010a2126d287a2a0cc833d312cb786385a7c2f9de69d25537f584a9bc9977b00000000666f"
+      "d8753bf61a8631f12984e3fd44f4014eca629276817b56f32e9b68bd82f416"
+    )),
+    hex!("c56414121acda1713c2f2a819d8ae38aed7c80c35c2a769298d34f03833cd5f1")
+  );
+}
+
+fn test_block(hardfork_version: u8, number: usize) -> Block {
+  Block::new(
+    BlockHeader {
+      hardfork_version,
+      hardfork_signal: hardfork_version,
+      timestamp: 0,
+      previous: [0; 32],
+      nonce: 0,
+    },
+    Transaction::V2 {
+      prefix: TransactionPrefix {
+        additional_timelock: Timelock::Block(number + 60),
+        inputs: vec![Input::Gen(number)],
+        outputs: vec![],
+        extra: vec![],
+      },
+      proofs: None,
+    },
+    vec![],
+  )
+  .unwrap()
+}
+
+#[test]
+fn block_pow() {
+  let block = test_block(16, 1);
+  let hash = block_pow_hash(&CACHE_000, &block).unwrap();
+  assert_eq!(hash, CACHE_000.hash(&block.serialize_pow_hash()));
+
+  assert!(verify_block_pow(&CACHE_000, &block, 1).unwrap());
+  // The hash's highest 64 bits are non-zero, so the hash can't be multiplied by 2**64
+  assert!(hash[24 ..] != [0; 8]);
+  assert!(!verify_block_pow(&CACHE_000, &block, 1 << 64).unwrap());
+
+  assert_eq!(
+    block_pow_hash(&CACHE_000, &test_block(11, 1)),
+    Err(RandomXError::UnsupportedHardFork(11))
+  );
+  assert_eq!(
+    verify_block_pow(&CACHE_000, &test_block(1, 1), 1),
+    Err(RandomXError::UnsupportedHardFork(1))
+  );
+}
+
+#[test]
+fn difficulty() {
+  assert!(check_hash(&[0xff; 32], 1));
+  assert!(!check_hash(&[0xff; 32], 2));
+
+  // 2**128 may be multiplied by up to 2**128 - 1, yet 2**129 may not be
+  let mut hash = [0; 32];
+  hash[16] = 1;
+  assert!(check_hash(&hash, u128::MAX));
+  hash[16] = 2;
+  assert!(!check_hash(&hash, u128::MAX));
+  assert!(check_hash(&hash, u128::MAX >> 1));
+
+  assert!(check_hash(&[0; 32], u128::MAX));
+}
@@ -0,0 +1,481 @@
+// RandomX の仮想マシン（インタプリタ）
+
+use crate::{
+  CACHE_LINE_SIZE, Cache,
+  float::{self, RoundingMode},
+};
+
+const PROGRAM_SIZE: usize = 256;
+const PROGRAM_ITERATIONS: usize = 2048;
+pub(crate) const PROGRAM_BYTES: usize = 128 + (8 * PROGRAM_SIZE);
+
+pub(crate) const SCRATCHPAD_L3: usize = 2 * 1024 * 1024;
+const SCRATCHPAD_L2: usize = 256 * 1024;
+const SCRATCHPAD_L1: usize = 16 * 1024;
+// Masks for 8-byte aligned accesses to each level of the scratchpad
+const L1_MASK: u64 = (SCRATCHPAD_L1 as u64 - 1) & !7;
+const L2_MASK: u64 = (SCRATCHPAD_L2 as u64 - 1) & !7;
+const L3_MASK: u64 = (SCRATCHPAD_L3 as u64 - 1) & !7;
+// The mask for 64-byte aligned accesses to the scratchpad
+const L3_MASK_64: u64 = (SCRATCHPAD_L3 as u64 - 1) & !63;
+
+const DATASET_BASE_SIZE: u64 = 1 << 31;
+const DATASET_EXTRA_SIZE: u64 = 33554368;
+const CACHE_LINE_ALIGN_MASK: u64 = (DATASET_BASE_SIZE - 1) & !(CACHE_LINE_SIZE as u64 - 1);
+
+const JUMP_BITS: u32 = 8;
+const JUMP_OFFSET: u32 = 8;
+const CONDITION_MASK: u64 = (1 << JUMP_BITS) - 1;
+// The condition at which `ISTORE` writes to L3
+const STORE_L3_CONDITION: u8 = 14;
+
+const MANTISSA_SIZE: u32 = 52;
+const MANTISSA_MASK: u64 = (1 << MANTISSA_SIZE) - 1;
+const EXPONENT_MASK: u64 = (1 << 11) - 1;
+const EXPONENT_BIAS: u64 = 1023;
+const DYNAMIC_EXPONENT_BITS: u32 = 4;
+const STATIC_EXPONENT_BITS: u32 = 4;
+const CONST_EXPONENT_BITS: u64 = 0x300;
+const DYNAMIC_MANTISSA_MASK: u64 = (1 << (MANTISSA_SIZE + DYNAMIC_EXPONENT_BITS)) - 1;
+const SCALE_MASK: u64 = 0x80F0000000000000;
+
+// A positive float with an exponent in the range [0, 32) and a random mantissa
+fn small_positive_float_bits(entropy: u64) -> u64 {
+  let exponent = (((entropy >> 59) + EXPONENT_BIAS) & EXPONENT_MASK) << MANTISSA_SIZE;
+  exponent | (entropy & MANTISSA_MASK)
+}
+
+fn float_mask(entropy: u64) -> u64 {
+  const MASK_22_BIT: u64 = (1 << 22) - 1;
+  let exponent = (CONST_EXPONENT_BITS |
+    ((entropy >> (64 - STATIC_EXPONENT_BITS)) << DYNAMIC_EXPONENT_BITS)) <<
+    MANTISSA_SIZE;
+  (entropy & MASK_22_BIT) | exponent
+}
+
+// Interpret a word as two signed 32-bit integers, converted to floats
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn floats(word: u64) -> [f64; 2] {
+  [f64::from(word as u32 as i32), f64::from((word >> 32) as u32 as i32)]
+}
+
+fn sign_extend(imm32: u32) -> u64 {
+  u64::from(imm32) | (if (imm32 >> 31) == 1 { !0 << 32 } else { 0 })
+}
+
+// The second operand of an integer instruction, which is an immediate if the source and
+// destination registers are the same
+#[derive(Clone, Copy)]
+enum Operand {
+  Register(usize),
+  Immediate(u64),
+}
+
+// A scratchpad address, calculated from a register (if present) and an immediate
+#[derive(Clone, Copy)]
+struct Address {
+  register: Option<usize>,
+  imm: u64,
+  mask: u64,
+}
+
+#[derive(Clone, Copy)]
+enum Instruction {
+  IAddRs { dst: usize, src: usize, shift: u32, imm: u64 },
+  IAddM { dst: usize, src: Address },
+  ISubR { dst: usize, src: Operand },
+  ISubM { dst: usize, src: Address },
+  IMulR { dst: usize, src: Operand },
+  IMulM { dst: usize, src: Address },
+  IMulhR { dst: usize, src: usize },
+  IMulhM { dst: usize, src: Address },
+  ISMulhR { dst: usize, src: usize },
+  ISMulhM { dst: usize, src: Address },
+  INegR { dst: usize },
+  IXorR { dst: usize, src: Operand },
+  IXorM { dst: usize, src: Address },
+  IRorR { dst: usize, src: Operand },
+  IRolR { dst: usize, src: Operand },
+  ISwapR { dst: usize, src: usize },
+  FSwapR { dst: usize },
+  FAddR { dst: usize, src: usize },
+  FAddM { dst: usize, src: Address },
+  FSubR { dst: usize, src: usize },
+  FSubM { dst: usize, src: Address },
+  FScalR { dst: usize },
+  FMulR { dst: usize, src: usize },
+  FDivM { dst: usize, src: Address },
+  FSqrtR { dst: usize },
+  CBranch { register: usize, imm: u64, mask: u64, target: usize },
+  CFRound { src: usize, rotation: u32 },
+  IStore { dst: Address, src: usize },
+  Nop,
+}
+
+/// The register file, as hashed between programs and for the final result.
+pub(crate) struct Registers {
+  r: [u64; 8],
+  f: [[f64; 2]; 4],
+  e: [[f64; 2]; 4],
+  pub(crate) a: [[f64; 2]; 4],
+}
+
+impl Registers {
+  pub(crate) fn new() -> Self {
+    Registers { r: [0; 8], f: [[0.0; 2]; 4], e: [[0.0; 2]; 4], a: [[0.0; 2]; 4] }
+  }
+
+  pub(crate) fn serialize(&self) -> [u8; 256] {
+    let mut res = [0; 256];
+    let floats = self.f.iter().chain(&self.e).chain(&self.a).flatten().map(|float| float.to_bits());
+    for (chunk, word) in res.chunks_exact_mut(8).zip(self.r.iter().copied().chain(floats)) {
+      chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    res
+  }
+}
+
+/// The virtual machine, which persists the scratchpad and rounding mode across programs.
+pub(crate) struct Vm<'a> {
+  cache: &'a Cache,
+  pub(crate) scratchpad: &'a mut [u64],
+  pub(crate) registers: Registers,
+  rounding_mode: RoundingMode,
+}
+
+impl<'a> Vm<'a> {
+  pub(crate) fn new(cache: &'a Cache, scratchpad: &'a mut [u64]) -> Self {
+    Vm { cache, scratchpad, registers: Registers::new(), rounding_mode: RoundingMode::Nearest }
+  }
+
+  fn load(&self, address: Address) -> u64 {
+    let base = address.register.map_or(0, |register| self.registers.r[register]);
+    self.scratchpad[usize::try_from(base.wrapping_add(address.imm) & address.mask).unwrap() / 8]
+  }
+
+  fn load_floats(&self, address: Address) -> [f64; 2] {
+    floats(self.load(address))
+  }
+
+  /// Run a program, as generated by AesGenerator4R.
+  pub(crate) fn run(&mut self, program: &[u8; PROGRAM_BYTES]) {
+    let entropy: [u64; 16] = core::array::from_fn(|i| {
+      u64::from_le_bytes(program[(i * 8) .. ((i + 1) * 8)].try_into().unwrap())
+    });
+
+    let registers = &mut self.registers;
+    registers.r = [0; 8];
+    for (i, a) in registers.a.iter_mut().enumerate() {
+      *a = [
+        f64::from_bits(small_positive_float_bits(entropy[2 * i])),
+        f64::from_bits(small_positive_float_bits(entropy[(2 * i) + 1])),
+      ];
+    }
+    let mut ma = entropy[8] & CACHE_LINE_ALIGN_MASK;
+    let mut mx = entropy[10] & u64::from(u32::MAX);
+    let read_registers: [usize; 4] =
+      core::array::from_fn(|i| (2 * i) + usize::from(((entropy[12] >> i) & 1) == 1));
+    let dataset_offset = (entropy[13] % ((DATASET_EXTRA_SIZE / (CACHE_LINE_SIZE as u64)) + 1)) *
+      (CACHE_LINE_SIZE as u64);
+    let e_mask = [float_mask(entropy[14]), float_mask(entropy[15])];
+
+    let instructions = compile(&program[128 ..]);
+
+    let mut sp_addr0 = mx;
+    let mut sp_addr1 = ma;
+    for _ in 0 .. PROGRAM_ITERATIONS {
+      let sp_mix = self.registers.r[read_registers[0]] ^ self.registers.r[read_registers[1]];
+      sp_addr0 = (sp_addr0 ^ sp_mix) & L3_MASK_64;
+      sp_addr1 = (sp_addr1 ^ (sp_mix >> 32)) & L3_MASK_64;
+      let sp_addr0_index = usize::try_from(sp_addr0).unwrap() / 8;
+      let sp_addr1_index = usize::try_from(sp_addr1).unwrap() / 8;
+
+      for i in 0 .. 8 {
+        self.registers.r[i] ^= self.scratchpad[sp_addr0_index + i];
+      }
+      for i in 0 .. 4 {
+        self.registers.f[i] = floats(self.scratchpad[sp_addr1_index + i]);
+        let e = floats(self.scratchpad[sp_addr1_index + 4 + i]);
+        self.registers.e[i] =
+          [0, 1].map(|j| f64::from_bits((e[j].to_bits() & DYNAMIC_MANTISSA_MASK) | e_mask[j]));
+      }
+
+      self.execute(&instructions, e_mask);
+
+      mx ^= (self.registers.r[read_registers[2]] ^ self.registers.r[read_registers[3]]) &
+        u64::from(u32::MAX);
+      mx &= CACHE_LINE_ALIGN_MASK;
+      let item = self.cache.dataset_item((dataset_offset + ma) / (CACHE_LINE_SIZE as u64));
+      for (r, item) in self.registers.r.iter_mut().zip(item) {
+        *r ^= item;
+      }
+      core::mem::swap(&mut mx, &mut ma);
+
+      self.scratchpad[sp_addr1_index .. (sp_addr1_index + 8)].copy_from_slice(&self.registers.r);
+      for i in 0 .. 4 {
+        for j in 0 .. 2 {
+          let f = self.registers.f[i][j].to_bits() ^ self.registers.e[i][j].to_bits();
+          self.registers.f[i][j] = f64::from_bits(f);
+          self.scratchpad[sp_addr0_index + (2 * i) + j] = f;
+        }
+      }
+
+      sp_addr0 = 0;
+      sp_addr1 = 0;
+    }
+  }
+
+  #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+  fn execute(&mut self, instructions: &[Instruction; PROGRAM_SIZE], e_mask: [u64; 2]) {
+    let mut pc = 0;
+    while pc < PROGRAM_SIZE {
+      let mode = self.rounding_mode;
+      let r = &self.registers.r;
+      let operand = |operand| match operand {
+        Operand::Register(src) => r[src],
+        Operand::Immediate(imm) => imm,
+      };
+      match instructions[pc] {
+        Instruction::IAddRs { dst, src, shift, imm } => {
+          self.registers.r[dst] = r[dst].wrapping_add(r[src] << shift).wrapping_add(imm);
+        }
+        Instruction::IAddM { dst, src } => {
+          self.registers.r[dst] = r[dst].wrapping_add(self.load(src));
+        }
+        Instruction::ISubR { dst, src } => {
+          self.registers.r[dst] = r[dst].wrapping_sub(operand(src));
+        }
+        Instruction::ISubM { dst, src } => {
+          self.registers.r[dst] = r[dst].wrapping_sub(self.load(src));
+        }
+        Instruction::IMulR { dst, src } => {
+          self.registers.r[dst] = r[dst].wrapping_mul(operand(src));
+        }
+        Instruction::IMulM { dst, src } => {
+          self.registers.r[dst] = r[dst].wrapping_mul(self.load(src));
+        }
+        Instruction::IMulhR { dst, src } => {
+          self.registers.r[dst] = ((u128::from(r[dst]) * u128::from(r[src])) >> 64) as u64;
+        }
+        Instruction::IMulhM { dst, src } => {
+          self.registers.r[dst] = ((u128::from(r[dst]) * u128::from(self.load(src))) >> 64) as u64;
+        }
+        Instruction::ISMulhR { dst, src } => {
+          self.registers.r[dst] =
+            ((i128::from(r[dst] as i64) * i128::from(r[src] as i64)) >> 64) as u64;
+        }
+        Instruction::ISMulhM { dst, src } => {
+          self.registers.r[dst] =
+            ((i128::from(r[dst] as i64) * i128::from(self.load(src) as i64)) >> 64) as u64;
+        }
+        Instruction::INegR { dst } => self.registers.r[dst] = r[dst].wrapping_neg(),
+        Instruction::IXorR { dst, src } => self.registers.r[dst] = r[dst] ^ operand(src),
+        Instruction::IXorM { dst, src } => self.registers.r[dst] = r[dst] ^ self.load(src),
+        Instruction::IRorR { dst, src } => {
+          self.registers.r[dst] = r[dst].rotate_right((operand(src) & 63) as u32);
+        }
+        Instruction::IRolR { dst, src } => {
+          self.registers.r[dst] = r[dst].rotate_left((operand(src) & 63) as u32);
+        }
+        Instruction::ISwapR { dst, src } => self.registers.r.swap(dst, src),
+        Instruction::FSwapR { dst } => {
+          if dst < 4 {
+            self.registers.f[dst].swap(0, 1);
+          } else {
+            self.registers.e[dst - 4].swap(0, 1);
+          }
+        }
+        Instruction::FAddR { dst, src } => {
+          let a = self.registers.a[src];
+          let f = &mut self.registers.f[dst];
+          *f = [0, 1].map(|j| float::add(f[j], a[j], mode));
+        }
+        Instruction::FAddM { dst, src } => {
+          let src = self.load_floats(src);
+          let f = &mut self.registers.f[dst];
+          *f = [0, 1].map(|j| float::add(f[j], src[j], mode));
+        }
+        Instruction::FSubR { dst, src } => {
+          let a = self.registers.a[src];
+          let f = &mut self.registers.f[dst];
+          *f = [0, 1].map(|j| float::sub(f[j], a[j], mode));
+        }
+        Instruction::FSubM { dst, src } => {
+          let src = self.load_floats(src);
+          let f = &mut self.registers.f[dst];
+          *f = [0, 1].map(|j| float::sub(f[j], src[j], mode));
+        }
+        Instruction::FScalR { dst } => {
+          let f = &mut self.registers.f[dst];
+          *f = f.map(|f| f64::from_bits(f.to_bits() ^ SCALE_MASK));
+        }
+        Instruction::FMulR { dst, src } => {
+          let a = self.registers.a[src];
+          let e = &mut self.registers.e[dst];
+          *e = [0, 1].map(|j| float::mul(e[j], a[j], mode));
+        }
+        Instruction::FDivM { dst, src } => {
+          let src = self.load_floats(src);
+          let e = &mut self.registers.e[dst];
+          *e = [0, 1].map(|j| {
+            let divisor = f64::from_bits((src[j].to_bits() & DYNAMIC_MANTISSA_MASK) | e_mask[j]);
+            float::div(e[j], divisor, mode)
+          });
+        }
+        Instruction::FSqrtR { dst } => {
+          let e = &mut self.registers.e[dst];
+          *e = e.map(|e| float::sqrt(e, mode));
+        }
+        Instruction::CBranch { register, imm, mask, target } => {
+          let value = r[register].wrapping_add(imm);
+          self.registers.r[register] = value;
+          if (value & mask) == 0 {
+            pc = target;
+            continue;
+          }
+        }
+        Instruction::CFRound { src, rotation } => {
+          self.rounding_mode = RoundingMode::from_bits(r[src].rotate_right(rotation));
+        }
+        Instruction::IStore { dst, src } => {
+          let address = r[dst.register.unwrap()].wrapping_add(dst.imm) & dst.mask;
+          self.scratchpad[usize::try_from(address).unwrap() / 8] = r[src];
+        }
+        Instruction::Nop => {}
+      }
+      pc += 1;
+    }
+  }
+}
+
+// Decode a program into its instructions
+fn compile(program: &[u8]) -> [Instruction; PROGRAM_SIZE] {
+  // The last instruction to modify each register, used as the target of branches
+  let mut register_usage: [Option<usize>; 8] = [None; 8];
+  let mut instructions = [Instruction::Nop; PROGRAM_SIZE];
+  for (i, (instruction, bytes)) in instructions.iter_mut().zip(program.chunks_exact(8)).enumerate()
+  {
+    let opcode = bytes[0];
+    let dst = usize::from(bytes[1] % 8);
+    let src = usize::from(bytes[2] % 8);
+    let modifier = bytes[3];
+    let imm32 = u32::from_le_bytes(bytes[4 ..].try_into().unwrap());
+    let imm = sign_extend(imm32);
+
+    let operand = if src == dst { Operand::Immediate(imm) } else { Operand::Register(src) };
+    let level_mask = if (modifier % 4) == 0 { L2_MASK } else { L1_MASK };
+    // The address for loads into integer registers, which is absolute within L3 if the source and
+    // destination registers are the same
+    let int_address = if src == dst {
+      Address { register: None, imm, mask: L3_MASK }
+    } else {
+      Address { register: Some(src), imm, mask: level_mask }
+    };
+    let float_address = Address { register: Some(src), imm, mask: level_mask };
+    let float_dst = dst % 4;
+    let float_src = src % 4;
+
+    *instruction = match opcode {
+      0 ..= 15 => Instruction::IAddRs {
+        dst,
+        src,
+        shift: u32::from((modifier >> 2) % 4),
+        // r5 can't be encoded without a displacement, which is then used as an immediate
+        imm: if dst == 5 { imm } else { 0 },
+      },
+      16 ..= 22 => Instruction::IAddM { dst, src: int_address },
+      23 ..= 38 => Instruction::ISubR { dst, src: operand },
+      39 ..= 45 => Instruction::ISubM { dst, src: int_address },
+      46 ..= 61 => Instruction::IMulR { dst, src: operand },
+      62 ..= 65 => Instruction::IMulM { dst, src: int_address },
+      66 ..= 69 => Instruction::IMulhR { dst, src },
+      70 => Instruction::IMulhM { dst, src: int_address },
+      71 ..= 74 => Instruction::ISMulhR { dst, src },
+      75 => Instruction::ISMulhM { dst, src: int_address },
+      76 ..= 83 => {
+        // Multiplication by a reciprocal, which is a NOP for divisors of zero and powers of two
+        if imm32.count_ones() <= 1 {
+          *instruction = Instruction::Nop;
+          continue;
+        }
+        Instruction::IMulR {
+          dst,
+          src: Operand::Immediate(crate::superscalar::reciprocal(u64::from(imm32))),
+        }
+      }
+      84 ..= 85 => Instruction::INegR { dst },
+      86 ..= 100 => Instruction::IXorR { dst, src: operand },
+      101 ..= 105 => Instruction::IXorM { dst, src: int_address },
+      106 ..= 113 => Instruction::IRorR { dst, src: operand },
+      114 ..= 115 => Instruction::IRolR { dst, src: operand },
+      116 ..= 119 => {
+        if src == dst {
+          *instruction = Instruction::Nop;
+          continue;
+        }
+        register_usage[src] = Some(i);
+        Instruction::ISwapR { dst, src }
+      }
+      120 ..= 123 => {
+        *instruction = Instruction::FSwapR { dst };
+        continue;
+      }
+      124 ..= 139 => {
+        *instruction = Instruction::FAddR { dst: float_dst, src: float_src };
+        continue;
+      }
+      140 ..= 144 => {
+        *instruction = Instruction::FAddM { dst: float_dst, src: float_address };
+        continue;
+      }
+      145 ..= 160 => {
+        *instruction = Instruction::FSubR { dst: float_dst, src: float_src };
+        continue;
+      }
+      161 ..= 165 => {
+        *instruction = Instruction::FSubM { dst: float_dst, src: float_address };
+        continue;
+      }
+      166 ..= 171 => {
+        *instruction = Instruction::FScalR { dst: float_dst };
+        continue;
+      }
+      172 ..= 203 => {
+        *instruction = Instruction::FMulR { dst: float_dst, src: float_src };
+        continue;
+      }
+      204 ..= 207 => {
+        *instruction = Instruction::FDivM { dst: float_dst, src: float_address };
+        continue;
+      }
+      208 ..= 213 => {
+        *instruction = Instruction::FSqrtR { dst: float_dst };
+        continue;
+      }
+      214 ..= 238 => {
+        let shift = u32::from(modifier >> 4) + JUMP_OFFSET;
+        // Clearing the bit below the condition limits the amount of successive jumps
+        let imm = (imm | (1 << shift)) & !(1 << (shift - 1));
+        // Continue from after the last instruction to modify the register
+        let target = register_usage[dst].map_or(0, |i| i + 1);
+        // All registers are considered modified by the branch
+        register_usage = [Some(i); 8];
+        *instruction =
+          Instruction::CBranch { register: dst, imm, mask: CONDITION_MASK << shift, target };
+        continue;
+      }
+      239 => {
+        *instruction = Instruction::CFRound { src, rotation: imm32 & 63 };
+        continue;
+      }
+      240 ..= 255 => {
+        let mask = if (modifier >> 4) < STORE_L3_CONDITION { level_mask } else { L3_MASK };
+        *instruction = Instruction::IStore { dst: Address { register: Some(dst), imm, mask }, src };
+        continue;
+      }
+    };
+    // This instruction modified its destination register
+    register_usage[dst] = Some(i);
+  }
+  instructions
+}
@@ -11,12 +11,19 @@ use std_shims::{
   vec::Vec,
 };
 
+#[cfg(feature = "prove")]
+use rand_core::{RngCore, CryptoRng};
+
 use zeroize::Zeroize;
+#[cfg(feature = "prove")]
+use zeroize::Zeroizing;
 
 use curve25519_dalek::{traits::Identity, EdwardsPoint, edwards::CompressedEdwardsY};
 
 use monero_io::*;
 use monero_ed25519::{UnreducedScalar, Scalar, Point, CompressedPoint};
+#[cfg(feature = "prove")]
+use monero_ed25519::Commitment;
 
 static H_POW_2_CELL: LazyLock<[EdwardsPoint; 64]> = LazyLock::new(|| {
   #[allow(non_snake_case)]
@@ -89,6 +96,58 @@ impl BorromeanSignatures {
 
     Scalar::hash(transcript) == self.ee
   }
+
+  // Sign the 64 rings of [keys_a[i], keys_b[i]], where `keys` is the discrete logarithm of the
+  // key selected by the respective bit of `bits`.
+  #[cfg(feature = "prove")]
+  fn prove<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    keys: &[Zeroizing<curve25519_dalek::Scalar>; 64],
+    bits: u64,
+    keys_a: &[EdwardsPoint],
+    keys_b: &[EdwardsPoint],
+  ) -> Self {
+    // The encodings of reduced scalars, which is how monerod creates these
+    fn unreduced(scalar: curve25519_dalek::Scalar) -> UnreducedScalar {
+      UnreducedScalar::read(&mut scalar.to_bytes().as_slice())
+        .expect("couldn't read an UnreducedScalar from 32 bytes")
+    }
+
+    let mut nonces = Vec::with_capacity(64);
+    let mut s1 = [curve25519_dalek::Scalar::ZERO; 64];
+    let mut transcript = [0; 2048];
+    for i in 0 .. 64 {
+      let nonce = Zeroizing::new(Scalar::random(&mut *rng).into());
+      #[allow(non_snake_case)]
+      let mut LV = EdwardsPoint::mul_base(&nonce);
+      // If we know the key for `keys_a[i]`, forge the signature for `keys_b[i]`
+      if ((bits >> i) & 1) == 0 {
+        s1[i] = Scalar::random(&mut *rng).into();
+        LV = EdwardsPoint::vartime_double_scalar_mul_basepoint(
+          &Scalar::hash(LV.compress().to_bytes()).into(),
+          &keys_b[i],
+          &s1[i],
+        );
+      }
+      transcript[(i * 32) .. ((i + 1) * 32)].copy_from_slice(&LV.compress().to_bytes());
+      nonces.push(nonce);
+    }
+    let ee = Scalar::hash(transcript);
+
+    let mut s0 = [curve25519_dalek::Scalar::ZERO; 64];
+    for i in 0 .. 64 {
+      if ((bits >> i) & 1) == 0 {
+        s0[i] = *nonces[i] - (ee.into() * *keys[i]);
+      } else {
+        s0[i] = Scalar::random(&mut *rng).into();
+        #[allow(non_snake_case)]
+        let LL = EdwardsPoint::vartime_double_scalar_mul_basepoint(&ee.into(), &keys_a[i], &s0[i]);
+        s1[i] = *nonces[i] - (Scalar::hash(LL.compress().to_bytes()).into() * *keys[i]);
+      }
+    }
+
+    BorromeanSignatures { s0: s0.map(unreduced), s1: s1.map(unreduced), ee }
+  }
 }
 
 /// A range proof premised on Borromean ring signatures.
@@ -113,6 +172,52 @@ impl BorromeanRange {
     write_raw_vec(CompressedPoint::write, &self.bit_commitments, w)
   }
 
+  /// Prove the commitment contains a 64-bit value.
+  ///
+  /// The bit commitments' masks are sampled so they sum to the commitment's mask.
+  ///
+  /// Borromean range proofs are only used by historical `RctType`s. This is intended for
+  /// generating test fixtures and must not be used to create new transactions. This function runs
+  /// in variable time.
+  #[cfg(feature = "prove")]
+  pub fn prove<R: RngCore + CryptoRng>(rng: &mut R, commitment: &Commitment) -> BorromeanRange {
+    #[allow(non_snake_case)]
+    let H_pow_2 = H_pow_2();
+
+    let mut masks =
+      core::array::from_fn::<_, 64, _>(|_| Zeroizing::new(curve25519_dalek::Scalar::ZERO));
+    let mut sum_masks = Zeroizing::new(curve25519_dalek::Scalar::ZERO);
+    for mask in &mut masks[.. 63] {
+      **mask = Scalar::random(&mut *rng).into();
+      *sum_masks += **mask;
+    }
+    *masks[63] = commitment.mask.into() - *sum_masks;
+
+    let mut bit_commitments = [EdwardsPoint::identity(); 64];
+    let mut commitments_sub_one = [EdwardsPoint::identity(); 64];
+    for i in 0 .. 64 {
+      let blinding = EdwardsPoint::mul_base(&masks[i]);
+      if ((commitment.amount >> i) & 1) == 0 {
+        bit_commitments[i] = blinding;
+        commitments_sub_one[i] = blinding - H_pow_2[i];
+      } else {
+        bit_commitments[i] = blinding + H_pow_2[i];
+        commitments_sub_one[i] = blinding;
+      }
+    }
+
+    BorromeanRange {
+      sigs: BorromeanSignatures::prove(
+        rng,
+        &masks,
+        commitment.amount,
+        &bit_commitments,
+        &commitments_sub_one,
+      ),
+      bit_commitments: bit_commitments.map(|point| Point::from(point).compress()),
+    }
+  }
+
   /// Verify the commitment contains a 64-bit value.
   #[must_use]
   pub fn verify(&self, commitment: &CompressedPoint) -> bool {
@@ -139,3 +244,43 @@ impl BorromeanRange {
     self.sigs.verify(&bit_commitments, &commitments_sub_one)
   }
 }
+
+// monerod's JSON represents the signatures (`asig`) and the bit commitments (`Ci`) each as a
+// single hex string of their concatenated encodings
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BorromeanRangeJson {
+  #[serde(with = "monero_io::serde_hex")]
+  asig: Vec<u8>,
+  #[serde(rename = "Ci", with = "monero_io::serde_hex")]
+  ci: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BorromeanRange {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut asig = Vec::with_capacity(((64 + 64) * 32) + 32);
+    self.sigs.write(&mut asig).expect("write failed but <Vec as io::Write> doesn't fail");
+    let mut ci = Vec::with_capacity(64 * 32);
+    write_raw_vec(CompressedPoint::write, &self.bit_commitments, &mut ci)
+      .expect("write failed but <Vec as io::Write> doesn't fail");
+    BorromeanRangeJson { asig, ci }.serialize(serializer)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BorromeanRange {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    use serde::de::Error;
+
+    let BorromeanRangeJson { mut asig, ci } = BorromeanRangeJson::deserialize(deserializer)?;
+    asig.extend(ci);
+    let mut reader = asig.as_slice();
+    let res =
+      BorromeanRange::read(&mut reader).map_err(|_| D::Error::custom("invalid BorromeanRange"))?;
+    if !reader.is_empty() {
+      Err(D::Error::custom("BorromeanRange had trailing bytes"))?;
+    }
+    Ok(res)
+  }
+}
@@ -0,0 +1,120 @@
+// Bulletproof(+) を monerod の JSON（`rctsig_prunable` 内の `bp` / `bpp`）と互換の形式で
+// `serde` により（逆）シリアライズする実装です。
+use std_shims::vec::Vec;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+
+use monero_ed25519::{Scalar, CompressedPoint};
+
+use crate::{MAX_LR, Bulletproof, OriginalProof, IpProof, PlusProof, WipProof};
+
+#[derive(Serialize, Deserialize)]
+struct OriginalJson {
+  A: CompressedPoint,
+  S: CompressedPoint,
+  T1: CompressedPoint,
+  T2: CompressedPoint,
+  taux: Scalar,
+  mu: Scalar,
+  L: Vec<CompressedPoint>,
+  R: Vec<CompressedPoint>,
+  a: Scalar,
+  b: Scalar,
+  t: Scalar,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlusJson {
+  A: CompressedPoint,
+  A1: CompressedPoint,
+  B: CompressedPoint,
+  r1: Scalar,
+  s1: Scalar,
+  d1: Scalar,
+  L: Vec<CompressedPoint>,
+  R: Vec<CompressedPoint>,
+}
+
+// A Bulletproof has the fields `S`, `T1`, ..., which a Bulletproof+ lacks, and a Bulletproof+
+// has the fields `A1`, `B`, ..., which a Bulletproof lacks, so the variant is unambiguous
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BulletproofJson {
+  Original(OriginalJson),
+  Plus(PlusJson),
+}
+
+impl Serialize for Bulletproof {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Bulletproof::Original(bp) => OriginalJson {
+        A: bp.A,
+        S: bp.S,
+        T1: bp.T1,
+        T2: bp.T2,
+        taux: Scalar::from(bp.tau_x),
+        mu: Scalar::from(bp.mu),
+        L: bp.ip.L.clone(),
+        R: bp.ip.R.clone(),
+        a: Scalar::from(bp.ip.a),
+        b: Scalar::from(bp.ip.b),
+        t: Scalar::from(bp.t_hat),
+      }
+      .serialize(serializer),
+      Bulletproof::Plus(bp) => PlusJson {
+        A: bp.A,
+        A1: bp.wip.A,
+        B: bp.wip.B,
+        r1: Scalar::from(bp.wip.r_answer),
+        s1: Scalar::from(bp.wip.s_answer),
+        d1: Scalar::from(bp.wip.delta_answer),
+        L: bp.wip.L.clone(),
+        R: bp.wip.R.clone(),
+      }
+      .serialize(serializer),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for Bulletproof {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // Apply the same bound `Bulletproof::read` does
+    let check_lr = |L: &[CompressedPoint], R: &[CompressedPoint]| {
+      if (L.len() > MAX_LR) || (R.len() > MAX_LR) {
+        Err(D::Error::custom("L/R exceeded the maximum length"))?;
+      }
+      Ok(())
+    };
+
+    Ok(match BulletproofJson::deserialize(deserializer)? {
+      BulletproofJson::Original(bp) => {
+        check_lr(&bp.L, &bp.R)?;
+        Bulletproof::Original(OriginalProof {
+          A: bp.A,
+          S: bp.S,
+          T1: bp.T1,
+          T2: bp.T2,
+          tau_x: bp.taux.into(),
+          mu: bp.mu.into(),
+          ip: IpProof { L: bp.L, R: bp.R, a: bp.a.into(), b: bp.b.into() },
+          t_hat: bp.t.into(),
+        })
+      }
+      BulletproofJson::Plus(bp) => {
+        check_lr(&bp.L, &bp.R)?;
+        Bulletproof::Plus(PlusProof {
+          A: bp.A,
+          wip: WipProof {
+            A: bp.A1,
+            B: bp.B,
+            r_answer: bp.r1.into(),
+            s_answer: bp.s1.into(),
+            delta_answer: bp.d1.into(),
+            L: bp.L,
+            R: bp.R,
+          },
+        })
+      }
+    })
+  }
+}
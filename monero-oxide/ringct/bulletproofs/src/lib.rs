@@ -41,6 +41,9 @@ use crate::plus::{
   AggregateRangeProof as PlusProof,
 };
 
+#[cfg(feature = "serde")]
+mod json;
+
 #[cfg(test)]
 mod tests;
 
@@ -67,7 +70,8 @@ pub enum BulletproofError {
 
 /// A Bulletproof(+).
 ///
-/// This encapsulates either a Bulletproof or a Bulletproof+.
+/// This encapsulates either a Bulletproof or a Bulletproof+. With the `serde` feature, this is
+/// (de)serialized as within monerod's JSON.
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Bulletproof {
@@ -257,7 +257,10 @@ fn core(
 }
 
 /// The CLSAG signature, as used in Monero.
+///
+/// With the `serde` feature, this is (de)serialized as within monerod's JSON.
 #[derive(Clone, PartialEq, Eq, Debug, Zeroize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Clsag {
   /// The difference of the commitment randomnesses, scaling the key image generator.
   pub D: CompressedPoint,
@@ -10,7 +10,12 @@ use std_shims::{
   io::{self, Read, Write},
 };
 
+#[cfg(feature = "sign")]
+use rand_core::{RngCore, CryptoRng};
+
 use zeroize::Zeroize;
+#[cfg(feature = "sign")]
+use zeroize::Zeroizing;
 
 use curve25519_dalek::EdwardsPoint;
 
@@ -40,6 +45,9 @@ pub enum MlsagError {
   /// Invalid ci vector.
   #[error("invalid ci")]
   InvalidCi,
+  /// The keys provided didn't match the signing ring member.
+  #[error("invalid key")]
+  InvalidKey,
 }
 
 /// A vector of rings, forming a matrix, to verify the MLSAG with.
@@ -101,7 +109,10 @@ impl RingMatrix {
 }
 
 /// The MLSAG linkable ring signature, as used in Monero.
+///
+/// With the `serde` feature, this is (de)serialized as within monerod's JSON.
 #[derive(Clone, PartialEq, Eq, Debug, Zeroize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mlsag {
   ss: Vec<Vec<Scalar>>,
   cc: Scalar,
@@ -196,6 +207,118 @@ impl Mlsag {
     }
     Ok(())
   }
+
+  /// Sign a MLSAG.
+  ///
+  /// `keys` is the discrete logarithm (over `G`) of each entry of the signing ring member, the
+  /// last being for the non-linkable layer. The key images are `key * Point::biased_hash(entry)`
+  /// for each linkable entry, and must be bound to within `msg` by the caller.
+  ///
+  /// MLSAGs are only used by historical `RctType`s. This is intended for generating test fixtures
+  /// and must not be used to create new transactions. This function runs in variable time.
+  ///
+  /// WARNING: This follows the Fiat-Shamir transcript format used by the Monero protocol, which
+  /// makes assumptions on what has already been transcripted and bound to within `msg`. Do not use
+  /// this if you don't know what you're doing.
+  #[cfg(feature = "sign")]
+  pub fn sign<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    msg: &[u8; 32],
+    ring: &RingMatrix,
+    signer_index: usize,
+    keys: &[Zeroizing<Scalar>],
+  ) -> Result<Mlsag, MlsagError> {
+    if signer_index >= ring.members() {
+      Err(MlsagError::InvalidRing)?;
+    }
+    if keys.len() != ring.member_len() {
+      Err(MlsagError::InvalidKey)?;
+    }
+
+    let signer = &ring.matrix[signer_index];
+    let mut key_images = Vec::with_capacity(keys.len() - 1);
+    for (j, (entry, key)) in signer.iter().zip(keys).enumerate() {
+      let key = Zeroizing::new((**key).into());
+      if EdwardsPoint::mul_base(&key) != *entry {
+        Err(MlsagError::InvalidKey)?;
+      }
+      if j != (keys.len() - 1) {
+        key_images.push(*key * Point::biased_hash(entry.compress().to_bytes()).into());
+      }
+    }
+
+    let mut buf = Vec::with_capacity(6 * 32);
+    buf.extend_from_slice(msg);
+    let challenge = |buf: &mut Vec<u8>| {
+      let c = Scalar::hash(&*buf).into();
+      buf.drain(msg.len() ..);
+      c
+    };
+
+    // Commit to the nonces for the signing ring member
+    let nonces = (0 .. keys.len())
+      .map(|_| Zeroizing::new(Scalar::random(&mut *rng).into()))
+      .collect::<Vec<Zeroizing<curve25519_dalek::Scalar>>>();
+    for (j, (entry, nonce)) in signer.iter().zip(&nonces).enumerate() {
+      let compressed_entry = entry.compress();
+      buf.extend_from_slice(compressed_entry.as_bytes());
+      buf.extend_from_slice(EdwardsPoint::mul_base(nonce).compress().as_bytes());
+      if j < key_images.len() {
+        #[allow(non_snake_case)]
+        let R = **nonce * Point::biased_hash(compressed_entry.to_bytes()).into();
+        buf.extend_from_slice(R.compress().as_bytes());
+      }
+    }
+    let mut ci = challenge(&mut buf);
+
+    // Walk the rest of the ring, as the verifier will, with random responses
+    let mut ss = vec![vec![]; ring.members()];
+    let mut cc = ci;
+    let mut i = (signer_index + 1) % ring.members();
+    while i != signer_index {
+      if i == 0 {
+        cc = ci;
+      }
+      for (j, ring_member_entry) in ring.matrix[i].iter().enumerate() {
+        let s = Scalar::random(&mut *rng);
+        #[allow(non_snake_case)]
+        let L =
+          EdwardsPoint::vartime_double_scalar_mul_basepoint(&ci, ring_member_entry, &s.into());
+        let compressed_ring_member_entry = ring_member_entry.compress();
+        buf.extend_from_slice(compressed_ring_member_entry.as_bytes());
+        buf.extend_from_slice(L.compress().as_bytes());
+        if let Some(ki) = key_images.get(j) {
+          #[allow(non_snake_case)]
+          let R = (s.into() * Point::biased_hash(compressed_ring_member_entry.to_bytes()).into()) +
+            (ci * ki);
+          buf.extend_from_slice(R.compress().as_bytes());
+        }
+        ss[i].push(s);
+      }
+      ci = challenge(&mut buf);
+      i = (i + 1) % ring.members();
+    }
+    if signer_index == 0 {
+      cc = ci;
+    }
+
+    // Close the ring with the responses for the signing ring member
+    ss[signer_index] = nonces
+      .iter()
+      .zip(keys)
+      .map(|(nonce, key)| Scalar::from(**nonce - (ci * (**key).into())))
+      .collect();
+
+    let mlsag = Mlsag { ss, cc: Scalar::from(cc) };
+    debug_assert!(mlsag
+      .verify(
+        msg,
+        ring,
+        &key_images.iter().map(|ki| Point::from(*ki).compress()).collect::<Vec<_>>()
+      )
+      .is_ok());
+    Ok(mlsag)
+  }
 }
 
 /// Builder for a RingMatrix when using an aggregate signature.
@@ -6,6 +6,7 @@ use monero_address::{Network, MoneroAddress};
 // monero-rpc doesn't include a transport
 // We can't include the simple-request crate there as then we'd have a cyclical dependency
 // Accordingly, we test monero-rpc here (implicitly testing the simple-request transport)
+use monero_rpc::RpcError;
 use monero_simple_request_rpc::*;
 
 static SEQUENTIAL: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
@@ -39,6 +40,10 @@ async fn test_rpc() {
 
     // And finally the hardfork version route
     assert_eq!(rpc.get_hardfork_version().await.unwrap(), block.header.hardfork_version);
+
+    // The node should be for mainnet, as regtest uses mainnet's genesis block
+    rpc.check_network(Network::Mainnet).await.unwrap();
+    assert!(matches!(rpc.check_network(Network::Stagenet).await, Err(RpcError::WrongNetwork)));
   }
 
   // Test generate_blocks
@@ -111,6 +116,37 @@ async fn test_decoy_rpc() {
     rpc.get_output_distribution(1 .. 0).await.unwrap_err();
   }
 
+  // Test the per-amount routes are consistent with the RingCT routes
+  {
+    use monero_oxide::{ed25519::CompressedPoint, transaction::Input};
+
+    let distribution_len = rpc.get_output_distribution_end_height().await.unwrap();
+    let distribution = rpc.get_output_distribution(.. distribution_len).await.unwrap();
+    assert_eq!(
+      rpc.get_output_distributions(&[0], .. distribution_len).await.unwrap(),
+      vec![distribution.clone()]
+    );
+
+    let histogram = rpc.get_output_histogram(&[0]).await.unwrap();
+    assert_eq!(histogram.len(), 1);
+    assert_eq!(histogram[0].amount, 0);
+    assert_eq!(histogram[0].total_instances, *distribution.last().unwrap());
+    assert!(rpc.get_output_histogram(&[]).await.unwrap().is_empty());
+
+    let outs = rpc.get_outs(&[0, 1, 2]).await.unwrap();
+    assert_eq!(rpc.get_outs_from_pools(&[(0, 0), (0, 1), (0, 2)]).await.unwrap(), outs);
+    let rings = rpc
+      .get_rings(&[
+        Input::ToKey { amount: None, key_offsets: vec![0, 1], key_image: CompressedPoint::G },
+        Input::ToKey { amount: None, key_offsets: vec![2], key_image: CompressedPoint::G },
+      ])
+      .await
+      .unwrap();
+    let ring_member = |i: usize| [outs[i].key, outs[i].commitment.compress()];
+    assert_eq!(rings, vec![vec![ring_member(0), ring_member(1)], vec![ring_member(2)]]);
+    rpc.get_rings(&[Input::Gen(0)]).await.unwrap_err();
+  }
+
   drop(guard);
 }
 
@@ -26,9 +26,10 @@ use monero_oxide::{
   ed25519::{CompressedPoint, Point},
   transaction::{Input, Timelock, Pruned, Transaction},
   block::Block,
+  genesis::genesis_hash,
   DEFAULT_LOCK_WINDOW,
 };
-use monero_address::Address;
+use monero_address::{Network, Address};
 
 /// 手数料推定が有効と見なされるブロック数。
 ///
@@ -87,6 +88,11 @@ pub enum RpcError {
   /// 英語原文: The priority intended for use wasn't usable.
   #[error("invalid priority")]
   InvalidPriority,
+  /// ノードが期待とは異なるネットワークのブロックチェーンを提供していた場合のエラー。
+  ///
+  /// 英語原文: The node served the blockchain of a different network than expected.
+  #[error("node served a different network")]
+  WrongNetwork,
 }
 
 /// スキャン可能なブロックを表す構造体。
@@ -107,6 +113,14 @@ pub struct ScannableBlock {
   /// 補足: RingCT 出力が存在しない場合は `None`、存在する場合は `Some` を返します。
   /// 英語原文: The output index for the first RingCT output within this block.
   pub output_index_for_first_ringct_output: Option<u64>,
+  /// ブロック内の各バージョン 1（RingCT 以前）トランザクションの出力インデックス。
+  ///
+  /// 補足: マイナートランザクションを先頭とするブロック内の順序で、バージョン 1 トランザクション
+  /// ごとに一つの要素を持ちます。各インデックスは同じ金額の出力プール内でのインデックスです。
+  /// 英語原文: The output indexes for the outputs of each version 1 (pre-RingCT) transaction within
+  /// this block, in order (with the miner transaction first). These indexes are within the pool of
+  /// outputs with the same amount.
+  pub pre_ringct_output_indexes: Vec<Vec<u64>>,
 }
 
 /// 手数料レートを表す構造体。
@@ -280,22 +294,42 @@ pub struct OutputInformation {
   pub transaction: [u8; 32],
 }
 
-fn rpc_hex(value: &str) -> Result<Vec<u8>, RpcError> {
-  hex::decode(value).map_err(|_| RpcError::InvalidNode("expected hex wasn't hex".to_string()))
+/// ある金額の出力プールに関するヒストグラムの要素。
+///
+/// 英語原文: An entry within the histogram of the pools of outputs, for a specific amount.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+pub struct OutputHistogramEntry {
+  /// この要素が対象とするプールの金額。
+  ///
+  /// 補足: RingCT 出力のプールは金額 `0` です。
+  pub amount: u64,
+  /// このプール内の出力の総数。
+  ///
+  /// 英語原文: The total amount of outputs within this pool.
+  pub total_instances: u64,
+  /// ノードのローカルビューに基づく、このプール内のアンロック済み出力の数。
+  ///
+  /// 英語原文: The amount of unlocked outputs within this pool, per the node's local view.
+  pub unlocked_instances: u64,
 }
 
 /// 文字列として渡された16進表現をデコードしてバイト列に変換します。
 ///
 /// 英語原文: helper to decode RPC hex strings into bytes.
-
-fn hash_hex(hash: &str) -> Result<[u8; 32], RpcError> {
-  rpc_hex(hash)?.try_into().map_err(|_| RpcError::InvalidNode("hash wasn't 32-bytes".to_string()))
+fn rpc_hex(value: &str) -> Result<Vec<u8>, RpcError> {
+  hex::decode(value).map_err(|_| RpcError::InvalidNode("expected hex wasn't hex".to_string()))
 }
 
 /// 16進表現のハッシュ文字列を `[u8; 32]` に変換します。
 ///
 /// 英語原文: helper to convert hex hash into 32-byte array.
+fn hash_hex(hash: &str) -> Result<[u8; 32], RpcError> {
+  rpc_hex(hash)?.try_into().map_err(|_| RpcError::InvalidNode("hash wasn't 32-bytes".to_string()))
+}
 
+/// RPC から渡された圧縮点（16進文字列）を `Point` に復元（decompress）します。
+///
+/// 英語原文: helper to parse a point from RPC-provided hex string and decompress it.
 fn rpc_point(point: &str) -> Result<Point, RpcError> {
   CompressedPoint::from(
     <[u8; 32]>::try_from(rpc_hex(point)?)
@@ -305,10 +339,6 @@ fn rpc_point(point: &str) -> Result<Point, RpcError> {
   .ok_or_else(|| RpcError::InvalidNode(format!("invalid point: {point}")))
 }
 
-/// RPC から渡された圧縮点（16進文字列）を `Point` に復元（decompress）します。
-///
-/// 英語原文: helper to parse a point from RPC-provided hex string and decompress it.
-
 /// An RPC connection to a Monero daemon.
 ///
 /// This is abstract such that users can use an HTTP library (which being their choice), a
@@ -613,6 +643,18 @@ pub trait Rpc: Sync + Clone {
     }
   }
 
+  /// Check the node serves the blockchain of the specified network.
+  ///
+  /// This compares the hash of the node's genesis block to the expected genesis block's hash.
+  fn check_network(&self, network: Network) -> impl Send + Future<Output = Result<(), RpcError>> {
+    async move {
+      if self.get_block_hash(0).await? != genesis_hash(network) {
+        Err(RpcError::WrongNetwork)?;
+      }
+      Ok(())
+    }
+  }
+
   /// Get a block from the node by its hash.
   ///
   /// The received block will be hashed in order to verify the correct block was returned.
@@ -688,12 +730,13 @@ pub trait Rpc: Sync + Clone {
         should be within the miner transaction. Then, as we scan transactions, we update the output
         index ourselves.
 
-        Please note we only will scan RingCT outputs so we only need to track the RingCT output
-        index. This decision was made due to spending CN outputs potentially having burdensome
-        requirements (the need to make a v1 TX due to insufficient decoys).
+        Outputs created by v1 transactions are indexed within the pool of outputs sharing their
+        amount, so their indexes can't be derived from the index of the first RingCT output. For
+        these, we request the output indexes of every v1 transaction. v1 transactions were only
+        allowed prior to hard fork 6, so this doesn't impact the scanning of modern blocks.
 
-        We bound ourselves to only scanning RingCT outputs by only scanning v2 transactions. This is
-        safe and correct since:
+        We only need to track the RingCT output index across v2 transactions. This is safe and
+        correct since:
 
         1) v1 transactions cannot create RingCT outputs.
 
@@ -717,15 +760,29 @@ pub trait Rpc: Sync + Clone {
              /src/cryptonote_core/blockchain.cpp#L3417
       */
 
-      // Get the index for the first output
+      // Get the index for the first RingCT output, and the indexes of all pre-RingCT outputs
       let mut output_index_for_first_ringct_output = None;
+      let mut pre_ringct_output_indexes = vec![];
       let miner_tx_hash = block.miner_transaction().hash();
       let miner_tx = Transaction::<Pruned>::from(block.miner_transaction().clone());
       for (hash, tx) in core::iter::once((&miner_tx_hash, &miner_tx))
         .chain(block.transactions.iter().zip(&transactions))
       {
-        // If this isn't a RingCT output, or there are no outputs, move to the next TX
-        if (!matches!(tx, Transaction::V2 { .. })) || tx.prefix().outputs.is_empty() {
+        let outputs = tx.prefix().outputs.len();
+
+        if matches!(tx, Transaction::V1 { .. }) {
+          let indexes = if outputs == 0 { vec![] } else { self.get_o_indexes(*hash).await? };
+          if indexes.len() != outputs {
+            Err(RpcError::InvalidNode(
+              "requested output indexes for a TX and got a distinct amount of indexes".to_string(),
+            ))?;
+          }
+          pre_ringct_output_indexes.push(indexes);
+          continue;
+        }
+
+        // If there are no outputs, or we already have the index, move to the next TX
+        if (outputs == 0) || output_index_for_first_ringct_output.is_some() {
           continue;
         }
 
@@ -735,10 +792,14 @@ pub trait Rpc: Sync + Clone {
           )
         })?;
         output_index_for_first_ringct_output = Some(index);
-        break;
       }
 
-      Ok(ScannableBlock { block, transactions, output_index_for_first_ringct_output })
+      Ok(ScannableBlock {
+        block,
+        transactions,
+        output_index_for_first_ringct_output,
+        pre_ringct_output_indexes,
+      })
     }
   }
 
@@ -1086,7 +1147,7 @@ pub trait Rpc: Sync + Clone {
   }
 }
 
-/// A trait for any object which can be used to select RingCT decoys.
+/// A trait for any object which can be used to select decoys.
 ///
 /// An implementation is provided for any satisfier of `Rpc`. It is not recommended to use an `Rpc`
 /// object to satisfy this. This should be satisfied by a local store of the output distribution,
@@ -1100,23 +1161,38 @@ pub trait DecoyRpc: Sync {
     &self,
   ) -> impl Send + Future<Output = Result<usize, RpcError>>;
 
-  /// Get the RingCT (zero-amount) output distribution.
+  /// Get the histogram of the pools of outputs with the specified amounts.
   ///
-  /// `range` is in terms of block numbers. The result may be smaller than the requested range if
-  /// the range starts before RingCT outputs were created on-chain.
-  fn get_output_distribution(
+  /// The RingCT outputs are within the pool for the amount `0`. All other pools are of the
+  /// pre-RingCT outputs, whose amounts were in the clear.
+  ///
+  /// One entry is returned for each requested amount, in the order requested.
+  fn get_output_histogram(
     &self,
+    amounts: &[u64],
+  ) -> impl Send + Future<Output = Result<Vec<OutputHistogramEntry>, RpcError>>;
+
+  /// Get the output distributions for the pools of outputs with the specified amounts.
+  ///
+  /// One distribution is returned for each requested amount, in the order requested.
+  ///
+  /// `range` is in terms of block numbers. Each result may be smaller than the requested range if
+  /// the range starts before outputs of its amount were created on-chain.
+  fn get_output_distributions(
+    &self,
+    amounts: &[u64],
     range: impl Send + RangeBounds<usize>,
-  ) -> impl Send + Future<Output = Result<Vec<u64>, RpcError>>;
+  ) -> impl Send + Future<Output = Result<Vec<Vec<u64>>, RpcError>>;
 
-  /// Get the specified outputs from the RingCT (zero-amount) pool.
-  fn get_outs(
+  /// Get the specified outputs, each specified as its amount and its index within the pool of
+  /// outputs with that amount.
+  fn get_outs_from_pools(
     &self,
-    indexes: &[u64],
+    outputs: &[(u64, u64)],
   ) -> impl Send + Future<Output = Result<Vec<OutputInformation>, RpcError>>;
 
-  /// Get the specified outputs from the RingCT (zero-amount) pool, but only return them if their
-  /// timelock has been satisfied.
+  /// Get the specified outputs from the pool of outputs with the specified amount, but only
+  /// return them if their timelock has been satisfied.
   ///
   /// The timelock being satisfied is distinct from being free of the 10-block lock applied to all
   /// Monero transactions.
@@ -1126,12 +1202,118 @@ pub trait DecoyRpc: Sync {
   /// used, yet the transaction's timelock is checked to be unlocked at the specified `height`.
   /// This offers a deterministic decoy selection, yet is fingerprintable as time-based timelocks
   /// aren't evaluated (and considered locked, preventing their selection).
-  fn get_unlocked_outputs(
+  fn get_unlocked_outputs_for_amount(
     &self,
+    amount: u64,
     indexes: &[u64],
     height: usize,
     fingerprintable_deterministic: bool,
   ) -> impl Send + Future<Output = Result<Vec<Option<[Point; 2]>>, RpcError>>;
+
+  /// Get the output distribution for the pool of outputs with the specified amount.
+  ///
+  /// `range` is in terms of block numbers. The result may be smaller than the requested range if
+  /// the range starts before outputs of this amount were created on-chain.
+  fn get_output_distribution_for_amount(
+    &self,
+    amount: u64,
+    range: impl Send + RangeBounds<usize>,
+  ) -> impl Send + Future<Output = Result<Vec<u64>, RpcError>> {
+    async move {
+      self.get_output_distributions(&[amount], range).await?.pop().ok_or_else(|| {
+        RpcError::InvalidNode("requested an output distribution and got none".to_string())
+      })
+    }
+  }
+
+  /// Get the specified outputs from the pool of outputs with the specified amount.
+  fn get_outs_for_amount(
+    &self,
+    amount: u64,
+    indexes: &[u64],
+  ) -> impl Send + Future<Output = Result<Vec<OutputInformation>, RpcError>> {
+    async move {
+      self
+        .get_outs_from_pools(&indexes.iter().map(|index| (amount, *index)).collect::<Vec<_>>())
+        .await
+    }
+  }
+
+  /// Get the rings used by the specified inputs.
+  ///
+  /// Each ring member is represented as `[output key, commitment]`, as expected by
+  /// `Transaction::verify`. For pre-RingCT outputs, the commitment is the commitment to the
+  /// output's amount with a mask of `1`, as Monero defines. The ring members for all inputs are
+  /// fetched with a single call to `get_outs_from_pools`.
+  fn get_rings(
+    &self,
+    inputs: &[Input],
+  ) -> impl Send + Future<Output = Result<Vec<Vec<[CompressedPoint; 2]>>, RpcError>> {
+    async move {
+      let mut outputs = vec![];
+      let mut ring_lens = Vec::with_capacity(inputs.len());
+      for input in inputs {
+        let Input::ToKey { amount, key_offsets, .. } = input else {
+          Err(RpcError::InternalError("requested the ring of a miner transaction".to_string()))?
+        };
+        // Convert the offsets to the positions of the ring members within their pool
+        let mut index = 0u64;
+        for offset in key_offsets {
+          index = index.checked_add(*offset).ok_or_else(|| {
+            RpcError::InternalError("ring member's index exceeded u64::MAX".to_string())
+          })?;
+          outputs.push((amount.unwrap_or(0), index));
+        }
+        ring_lens.push(key_offsets.len());
+      }
+
+      let outs = self.get_outs_from_pools(&outputs).await?;
+      if outs.len() != outputs.len() {
+        Err(RpcError::InvalidNode("get_outs response omitted requested outputs".to_string()))?;
+      }
+      let mut outs = outs.into_iter();
+      Ok(
+        ring_lens
+          .into_iter()
+          .map(|ring_len| {
+            outs.by_ref().take(ring_len).map(|out| [out.key, out.commitment.compress()]).collect()
+          })
+          .collect(),
+      )
+    }
+  }
+
+  /// Get the RingCT (zero-amount) output distribution.
+  ///
+  /// `range` is in terms of block numbers. The result may be smaller than the requested range if
+  /// the range starts before RingCT outputs were created on-chain.
+  fn get_output_distribution(
+    &self,
+    range: impl Send + RangeBounds<usize>,
+  ) -> impl Send + Future<Output = Result<Vec<u64>, RpcError>> {
+    self.get_output_distribution_for_amount(0, range)
+  }
+
+  /// Get the specified outputs from the RingCT (zero-amount) pool.
+  fn get_outs(
+    &self,
+    indexes: &[u64],
+  ) -> impl Send + Future<Output = Result<Vec<OutputInformation>, RpcError>> {
+    self.get_outs_for_amount(0, indexes)
+  }
+
+  /// Get the specified outputs from the RingCT (zero-amount) pool, but only return them if their
+  /// timelock has been satisfied.
+  ///
+  /// This is equivalent to `get_unlocked_outputs_for_amount` with an amount of `0`.
+  fn get_unlocked_outputs(
+    &self,
+    indexes: &[u64],
+    height: usize,
+    fingerprintable_deterministic: bool,
+  ) -> impl Send + Future<Output = Result<Vec<Option<[Point; 2]>>, RpcError>> {
+    self.get_unlocked_outputs_for_amount(0, indexes, height, fingerprintable_deterministic)
+  }
 }
 
 impl<R: Rpc> DecoyRpc for R {
@@ -1141,13 +1323,62 @@ impl<R: Rpc> DecoyRpc for R {
     async move { <Self as Rpc>::get_height(self).await }
   }
 
-  fn get_output_distribution(
+  fn get_output_histogram(
+    &self,
+    amounts: &[u64],
+  ) -> impl Send + Future<Output = Result<Vec<OutputHistogramEntry>, RpcError>> {
+    async move {
+      #[derive(Debug, Deserialize)]
+      struct HistogramResponse {
+        histogram: Option<Vec<OutputHistogramEntry>>,
+        status: String,
+      }
+
+      // Requesting no amounts would return the histogram for every amount
+      if amounts.is_empty() {
+        return Ok(vec![]);
+      }
+
+      let res: HistogramResponse = self
+        .json_rpc_call(
+          "get_output_histogram",
+          Some(json!({
+            "amounts": amounts,
+            // With a minimum count of 0, every requested amount will be present
+            "min_count": 0,
+            "max_count": 0,
+            "unlocked": true,
+            "recent_cutoff": 0,
+          })),
+        )
+        .await?;
+
+      if res.status != "OK" {
+        Err(RpcError::InvalidNode("bad response to get_output_histogram".to_string()))?;
+      }
+
+      // The node returns the histogram sorted by amount, without duplicates
+      let histogram = res.histogram.unwrap_or(vec![]);
+      amounts
+        .iter()
+        .map(|amount| {
+          histogram.iter().find(|entry| entry.amount == *amount).copied().ok_or_else(|| {
+            RpcError::InvalidNode("get_output_histogram response omitted an amount".to_string())
+          })
+        })
+        .collect()
+    }
+  }
+
+  fn get_output_distributions(
     &self,
+    amounts: &[u64],
     range: impl Send + RangeBounds<usize>,
-  ) -> impl Send + Future<Output = Result<Vec<u64>, RpcError>> {
+  ) -> impl Send + Future<Output = Result<Vec<Vec<u64>>, RpcError>> {
     async move {
-      #[derive(Default, Debug, Deserialize)]
+      #[derive(Debug, Deserialize)]
       struct Distribution {
+        amount: u64,
         distribution: Vec<u64>,
         // A blockchain with just its genesis block has a height of 1
         start_height: usize,
@@ -1155,7 +1386,7 @@ impl<R: Rpc> DecoyRpc for R {
 
       #[derive(Debug, Deserialize)]
       struct Distributions {
-        distributions: [Distribution; 1],
+        distributions: Vec<Distribution>,
         status: String,
       }
 
@@ -1185,7 +1416,7 @@ impl<R: Rpc> DecoyRpc for R {
           "get_output_distribution",
           Some(json!({
             "binary": false,
-            "amounts": [0],
+            "amounts": amounts,
             "cumulative": true,
             // These are actually block numbers, not heights
             "from_height": from,
@@ -1200,66 +1431,83 @@ impl<R: Rpc> DecoyRpc for R {
         ))?;
       }
 
-      let mut distributions = distributions.distributions;
-      let Distribution { start_height, mut distribution } = core::mem::take(&mut distributions[0]);
-      // start_height is also actually a block number, and it should be at least `from`
-      // It may be after depending on when these outputs first appeared on the blockchain
-      // Unfortunately, we can't validate without a binary search to find the RingCT activation
-      // block and an iterative search from there, so we solely sanity check it
-      if start_height < from {
-        Err(RpcError::InvalidNode(format!(
-          "requested distribution from {from} and got from {start_height}"
-        )))?;
-      }
-      // It shouldn't be after `to` though
-      if start_height > to {
-        Err(RpcError::InvalidNode(format!(
-          "requested distribution to {to} and got from {start_height}"
-        )))?;
-      }
-
-      let expected_len = if zero_zero_case {
-        2
-      } else {
-        (to - start_height).checked_add(1).ok_or_else(|| {
-          RpcError::InternalError("expected length of distribution exceeded usize".to_string())
-        })?
-      };
-      // Yet this is actually a height
-      if expected_len != distribution.len() {
-        Err(RpcError::InvalidNode(format!(
-          "distribution length ({}) wasn't of the requested length ({})",
-          distribution.len(),
-          expected_len
-        )))?;
-      }
-      // Requesting to = 0 returns the distribution for the entire chain
-      // We work around this by requesting 0, 1 (yielding two blocks), then popping the second
-      // block
-      if zero_zero_case {
-        distribution.pop();
+      if distributions.distributions.len() != amounts.len() {
+        Err(RpcError::InvalidNode(
+          "requested output distributions and got a distinct amount of distributions".to_string(),
+        ))?;
       }
 
-      // Check the distribution monotonically increases
+      let mut res = Vec::with_capacity(amounts.len());
+      for (amount, Distribution { amount: actual_amount, start_height, mut distribution }) in
+        amounts.iter().zip(distributions.distributions)
       {
-        let mut monotonic = 0;
-        for d in &distribution {
-          if *d < monotonic {
-            Err(RpcError::InvalidNode(
-              "received output distribution didn't increase monotonically".to_string(),
-            ))?;
+        if *amount != actual_amount {
+          Err(RpcError::InvalidNode(format!(
+            "requested distribution for {amount} and got distribution for {actual_amount}"
+          )))?;
+        }
+
+        // start_height is also actually a block number, and it should be at least `from`
+        // It may be after depending on when these outputs first appeared on the blockchain
+        // Unfortunately, we can't validate without a binary search to find the RingCT activation
+        // block and an iterative search from there, so we solely sanity check it
+        if start_height < from {
+          Err(RpcError::InvalidNode(format!(
+            "requested distribution from {from} and got from {start_height}"
+          )))?;
+        }
+        // It shouldn't be after `to` though
+        if start_height > to {
+          Err(RpcError::InvalidNode(format!(
+            "requested distribution to {to} and got from {start_height}"
+          )))?;
+        }
+
+        let expected_len = if zero_zero_case {
+          2
+        } else {
+          (to - start_height).checked_add(1).ok_or_else(|| {
+            RpcError::InternalError("expected length of distribution exceeded usize".to_string())
+          })?
+        };
+        // Yet this is actually a height
+        if expected_len != distribution.len() {
+          Err(RpcError::InvalidNode(format!(
+            "distribution length ({}) wasn't of the requested length ({})",
+            distribution.len(),
+            expected_len
+          )))?;
+        }
+        // Requesting to = 0 returns the distribution for the entire chain
+        // We work around this by requesting 0, 1 (yielding two blocks), then popping the second
+        // block
+        if zero_zero_case {
+          distribution.pop();
+        }
+
+        // Check the distribution monotonically increases
+        {
+          let mut monotonic = 0;
+          for d in &distribution {
+            if *d < monotonic {
+              Err(RpcError::InvalidNode(
+                "received output distribution didn't increase monotonically".to_string(),
+              ))?;
+            }
+            monotonic = *d;
           }
-          monotonic = *d;
         }
+
+        res.push(distribution);
       }
 
-      Ok(distribution)
+      Ok(res)
     }
   }
 
-  fn get_outs(
+  fn get_outs_from_pools(
     &self,
-    indexes: &[u64],
+    outputs: &[(u64, u64)],
   ) -> impl Send + Future<Output = Result<Vec<OutputInformation>, RpcError>> {
     async move {
       #[derive(Debug, Deserialize)]
@@ -1281,15 +1529,15 @@ impl<R: Rpc> DecoyRpc for R {
       //   /src/rpc/core_rpc_server.cpp#L67
       const MAX_OUTS: usize = 5000;
 
-      let mut res = Vec::with_capacity(indexes.len());
-      for indexes in indexes.chunks(MAX_OUTS) {
+      let mut res = Vec::with_capacity(outputs.len());
+      for outputs in outputs.chunks(MAX_OUTS) {
         let rpc_res: OutsResponse = self
           .rpc_call(
             "get_outs",
             Some(json!({
               "get_txid": true,
-              "outputs": indexes.iter().map(|o| json!({
-                "amount": 0,
+              "outputs": outputs.iter().map(|(amount, o)| json!({
+                "amount": amount,
                 "index": o
               })).collect::<Vec<_>>()
             })),
@@ -1300,7 +1548,7 @@ impl<R: Rpc> DecoyRpc for R {
           Err(RpcError::InvalidNode("bad response to get_outs".to_string()))?;
         }
 
-        if rpc_res.outs.len() != indexes.len() {
+        if rpc_res.outs.len() != outputs.len() {
           Err(RpcError::InvalidNode("get_outs response omitted requested outputs".to_string()))?;
         }
 
@@ -1328,14 +1576,15 @@ impl<R: Rpc> DecoyRpc for R {
     }
   }
 
-  fn get_unlocked_outputs(
+  fn get_unlocked_outputs_for_amount(
     &self,
+    amount: u64,
     indexes: &[u64],
     height: usize,
     fingerprintable_deterministic: bool,
   ) -> impl Send + Future<Output = Result<Vec<Option<[Point; 2]>>, RpcError>> {
     async move {
-      let outs = self.get_outs(indexes).await?;
+      let outs = self.get_outs_for_amount(amount, indexes).await?;
 
       // Only need to fetch txs to do deterministic check on timelock
       let txs = if fingerprintable_deterministic {
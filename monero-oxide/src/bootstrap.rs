@@ -0,0 +1,243 @@
+// monerod の `blockchain.raw` ブートストラップ形式（`monero-blockchain-export` が出力し、
+// `monero-blockchain-import` が読み込む形式）の読み書き。
+// ファイルはマジック値、固定長のヘッダ、そして長さプレフィックス付きのチャンクの列からなり、
+// 各チャンクはブロックとそのトランザクションを 1 つずつ含みます。
+use std_shims::{
+  vec,
+  vec::Vec,
+  io::{self, Read, Write},
+};
+
+use crate::{io::*, transaction::Transaction, block::Block};
+
+/// The magic which prefixes a bootstrap file.
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/blockchain_utilities/blockchain_utilities.h#L33
+pub const BLOCKCHAIN_RAW_MAGIC: u32 = 0x28721586;
+
+/// The size of the header following the magic, as written by monerod.
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/blockchain_utilities/bootstrap_file.h#L50
+pub const HEADER_SIZE: u32 = 1024;
+
+/// The maximum size of a chunk monerod will import.
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/blockchain_utilities/bootstrap_file.h#L48
+pub const MAX_CHUNK_SIZE: u32 = 1_000_000;
+
+// The version of the file format written by monerod
+const MAJOR_VERSION: u8 = 0;
+const MINOR_VERSION: u8 = 1;
+
+// Check the transactions are the ones committed to by the block, in order
+fn check_transactions(block: &Block, transactions: &[Transaction]) -> io::Result<()> {
+  if (block.transactions.len() != transactions.len()) ||
+    block.transactions.iter().zip(transactions).any(|(hash, tx)| *hash != tx.hash())
+  {
+    Err(io::Error::other("transactions weren't those within the block"))?;
+  }
+  Ok(())
+}
+
+/// A block, its transactions, and the metadata stored alongside them in a bootstrap file.
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/blockchain_utilities/bootstrap_serialization.h#L142-L159
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BlockPackage {
+  /// The block.
+  pub block: Block,
+  /// The transactions within the block (excluding the miner transaction), in order.
+  pub transactions: Vec<Transaction>,
+  /// The block's weight.
+  pub block_weight: usize,
+  /// The cumulative difficulty of the blockchain as of this block.
+  pub cumulative_difficulty: u128,
+  /// The amount of coins generated as of this block.
+  pub coins_generated: u64,
+}
+
+impl BlockPackage {
+  /// Write the BlockPackage.
+  ///
+  /// This will error if the transactions aren't those within the block.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    check_transactions(&self.block, &self.transactions)?;
+    self.block.write(w)?;
+    write_vec(Transaction::write, &self.transactions, w)?;
+    VarInt::write(&self.block_weight, w)?;
+    // `difficulty_type` is serialized as the VarInts of its high and low 64 bits
+    // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+    //   /src/serialization/difficulty_type.h#L38-L67
+    VarInt::write(&u64::try_from(self.cumulative_difficulty >> 64).unwrap(), w)?;
+    VarInt::write(&u64::try_from(self.cumulative_difficulty & u128::from(u64::MAX)).unwrap(), w)?;
+    VarInt::write(&self.coins_generated, w)
+  }
+
+  /// Serialize the BlockPackage to a `Vec<u8>`.
+  pub fn serialize(&self) -> io::Result<Vec<u8>> {
+    let mut serialized = vec![];
+    self.write(&mut serialized)?;
+    Ok(serialized)
+  }
+
+  /// Read a BlockPackage.
+  ///
+  /// This will error if the transactions aren't those within the block.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<BlockPackage> {
+    let block = Block::read(r)?;
+    let transactions = read_vec(Transaction::read, Some(block.transactions.len()), r)?;
+    check_transactions(&block, &transactions)?;
+    let block_weight = VarInt::read(r)?;
+    let cumulative_difficulty = {
+      let high: u64 = VarInt::read(r)?;
+      let low: u64 = VarInt::read(r)?;
+      (u128::from(high) << 64) | u128::from(low)
+    };
+    let coins_generated = VarInt::read(r)?;
+    Ok(BlockPackage { block, transactions, block_weight, cumulative_difficulty, coins_generated })
+  }
+}
+
+/// A reader for a bootstrap file, yielding its blocks in order.
+///
+/// The iterator yields the blocks and their transactions. Use
+/// [`BootstrapReader::read_package`] to additionally obtain the metadata stored with each block.
+/// After an error, the position within the underlying reader is unspecified.
+#[derive(Debug)]
+pub struct BootstrapReader<R: Read> {
+  reader: R,
+}
+
+impl<R: Read> BootstrapReader<R> {
+  /// Create a new reader, reading the magic and header of the bootstrap file.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/blockchain_utilities/bootstrap_file.cpp#L310-L358
+  pub fn new(mut reader: R) -> io::Result<Self> {
+    if read_u32(&mut reader)? != BLOCKCHAIN_RAW_MAGIC {
+      Err(io::Error::other("bootstrap file didn't start with the expected magic"))?;
+    }
+
+    // The header starts with the length-prefixed file info
+    let file_info_len = read_u32(&mut reader)?;
+    if file_info_len > (HEADER_SIZE - 4) {
+      Err(io::Error::other("bootstrap file's file info exceeded the header"))?;
+    }
+    let mut file_info = vec![0; usize::try_from(file_info_len).unwrap()];
+    reader.read_exact(&mut file_info)?;
+    let mut file_info = file_info.as_slice();
+    // A different major version would be a different format, which this can't read
+    if read_byte(&mut file_info)? != MAJOR_VERSION {
+      Err(io::Error::other("bootstrap file had an unsupported major version"))?;
+    }
+    let _minor_version = read_byte(&mut file_info)?;
+    let header_size: u32 = VarInt::read(&mut file_info)?;
+    if !file_info.is_empty() {
+      Err(io::Error::other("bootstrap file's file info had extra bytes after it"))?;
+    }
+
+    // Skip the rest of the header (the blocks info, which monerod doesn't populate, and padding)
+    let Some(mut to_skip) = header_size.checked_sub(4 + file_info_len) else {
+      Err(io::Error::other("bootstrap file's header was smaller than its file info"))?
+    };
+    let mut buf = [0; 256];
+    while to_skip != 0 {
+      let len = to_skip.min(256);
+      reader.read_exact(&mut buf[.. usize::try_from(len).unwrap()])?;
+      to_skip -= len;
+    }
+
+    Ok(BootstrapReader { reader })
+  }
+
+  /// Read the next block from the bootstrap file, with its metadata.
+  ///
+  /// Returns `None` once the end of the file is reached.
+  pub fn read_package(&mut self) -> io::Result<Option<BlockPackage>> {
+    // Only a lack of any bytes for the chunk size is the end of the file
+    let mut chunk_size = [0; 4];
+    if self.reader.read(&mut chunk_size[.. 1])? == 0 {
+      return Ok(None);
+    }
+    self.reader.read_exact(&mut chunk_size[1 ..])?;
+    let chunk_size = u32::from_le_bytes(chunk_size);
+    if chunk_size > MAX_CHUNK_SIZE {
+      Err(io::Error::other("bootstrap file's chunk exceeded the maximum chunk size"))?;
+    }
+
+    let mut chunk = vec![0; usize::try_from(chunk_size).unwrap()];
+    self.reader.read_exact(&mut chunk)?;
+    let mut chunk = chunk.as_slice();
+    let package = BlockPackage::read(&mut chunk)?;
+    if !chunk.is_empty() {
+      Err(io::Error::other("bootstrap file's chunk had extra bytes after its block"))?;
+    }
+    Ok(Some(package))
+  }
+
+  /// Consume this reader, returning the underlying reader.
+  pub fn into_inner(self) -> R {
+    self.reader
+  }
+}
+
+impl<R: Read> Iterator for BootstrapReader<R> {
+  type Item = io::Result<(Block, Vec<Transaction>)>;
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .read_package()
+      .transpose()
+      .map(|package| package.map(|BlockPackage { block, transactions, .. }| (block, transactions)))
+  }
+}
+
+/// A writer for a bootstrap file, as importable by `monero-blockchain-import`.
+#[derive(Debug)]
+pub struct BootstrapWriter<W: Write> {
+  writer: W,
+}
+
+impl<W: Write> BootstrapWriter<W> {
+  /// Create a new writer, writing the magic and header of the bootstrap file.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/blockchain_utilities/bootstrap_file.cpp#L101-L146
+  pub fn new(mut writer: W) -> io::Result<Self> {
+    writer.write_all(&BLOCKCHAIN_RAW_MAGIC.to_le_bytes())?;
+
+    let mut header = Vec::with_capacity(usize::try_from(HEADER_SIZE).unwrap());
+    {
+      let mut file_info = vec![MAJOR_VERSION, MINOR_VERSION];
+      VarInt::write(&HEADER_SIZE, &mut file_info)?;
+      header.extend(u32::try_from(file_info.len()).unwrap().to_le_bytes());
+      header.extend(file_info);
+    }
+    {
+      // monerod leaves the first block, last block, and last block's position as zero
+      let blocks_info = [0; 3];
+      header.extend(u32::try_from(blocks_info.len()).unwrap().to_le_bytes());
+      header.extend(blocks_info);
+    }
+    header.resize(usize::try_from(HEADER_SIZE).unwrap(), 0);
+    writer.write_all(&header)?;
+
+    Ok(BootstrapWriter { writer })
+  }
+
+  /// Write a block, with its metadata, as the next chunk of the bootstrap file.
+  ///
+  /// This will error if the transactions aren't those within the block or if the chunk would
+  /// exceed [`MAX_CHUNK_SIZE`].
+  pub fn write_package(&mut self, package: &BlockPackage) -> io::Result<()> {
+    let chunk = package.serialize()?;
+    let chunk_size = u32::try_from(chunk.len())
+      .ok()
+      .filter(|chunk_size| *chunk_size <= MAX_CHUNK_SIZE)
+      .ok_or_else(|| io::Error::other("block exceeded the maximum chunk size"))?;
+    self.writer.write_all(&chunk_size.to_le_bytes())?;
+    self.writer.write_all(&chunk)
+  }
+
+  /// Consume this writer, returning the underlying writer.
+  pub fn into_inner(self) -> W {
+    self.writer
+  }
+}
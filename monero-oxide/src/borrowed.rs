@@ -0,0 +1,477 @@
+// シリアライズ済みのトランザクション・ブロックに対する借用ビュー。
+// 読み取り時には構造（長さと `monero_primitives` の上限）のみを検証し、各フィールドは参照時に
+// 遅延的に解釈します。プレフィックス・入力・出力・extra の参照はアロケーションを行いません。
+use std_shims::io;
+
+use crate::{
+  io::*,
+  ed25519::CompressedPoint,
+  primitives::keccak256,
+  ringct::{RctType, bulletproofs::MAX_COMMITMENTS},
+  transaction::{Input, Output, Timelock, TransactionPrefix, NotPruned, Pruned, Transaction},
+  block::{BlockHeader, Block},
+};
+
+// The maximum length of a Bulletproof(+)'s L/R, as enforced by `Bulletproof::read`
+const MAX_LR: usize = (MAX_COMMITMENTS.ilog2() as usize) + 6;
+// The size of a BorromeanRange (s0, s1, ee, and the bit commitments)
+const BORROMEAN_RANGE_SIZE: usize = (64 * 32) + (64 * 32) + 32 + (64 * 32);
+
+// Advance the reader past the specified amount of bytes, returning them
+fn take<'a>(r: &mut &'a [u8], len: Option<usize>) -> io::Result<&'a [u8]> {
+  let len = len.ok_or_else(|| io::Error::other("length exceeded usize::MAX"))?;
+  if r.len() < len {
+    Err(io::Error::other("not enough bytes"))?;
+  }
+  let (bytes, rest) = r.split_at(len);
+  *r = rest;
+  Ok(bytes)
+}
+
+// The bytes consumed to advance `start` to `end`
+fn consumed<'a>(start: &'a [u8], end: &[u8]) -> &'a [u8] {
+  &start[.. (start.len() - end.len())]
+}
+
+/// The key offsets of an input, read from their serialization on demand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyOffsetsRef<'a> {
+  len: usize,
+  bytes: &'a [u8],
+}
+
+impl<'a> KeyOffsetsRef<'a> {
+  /// The amount of key offsets.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// If there are no key offsets.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Iterate over the key offsets.
+  pub fn iter(&self) -> impl ExactSizeIterator<Item = u64> + 'a {
+    let mut bytes = self.bytes;
+    (0 .. self.len).map(move |_| VarInt::read(&mut bytes).expect("key offsets weren't validated"))
+  }
+}
+
+/// A borrowed view of an input.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputRef<'a> {
+  /// An input for a miner transaction, which is generating new coins.
+  Gen(usize),
+  /// An input spending an output on-chain.
+  ToKey {
+    /// The pool this input spends an output of.
+    amount: Option<u64>,
+    /// The decoys used by this input's ring, specified as their offset distance from each other.
+    key_offsets: KeyOffsetsRef<'a>,
+    /// The key image (linking tag, nullifer) for the spent output.
+    key_image: CompressedPoint,
+  },
+}
+
+impl<'a> InputRef<'a> {
+  // This mirrors `Input::read`
+  fn read(r: &mut &'a [u8]) -> io::Result<Self> {
+    Ok(match read_byte(r)? {
+      255 => InputRef::Gen(VarInt::read(r)?),
+      2 => {
+        let amount = VarInt::read(r)?;
+        let amount = if amount == 0 { None } else { Some(amount) };
+
+        let len: usize = VarInt::read(r)?;
+        if len > Transaction::<NotPruned>::NON_MINER_SIZE_UPPER_BOUND.0 {
+          Err(io::Error::other("vector exceeds bound on length"))?;
+        }
+        let start = *r;
+        for _ in 0 .. len {
+          <u64 as VarInt>::read(r)?;
+        }
+        let key_offsets = KeyOffsetsRef { len, bytes: consumed(start, r) };
+
+        InputRef::ToKey { amount, key_offsets, key_image: CompressedPoint::read(r)? }
+      }
+      _ => Err(io::Error::other("Tried to deserialize unknown/unused input type"))?,
+    })
+  }
+
+  /// Convert this into an owned `Input`.
+  pub fn into_owned(self) -> Input {
+    match self {
+      InputRef::Gen(height) => Input::Gen(height),
+      InputRef::ToKey { amount, key_offsets, key_image } => {
+        Input::ToKey { amount, key_offsets: key_offsets.iter().collect(), key_image }
+      }
+    }
+  }
+}
+
+/// A borrowed view of a transaction's prefix.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransactionPrefixRef<'a> {
+  additional_timelock: Timelock,
+  inputs: usize,
+  inputs_bytes: &'a [u8],
+  outputs: usize,
+  outputs_bytes: &'a [u8],
+  rct_outputs: bool,
+  extra: &'a [u8],
+}
+
+impl<'a> TransactionPrefixRef<'a> {
+  // This mirrors `TransactionPrefix::read`, applying the same bounds
+  fn read(r: &mut &'a [u8], version: u64) -> io::Result<Self> {
+    let additional_timelock = Timelock::read(r)?;
+
+    let inputs: usize = VarInt::read(r)?;
+    if inputs > TransactionPrefix::INPUTS_UPPER_BOUND.0 {
+      Err(io::Error::other("vector exceeds bound on length"))?;
+    }
+    if inputs == 0 {
+      Err(io::Error::other("transaction had no inputs"))?;
+    }
+    let start = *r;
+    let is_miner_tx = matches!(InputRef::read(r)?, InputRef::Gen(_));
+    for _ in 1 .. inputs {
+      InputRef::read(r)?;
+    }
+    let inputs_bytes = consumed(start, r);
+
+    let outputs: usize = VarInt::read(r)?;
+    if (!is_miner_tx) && (outputs > TransactionPrefix::NON_MINER_OUTPUTS_UPPER_BOUND.0) {
+      Err(io::Error::other("vector exceeds bound on length"))?;
+    }
+    let rct_outputs = (!is_miner_tx) && (version == 2);
+    let start = *r;
+    for _ in 0 .. outputs {
+      Output::read(rct_outputs, r)?;
+    }
+    let outputs_bytes = consumed(start, r);
+
+    let extra: usize = VarInt::read(r)?;
+    if (!is_miner_tx) && (extra > Transaction::<NotPruned>::NON_MINER_SIZE_UPPER_BOUND.0) {
+      Err(io::Error::other("vector exceeds bound on length"))?;
+    }
+    let extra = take(r, Some(extra))?;
+
+    Ok(TransactionPrefixRef {
+      additional_timelock,
+      inputs,
+      inputs_bytes,
+      outputs,
+      outputs_bytes,
+      rct_outputs,
+      extra,
+    })
+  }
+
+  /// The timelock this transaction is additionally constrained by.
+  pub fn additional_timelock(&self) -> Timelock {
+    self.additional_timelock
+  }
+
+  /// Iterate over the inputs for this transaction.
+  pub fn inputs(&self) -> impl ExactSizeIterator<Item = InputRef<'a>> + 'a {
+    let mut bytes = self.inputs_bytes;
+    (0 .. self.inputs).map(move |_| InputRef::read(&mut bytes).expect("inputs weren't validated"))
+  }
+
+  /// Iterate over the outputs for this transaction.
+  pub fn outputs(&self) -> impl ExactSizeIterator<Item = Output> + 'a {
+    let (mut bytes, rct) = (self.outputs_bytes, self.rct_outputs);
+    (0 .. self.outputs)
+      .map(move |_| Output::read(rct, &mut bytes).expect("outputs weren't validated"))
+  }
+
+  /// The additional data included within the transaction.
+  pub fn extra(&self) -> &'a [u8] {
+    self.extra
+  }
+
+  /// Convert this into an owned `TransactionPrefix`.
+  pub fn into_owned(self) -> TransactionPrefix {
+    TransactionPrefix {
+      additional_timelock: self.additional_timelock,
+      inputs: self.inputs().map(InputRef::into_owned).collect(),
+      outputs: self.outputs().collect(),
+      extra: self.extra.to_vec(),
+    }
+  }
+}
+
+// Advance the reader past a Bulletproof(+), as read by `Bulletproof::read(_plus)`
+fn skip_bulletproof(r: &mut &[u8], plus: bool) -> io::Result<()> {
+  // A, S, T1, T2, tau_x, mu for a Bulletproof, or A, A1, B, r1, s1, d1 for a Bulletproof+
+  take(r, Some(6 * 32))?;
+  for _ in 0 .. 2 {
+    let len: usize = VarInt::read(r)?;
+    if len > MAX_LR {
+      Err(io::Error::other("vector exceeds bound on length"))?;
+    }
+    take(r, Some(len * 32))?;
+  }
+  if !plus {
+    take(r, Some(3 * 32))?;
+  }
+  Ok(())
+}
+
+// Advance the reader past RingCT proofs, as read by `RctProofs::read`, returning the length of
+// the RctBase
+fn skip_rct_proofs(
+  r: &mut &[u8],
+  ring_length: usize,
+  inputs: usize,
+  outputs: usize,
+) -> io::Result<Option<usize>> {
+  let start = *r;
+  let rct_type = read_byte(r)?;
+  if rct_type == 0 {
+    return Ok(None);
+  }
+  let rct_type = RctType::try_from(rct_type).map_err(|()| io::Error::other("invalid RCT type"))?;
+  let borromean = matches!(rct_type, RctType::AggregateMlsagBorromean | RctType::MlsagBorromean);
+  if (!borromean) && (outputs == 0) {
+    Err(io::Error::other("RCT with Bulletproofs(+) had 0 outputs"))?;
+  }
+
+  // RctBase
+  <u64 as VarInt>::read(r)?;
+  if rct_type == RctType::MlsagBorromean {
+    take(r, inputs.checked_mul(32))?;
+  }
+  let encrypted_amount = if rct_type.compact_encrypted_amounts() { 8 } else { 64 };
+  take(r, outputs.checked_mul(encrypted_amount + 32))?;
+  let base_len = start.len() - r.len();
+
+  // RctPrunable
+  let mlsags = |r: &mut &[u8], inputs: usize, width: usize| -> io::Result<()> {
+    let mlsag = ring_length.checked_mul(width).and_then(|ss| ss.checked_mul(32));
+    take(r, mlsag.and_then(|ss| ss.checked_add(32)).and_then(|mlsag| mlsag.checked_mul(inputs)))
+      .map(|_| ())
+  };
+  match rct_type {
+    RctType::AggregateMlsagBorromean => {
+      take(r, outputs.checked_mul(BORROMEAN_RANGE_SIZE))?;
+      mlsags(
+        r,
+        1,
+        inputs
+          .checked_add(1)
+          .ok_or_else(|| io::Error::other("reading a MLSAG for more inputs than representable"))?,
+      )?;
+    }
+    RctType::MlsagBorromean => {
+      take(r, outputs.checked_mul(BORROMEAN_RANGE_SIZE))?;
+      mlsags(r, inputs, 2)?;
+    }
+    RctType::MlsagBulletproofs | RctType::MlsagBulletproofsCompactAmount => {
+      if (if rct_type == RctType::MlsagBulletproofs {
+        u64::from(read_u32(r)?)
+      } else {
+        VarInt::read(r)?
+      }) != 1
+      {
+        Err(io::Error::other("n bulletproofs instead of one"))?;
+      }
+      skip_bulletproof(r, false)?;
+      mlsags(r, inputs, 2)?;
+      take(r, inputs.checked_mul(32))?;
+    }
+    RctType::ClsagBulletproof | RctType::ClsagBulletproofPlus => {
+      if read_byte(r)? != 1 {
+        Err(io::Error::other("n bulletproofs instead of one"))?;
+      }
+      skip_bulletproof(r, rct_type == RctType::ClsagBulletproofPlus)?;
+      let clsag = ring_length.checked_mul(32).and_then(|s| s.checked_add(64));
+      take(r, clsag.and_then(|clsag| clsag.checked_mul(inputs)))?;
+      take(r, inputs.checked_mul(32))?;
+    }
+  }
+
+  Ok(Some(base_len))
+}
+
+/// A borrowed view of a serialized transaction.
+///
+/// Reading this only checks the transaction's structure, applying the same bounds as
+/// `Transaction::read`. The prefix is interpreted on demand without allocating. The proofs are only
+/// checked to have the correct length, so converting this into an owned `Transaction` may still
+/// fail (such as due to a non-canonical scalar).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransactionRef<'a> {
+  serialized: &'a [u8],
+  version: u8,
+  prefix: TransactionPrefixRef<'a>,
+  // The length of the version and prefix
+  prefix_len: usize,
+  // The length of the version, prefix, and RctBase, if this has RingCT proofs
+  base_len: Option<usize>,
+}
+
+impl<'a> TransactionRef<'a> {
+  /// Read a borrowed view of a transaction, advancing the slice past it.
+  pub fn read(r: &mut &'a [u8]) -> io::Result<Self> {
+    let start = *r;
+    let version = VarInt::read(r)?;
+    let prefix = TransactionPrefixRef::read(r, version)?;
+    let prefix_len = start.len() - r.len();
+
+    let mut inputs = prefix.inputs();
+    let first = inputs.next().expect("transaction without inputs was read");
+    let is_miner_tx = (prefix.inputs == 1) && matches!(first, InputRef::Gen(_));
+    let ring_length = match first {
+      InputRef::Gen(_) => 0,
+      InputRef::ToKey { key_offsets, .. } => key_offsets.len(),
+    };
+
+    let (version, base_len) = match version {
+      1 => {
+        if !is_miner_tx {
+          for input in prefix.inputs() {
+            match input {
+              InputRef::ToKey { key_offsets, .. } => {
+                take(r, key_offsets.len().checked_mul(64))?;
+              }
+              InputRef::Gen(_) => {
+                Err(io::Error::other("reading signatures for a transaction with non-ToKey inputs"))?
+              }
+            }
+          }
+        }
+        (1, None)
+      }
+      2 => {
+        let base_len = skip_rct_proofs(r, ring_length, prefix.inputs, prefix.outputs)?;
+        (2, base_len.map(|base_len| prefix_len + base_len))
+      }
+      _ => Err(io::Error::other("tried to deserialize unknown version"))?,
+    };
+
+    Ok(TransactionRef { serialized: consumed(start, r), version, prefix, prefix_len, base_len })
+  }
+
+  /// The serialization of this transaction.
+  pub fn serialized(&self) -> &'a [u8] {
+    self.serialized
+  }
+
+  /// Get the version of this transaction.
+  pub fn version(&self) -> u8 {
+    self.version
+  }
+
+  /// Get the prefix of this transaction.
+  pub fn prefix(&self) -> TransactionPrefixRef<'a> {
+    self.prefix
+  }
+
+  /// The hash of the prunable part of the transaction.
+  ///
+  /// This is all zeroes for a version 2 transaction without RingCT proofs, and None for version 1
+  /// transactions, as with `Transaction::prunable_hash`.
+  pub fn prunable_hash(&self) -> Option<[u8; 32]> {
+    if self.version == 1 {
+      None?;
+    }
+    Some(match self.base_len {
+      None => [0; 32],
+      Some(base_len) => keccak256(&self.serialized[base_len ..]),
+    })
+  }
+
+  /// The hash of the transaction.
+  pub fn hash(&self) -> [u8; 32] {
+    let Some(prunable_hash) = self.prunable_hash() else {
+      return keccak256(self.serialized);
+    };
+    let base_len = self.base_len.unwrap_or(self.serialized.len());
+    let mut hashes = [0; 96];
+    hashes[.. 32].copy_from_slice(&keccak256(&self.serialized[.. self.prefix_len]));
+    hashes[32 .. 64].copy_from_slice(&keccak256(&self.serialized[self.prefix_len .. base_len]));
+    hashes[64 ..].copy_from_slice(&prunable_hash);
+    keccak256(hashes)
+  }
+
+  /// Convert this into an owned `Transaction`.
+  pub fn into_owned(self) -> io::Result<Transaction> {
+    Transaction::read(&mut { self.serialized })
+  }
+
+  /// Convert this into an owned, pruned `Transaction`.
+  ///
+  /// This doesn't read the prunable part of the transaction.
+  pub fn into_pruned(self) -> io::Result<Transaction<Pruned>> {
+    Transaction::read(&mut { self.serialized })
+  }
+}
+
+/// A borrowed view of a serialized block.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BlockRef<'a> {
+  serialized: &'a [u8],
+  header: BlockHeader,
+  miner_transaction: TransactionRef<'a>,
+  transactions: &'a [u8],
+}
+
+impl<'a> BlockRef<'a> {
+  /// Read a borrowed view of a block, advancing the slice past it.
+  ///
+  /// This applies the same checks as `Block::read`.
+  pub fn read(r: &mut &'a [u8]) -> io::Result<Self> {
+    let start = *r;
+    let header = BlockHeader::read(r)?;
+    let miner_transaction = TransactionRef::read(r)?;
+
+    let transactions: usize = VarInt::read(r)?;
+    if transactions >= Block::MAX_TRANSACTIONS {
+      Err(io::Error::other("amount of transaction exceeds limit"))?;
+    }
+    let transactions = take(r, transactions.checked_mul(32))?;
+
+    let mut inputs = miner_transaction.prefix().inputs();
+    if (inputs.len() != 1) || (!matches!(inputs.next(), Some(InputRef::Gen(_)))) {
+      Err(io::Error::other("block failed sanity checks"))?;
+    }
+
+    Ok(BlockRef { serialized: consumed(start, r), header, miner_transaction, transactions })
+  }
+
+  /// The serialization of this block.
+  pub fn serialized(&self) -> &'a [u8] {
+    self.serialized
+  }
+
+  /// The block's header.
+  pub fn header(&self) -> &BlockHeader {
+    &self.header
+  }
+
+  /// The miner's transaction.
+  pub fn miner_transaction(&self) -> TransactionRef<'a> {
+    self.miner_transaction
+  }
+
+  /// The zero-indexed position of this block within the blockchain.
+  pub fn number(&self) -> usize {
+    match self.miner_transaction.prefix().inputs().next() {
+      Some(InputRef::Gen(number)) => number,
+      _ => panic!("block's miner transaction wasn't validated"),
+    }
+  }
+
+  /// Iterate over the hashes of the transactions within this block.
+  pub fn transactions(&self) -> impl ExactSizeIterator<Item = [u8; 32]> + 'a {
+    self.transactions.chunks_exact(32).map(|hash| hash.try_into().expect("chunk wasn't 32 bytes"))
+  }
+
+  /// Convert this into an owned `Block`.
+  pub fn into_owned(self) -> io::Result<Block> {
+    Block::read(&mut { self.serialized })
+  }
+}
@@ -0,0 +1,178 @@
+// ブロック報酬（`get_block_reward` 相当）とブロック重量の中央値に関する合意規則。
+// 放出曲線・テール放出・中央値を超えたブロックへのペナルティを計算します。
+#[allow(unused_imports)]
+use std_shims::prelude::*;
+
+use crate::difficulty::difficulty_target;
+
+/// The total supply of atomic units the emission curve approaches.
+pub const MONEY_SUPPLY: u64 = u64::MAX;
+/// The emission per minute once the emission curve is exhausted (the tail emission).
+pub const FINAL_SUBSIDY_PER_MINUTE: u64 = 300_000_000_000;
+// The emission speed factor for a one-minute block time
+const EMISSION_SPEED_FACTOR_PER_MINUTE: u64 = 20;
+
+/// The amount of blocks whose weights are considered by the short-term median.
+pub const REWARD_BLOCKS_WINDOW: usize = 100;
+/// The amount of blocks whose long-term weights are considered by the long-term median.
+pub const LONG_TERM_BLOCK_WEIGHT_WINDOW: usize = 100_000;
+/// The factor the short-term median may exceed the long-term median by.
+pub const SHORT_TERM_BLOCK_WEIGHT_SURGE_FACTOR: usize = 50;
+/// The first hard fork to use the long-term median.
+pub const LONG_TERM_BLOCK_WEIGHT_HARDFORK_VERSION: u8 = 10;
+/// The first hard fork to calculate the penalty with the median bounded by the long-term median.
+pub const EFFECTIVE_MEDIAN_IN_PENALTY_HARDFORK_VERSION: u8 = 12;
+/// The first hard fork to allow long-term weights up to 1.7 times the long-term median.
+pub const SCALING_2021_HARDFORK_VERSION: u8 = 15;
+
+/// An error from applying the consensus rules.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum ConsensusError {
+  /// The block's weight exceeded the limit of twice the median weight.
+  #[error("block weight ({weight}) exceeded the limit ({limit})")]
+  OversizedBlock {
+    /// The block's weight.
+    weight: usize,
+    /// The maximum weight allowed.
+    limit: usize,
+  },
+}
+
+/// The weight a block may have without incurring a penalty, regardless of the median.
+pub fn full_reward_zone(hardfork_version: u8) -> usize {
+  if hardfork_version < 2 {
+    20_000
+  } else if hardfork_version < 5 {
+    60_000
+  } else {
+    300_000
+  }
+}
+
+/// The median of a list of values, as calculated by Monero.
+///
+/// The median of an empty list is 0, and the median of an even-length list is the floored mean of
+/// the two middle values.
+pub fn median(values: &[usize]) -> usize {
+  let mut values = values.to_vec();
+  values.sort_unstable();
+  let middle = values.len() / 2;
+  match values.len() {
+    0 => 0,
+    len if (len % 2) == 1 => values[middle],
+    _ => values[middle - 1].midpoint(values[middle]),
+  }
+}
+
+// The long-term median, bounded by the minimum the long-term median may be
+fn long_term_effective_median(long_term_weights: &[usize]) -> usize {
+  median(long_term_weights).max(full_reward_zone(LONG_TERM_BLOCK_WEIGHT_HARDFORK_VERSION))
+}
+
+/// The long-term weight of a block.
+///
+/// `long_term_weights` are the long-term weights of the prior [`LONG_TERM_BLOCK_WEIGHT_WINDOW`]
+/// blocks (or all prior blocks, if there are fewer). Prior to the long-term median, this is the
+/// block's weight.
+pub fn long_term_block_weight(
+  block_weight: usize,
+  long_term_weights: &[usize],
+  hardfork_version: u8,
+) -> usize {
+  if hardfork_version < LONG_TERM_BLOCK_WEIGHT_HARDFORK_VERSION {
+    return block_weight;
+  }
+  let long_term_median = long_term_effective_median(long_term_weights);
+  let bound = if hardfork_version < SCALING_2021_HARDFORK_VERSION {
+    long_term_median + ((long_term_median * 2) / 5)
+  } else {
+    long_term_median + ((long_term_median * 7) / 10)
+  };
+  block_weight.min(bound)
+}
+
+/// The median weight the weight limit of the next block is derived from.
+///
+/// The limit is twice this median. From hard fork 12, this is also the median used to calculate
+/// the reward of the next block.
+///
+/// `short_term_weights` are the weights of the prior [`REWARD_BLOCKS_WINDOW`] blocks and
+/// `long_term_weights` are the long-term weights of the prior [`LONG_TERM_BLOCK_WEIGHT_WINDOW`]
+/// blocks (or all prior blocks, if there are fewer). `long_term_weights` is ignored prior to the
+/// long-term median.
+pub fn median_block_weight(
+  short_term_weights: &[usize],
+  long_term_weights: &[usize],
+  hardfork_version: u8,
+) -> usize {
+  let short_term_median = median(short_term_weights);
+  let median = if hardfork_version < LONG_TERM_BLOCK_WEIGHT_HARDFORK_VERSION {
+    short_term_median
+  } else {
+    short_term_median
+      .max(full_reward_zone(LONG_TERM_BLOCK_WEIGHT_HARDFORK_VERSION))
+      .min(SHORT_TERM_BLOCK_WEIGHT_SURGE_FACTOR * long_term_effective_median(long_term_weights))
+  };
+  median.max(full_reward_zone(hardfork_version))
+}
+
+/// The median weight used to calculate the reward of the next block.
+///
+/// Prior to hard fork 12, this is the short-term median, without the bound by the long-term
+/// median [`median_block_weight`] applies. Afterwards, this is [`median_block_weight`]. The
+/// arguments are as for [`median_block_weight`].
+pub fn reward_median_block_weight(
+  short_term_weights: &[usize],
+  long_term_weights: &[usize],
+  hardfork_version: u8,
+) -> usize {
+  if hardfork_version < EFFECTIVE_MEDIAN_IN_PENALTY_HARDFORK_VERSION {
+    median(short_term_weights).max(full_reward_zone(hardfork_version))
+  } else {
+    median_block_weight(short_term_weights, long_term_weights, hardfork_version)
+  }
+}
+
+/// The reward for a block without any penalty, excluding fees.
+///
+/// `already_generated_coins` is the amount of coins emitted by all prior blocks.
+pub fn base_reward(already_generated_coins: u64, hardfork_version: u8) -> u64 {
+  let target_minutes = difficulty_target(hardfork_version) / 60;
+  let emission_speed_factor = EMISSION_SPEED_FACTOR_PER_MINUTE - (target_minutes - 1);
+  ((MONEY_SUPPLY - already_generated_coins) >> emission_speed_factor)
+    .max(FINAL_SUBSIDY_PER_MINUTE * target_minutes)
+}
+
+/// The reward for a block, excluding fees (`get_block_reward`).
+///
+/// `median_weight` should be the result of [`reward_median_block_weight`] and `block_weight` the
+/// sum of the weights of the block's transactions, including the miner transaction. Blocks heavier
+/// than the median are penalized, with blocks heavier than twice the median being invalid. Blocks
+/// must also not exceed twice [`median_block_weight`], which is only distinct before hard fork 12.
+pub fn block_reward(
+  median_weight: usize,
+  block_weight: usize,
+  already_generated_coins: u64,
+  hardfork_version: u8,
+) -> Result<u64, ConsensusError> {
+  let base_reward = base_reward(already_generated_coins, hardfork_version);
+
+  let median_weight = median_weight.max(full_reward_zone(hardfork_version));
+  if block_weight <= median_weight {
+    return Ok(base_reward);
+  }
+  let limit = 2 * median_weight;
+  if block_weight > limit {
+    Err(ConsensusError::OversizedBlock { weight: block_weight, limit })?;
+  }
+
+  // base_reward * (1 - (((block_weight - median_weight) / median_weight) ** 2)), which Monero
+  // calculates with a 64-bit multiplicand
+  let median_weight = u64::try_from(median_weight).expect("median weight exceeded 64 bits");
+  let block_weight = u64::try_from(block_weight).expect("block weight exceeded 64 bits");
+  let multiplicand = ((2 * median_weight) - block_weight).wrapping_mul(block_weight);
+  let reward = (u128::from(base_reward) * u128::from(multiplicand)) /
+    u128::from(median_weight) /
+    u128::from(median_weight);
+  Ok(u64::try_from(reward).expect("penalized reward exceeded the base reward"))
+}
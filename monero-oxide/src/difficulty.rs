@@ -0,0 +1,164 @@
+// 難易度の計算（`next_difficulty` 相当）と PoW ハッシュの難易度判定。
+// 直近のブロックのタイムスタンプと累積難易度（128 ビット）から次のブロックの難易度を求めます。
+#[allow(unused_imports)]
+use std_shims::prelude::*;
+use std_shims::collections::VecDeque;
+
+/// The amount of blocks whose timestamps and cumulative difficulties are considered.
+pub const DIFFICULTY_WINDOW: usize = 720;
+/// The amount of the most recent blocks which aren't considered.
+pub const DIFFICULTY_LAG: usize = 15;
+/// The amount of blocks with outlying timestamps removed from each side of the window.
+pub const DIFFICULTY_CUT: usize = 60;
+/// The amount of blocks needed to calculate the next difficulty, including the lagged blocks.
+pub const DIFFICULTY_BLOCKS_COUNT: usize = DIFFICULTY_WINDOW + DIFFICULTY_LAG;
+
+/// The target block time, in seconds, prior to hard fork 2.
+pub const DIFFICULTY_TARGET_V1: u64 = 60;
+/// The target block time, in seconds, since hard fork 2.
+pub const DIFFICULTY_TARGET_V2: u64 = 120;
+
+/// An error from calculating a difficulty.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum DifficultyError {
+  /// The amount of timestamps didn't match the amount of cumulative difficulties.
+  #[error("amount of timestamps didn't match the amount of cumulative difficulties")]
+  InvalidLength,
+  /// The cumulative difficulties weren't strictly increasing.
+  #[error("cumulative difficulties weren't strictly increasing")]
+  NonIncreasingCumulativeDifficulty,
+  /// The difficulty (or cumulative difficulty) exceeded 128 bits.
+  #[error("difficulty exceeded 128 bits")]
+  Overflow,
+}
+
+/// The target block time, in seconds, for a block with the specified hard fork.
+pub fn difficulty_target(hardfork_version: u8) -> u64 {
+  if hardfork_version < 2 {
+    DIFFICULTY_TARGET_V1
+  } else {
+    DIFFICULTY_TARGET_V2
+  }
+}
+
+/// Calculate the difficulty for the next block.
+///
+/// The timestamps and cumulative difficulties are of the prior [`DIFFICULTY_BLOCKS_COUNT`] blocks
+/// (or all prior blocks, if there are fewer), ordered from oldest to newest. The genesis block is
+/// never included. Only the oldest [`DIFFICULTY_WINDOW`] blocks are considered, with the rest
+/// being the lag.
+///
+/// `target_seconds` should be the result of [`difficulty_target`] for the next block.
+pub fn next_difficulty(
+  timestamps: &[u64],
+  cumulative_difficulties: &[u128],
+  target_seconds: u64,
+) -> Result<u128, DifficultyError> {
+  if timestamps.len() != cumulative_difficulties.len() {
+    Err(DifficultyError::InvalidLength)?;
+  }
+  let length = timestamps.len().min(DIFFICULTY_WINDOW);
+  if length <= 1 {
+    return Ok(1);
+  }
+
+  let mut timestamps = timestamps[.. length].to_vec();
+  timestamps.sort_unstable();
+
+  // Remove the outlying timestamps, once there's enough blocks to
+  let (cut_begin, cut_end) = if length <= (DIFFICULTY_WINDOW - (2 * DIFFICULTY_CUT)) {
+    (0, length)
+  } else {
+    let cut_begin = (length - (DIFFICULTY_WINDOW - (2 * DIFFICULTY_CUT))).div_ceil(2);
+    (cut_begin, cut_begin + (DIFFICULTY_WINDOW - (2 * DIFFICULTY_CUT)))
+  };
+
+  let time_span = u128::from((timestamps[cut_end - 1] - timestamps[cut_begin]).max(1));
+  let total_work = cumulative_difficulties[cut_end - 1]
+    .checked_sub(cumulative_difficulties[cut_begin])
+    .filter(|total_work| *total_work != 0)
+    .ok_or(DifficultyError::NonIncreasingCumulativeDifficulty)?;
+
+  // ceil((total_work * target_seconds) / time_span), without a 256-bit intermediate value
+  let target_seconds = u128::from(target_seconds);
+  let (quotient, remainder) = (total_work / time_span, total_work % time_span);
+  quotient
+    .checked_mul(target_seconds)
+    .and_then(|difficulty| difficulty.checked_add((remainder * target_seconds).div_ceil(time_span)))
+    .ok_or(DifficultyError::Overflow)
+}
+
+/// Check if a proof-of-work hash meets the specified difficulty.
+///
+/// This is if `hash * difficulty`, with the hash interpreted as a little-endian 256-bit integer,
+/// doesn't overflow 256 bits.
+pub fn check_hash(hash: &[u8; 32], difficulty: u128) -> bool {
+  let difficulty = [difficulty & u128::from(u64::MAX), difficulty >> 64];
+  let mut product = [0u128; 6];
+  for (i, limb) in hash.chunks_exact(8).enumerate() {
+    let limb = u128::from(u64::from_le_bytes(limb.try_into().unwrap()));
+    let mut carry = 0;
+    for (j, difficulty) in difficulty.iter().enumerate() {
+      let value = product[i + j] + (limb * difficulty) + carry;
+      product[i + j] = value & u128::from(u64::MAX);
+      carry = value >> 64;
+    }
+    product[i + 2] += carry;
+  }
+  (product[4] == 0) && (product[5] == 0)
+}
+
+/// A window of blocks, tracking the cumulative difficulty of the chain.
+///
+/// This is intended to be updated with every block after the genesis block, in order, yielding
+/// the difficulty each block is expected to have.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DifficultyWindow {
+  cumulative_difficulty: u128,
+  timestamps: VecDeque<u64>,
+  cumulative_difficulties: VecDeque<u128>,
+}
+
+impl Default for DifficultyWindow {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl DifficultyWindow {
+  /// The window for the chain consisting solely of the genesis block, which has a difficulty of 1.
+  pub fn new() -> DifficultyWindow {
+    DifficultyWindow {
+      cumulative_difficulty: 1,
+      timestamps: VecDeque::with_capacity(DIFFICULTY_BLOCKS_COUNT),
+      cumulative_difficulties: VecDeque::with_capacity(DIFFICULTY_BLOCKS_COUNT),
+    }
+  }
+
+  /// The cumulative difficulty of the chain.
+  pub fn cumulative_difficulty(&self) -> u128 {
+    self.cumulative_difficulty
+  }
+
+  /// Add the next block, with the specified timestamp and difficulty, to the window.
+  pub fn push(&mut self, timestamp: u64, difficulty: u128) -> Result<(), DifficultyError> {
+    self.cumulative_difficulty =
+      self.cumulative_difficulty.checked_add(difficulty).ok_or(DifficultyError::Overflow)?;
+    if self.timestamps.len() == DIFFICULTY_BLOCKS_COUNT {
+      self.timestamps.pop_front();
+      self.cumulative_difficulties.pop_front();
+    }
+    self.timestamps.push_back(timestamp);
+    self.cumulative_difficulties.push_back(self.cumulative_difficulty);
+    Ok(())
+  }
+
+  /// The difficulty for the next block, which will have the specified hard fork.
+  pub fn next_difficulty(&self, hardfork_version: u8) -> Result<u128, DifficultyError> {
+    next_difficulty(
+      self.timestamps.iter().copied().collect::<Vec<_>>().as_slice(),
+      self.cumulative_difficulties.iter().copied().collect::<Vec<_>>().as_slice(),
+      difficulty_target(hardfork_version),
+    )
+  }
+}
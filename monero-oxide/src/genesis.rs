@@ -0,0 +1,55 @@
+// 各ネットワーク（メインネット・テストネット・ステージネット）のジェネシスブロック。
+// メインネットとテストネットは同じマイナートランザクションを持ち、ヘッダのノンスのみが異なります。
+use std_shims::vec;
+
+use monero_address::Network;
+
+use crate::{
+  block::{BlockHeader, Block},
+  transaction::Transaction,
+};
+
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/cryptonote_config.h
+const GENESIS_TRANSACTION: [u8; 80] = hex_literal::hex!(
+  "013c01ff0001ffffffffffff03029b2e4c0281c0b02e7c53291a94d1d0cbff8883f8024f5142ee494ffbbd0880712101"
+  "7767aafcde9be00dcfd098715ebcf7f410daebc582fda69d24a28e9d0bc890d1"
+);
+const STAGENET_GENESIS_TRANSACTION: [u8; 80] = hex_literal::hex!(
+  "013c01ff0001ffffffffffff0302df5d56da0c7d643ddd1ce61901c7bdc5fb1738bfe39fbe69c28a3a7032729c0f2101"
+  "168d0c4ca86fb55a4cf6a36d31431be1c53a3bd7411bb24e8832410289fa6f3b"
+);
+
+/// The hash of the genesis block of the mainnet.
+pub const MAINNET_GENESIS_HASH: [u8; 32] =
+  hex_literal::hex!("418015bb9ae982a1975da7d79277c2705727a56894ba0fb246adaabb1f4632e3");
+/// The hash of the genesis block of the testnet.
+pub const TESTNET_GENESIS_HASH: [u8; 32] =
+  hex_literal::hex!("48ca7cd3c8de5b6a4d53d2861fbdaedca141553559f9be9520068053cda8430b");
+/// The hash of the genesis block of the stagenet.
+pub const STAGENET_GENESIS_HASH: [u8; 32] =
+  hex_literal::hex!("76ee3cc98646292206cd3e86f74d88b4dcc1d937088645e9b0cbca84b7ce74eb");
+
+/// The genesis block of the specified network.
+pub fn genesis_block(network: Network) -> Block {
+  let (nonce, miner_transaction) = match network {
+    Network::Mainnet => (10_000, GENESIS_TRANSACTION),
+    Network::Testnet => (10_001, GENESIS_TRANSACTION),
+    Network::Stagenet => (10_002, STAGENET_GENESIS_TRANSACTION),
+  };
+  let header =
+    BlockHeader { hardfork_version: 1, hardfork_signal: 0, timestamp: 0, previous: [0; 32], nonce };
+  let miner_transaction = Transaction::read(&mut miner_transaction.as_slice())
+    .expect("couldn't read the genesis block's miner transaction");
+  Block::new(header, miner_transaction, vec![])
+    .expect("genesis block's miner transaction didn't define its number")
+}
+
+/// The hash of the genesis block of the specified network.
+pub fn genesis_hash(network: Network) -> [u8; 32] {
+  match network {
+    Network::Mainnet => MAINNET_GENESIS_HASH,
+    Network::Testnet => TESTNET_GENESIS_HASH,
+    Network::Stagenet => STAGENET_GENESIS_HASH,
+  }
+}
@@ -0,0 +1,256 @@
+// ハードフォークの有効化高さ（ネットワークごと）と、ハードフォークに依存する規則の表。
+// 許可される RctType・リングサイズ・ビュータグ・出力数の上限などを一か所で参照できるようにし、
+// トランザクションがあるハードフォークの規則に従っているかを検査します。
+#[allow(unused_imports)]
+use std_shims::prelude::*;
+
+use monero_address::Network;
+
+use crate::{
+  ringct::{bulletproofs::MAX_COMMITMENTS, RctType},
+  transaction::{Input, Transaction, PotentiallyPruned, PotentiallyPrunedRctProofs},
+  DEFAULT_LOCK_WINDOW, COINBASE_LOCK_WINDOW,
+};
+
+// The heights each hard fork activated at, indexed by the hard fork's version minus one
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/hardforks/hardforks.cpp
+const MAINNET_HEIGHTS: [usize; 16] = [
+  0, 1_009_827, 1_141_317, 1_220_516, 1_288_616, 1_400_000, 1_546_000, 1_685_555, 1_686_275,
+  1_788_000, 1_788_720, 1_978_433, 2_210_000, 2_210_720, 2_688_888, 2_689_608,
+];
+const TESTNET_HEIGHTS: [usize; 16] = [
+  0, 624_634, 800_500, 801_219, 802_660, 971_400, 1_057_027, 1_057_058, 1_057_778, 1_154_318,
+  1_155_038, 1_308_737, 1_543_939, 1_544_659, 1_982_800, 1_983_520,
+];
+const STAGENET_HEIGHTS: [usize; 16] = [
+  0, 32_000, 33_000, 34_000, 35_000, 36_000, 37_000, 176_456, 177_176, 269_000, 269_720, 454_721,
+  675_405, 676_125, 1_151_000, 1_151_720,
+];
+
+/// An error from checking a transaction against the rules of a hard fork.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum HardForkError {
+  /// The transaction was a miner transaction.
+  #[error("miner transactions aren't checked as signed transactions")]
+  MinerTransaction,
+  /// The transaction's version wasn't allowed.
+  #[error("transaction version wasn't allowed")]
+  InvalidVersion,
+  /// The transaction's RingCT type wasn't allowed.
+  #[error("RctType {0:?} wasn't allowed")]
+  InvalidRctType(RctType),
+  /// The ring for the specified input had a disallowed size.
+  #[error("ring for input {0} had a disallowed size")]
+  InvalidRingSize(usize),
+  /// The transaction's outputs had disallowed types (with or without view tags).
+  #[error("outputs had disallowed types")]
+  InvalidOutputTypes,
+  /// The transaction had too few outputs.
+  #[error("too few outputs")]
+  TooFewOutputs,
+  /// The transaction had too many outputs.
+  #[error("too many outputs")]
+  TooManyOutputs,
+}
+
+/// A hard fork of the Monero protocol.
+///
+/// Hard forks are ordered by their version, allowing comparisons such as
+/// `hardfork >= HardFork::new(15).unwrap()`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HardFork(u8);
+
+impl HardFork {
+  /// The latest hard fork.
+  pub const LATEST: HardFork = HardFork(16);
+
+  /// The hard fork with the specified version.
+  ///
+  /// Returns `None` if the version isn't of a known hard fork.
+  pub const fn new(version: u8) -> Option<HardFork> {
+    if (version == 0) || (version > Self::LATEST.0) {
+      return None;
+    }
+    Some(HardFork(version))
+  }
+
+  /// The version of this hard fork, as used within `BlockHeader::hardfork_version`.
+  pub const fn version(self) -> u8 {
+    self.0
+  }
+
+  fn heights(network: Network) -> &'static [usize; 16] {
+    match network {
+      Network::Mainnet => &MAINNET_HEIGHTS,
+      Network::Testnet => &TESTNET_HEIGHTS,
+      Network::Stagenet => &STAGENET_HEIGHTS,
+    }
+  }
+
+  /// The height of the first block of this hard fork on the specified network.
+  pub fn activation_height(self, network: Network) -> usize {
+    Self::heights(network)[usize::from(self.0 - 1)]
+  }
+
+  /// The hard fork the block at the specified height is of, on the specified network.
+  pub fn at_height(network: Network, height: usize) -> HardFork {
+    let forks = Self::heights(network).iter().take_while(|activation| **activation <= height);
+    HardFork(u8::try_from(forks.count()).expect("more than 255 hard forks"))
+  }
+
+  /// If version 1 (pre-RingCT) transactions are allowed.
+  pub fn v1_transactions_allowed(self) -> bool {
+    self.0 < 6
+  }
+
+  /// If version 2 (RingCT) transactions are allowed.
+  pub fn v2_transactions_allowed(self) -> bool {
+    self.0 >= 4
+  }
+
+  /// If transactions with the specified RingCT type are allowed.
+  pub fn rct_type_allowed(self, rct_type: RctType) -> bool {
+    let (first, last) = match rct_type {
+      RctType::AggregateMlsagBorromean | RctType::MlsagBorromean => (4, 8),
+      RctType::MlsagBulletproofs => (8, 10),
+      RctType::MlsagBulletproofsCompactAmount => (10, 13),
+      RctType::ClsagBulletproof => (13, 15),
+      RctType::ClsagBulletproofPlus => (15, Self::LATEST.0),
+    };
+    (first ..= last).contains(&self.0)
+  }
+
+  /// The minimum size of a ring.
+  ///
+  /// Prior to hard fork 8, Monero exempted inputs without enough outputs of the same amount to
+  /// form a ring of this size, which isn't reflected here.
+  pub fn minimum_ring_size(self) -> usize {
+    match self.0 {
+      1 => 1,
+      2 ..= 5 => 3,
+      6 => 5,
+      7 => 7,
+      8 ..= 14 => 11,
+      _ => 16,
+    }
+  }
+
+  /// The maximum size of a ring, if there is one.
+  pub fn maximum_ring_size(self) -> Option<usize> {
+    (self.0 >= 8).then(|| self.minimum_ring_size())
+  }
+
+  /// If outputs with view tags are allowed.
+  pub fn view_tags_allowed(self) -> bool {
+    self.0 >= 15
+  }
+
+  /// If outputs are required to have view tags.
+  ///
+  /// During the hard fork introducing view tags, a transaction's outputs may either all have view
+  /// tags or all not have view tags.
+  pub fn view_tags_required(self) -> bool {
+    self.0 > 15
+  }
+
+  /// The minimum amount of outputs a version 2 transaction must have.
+  pub fn minimum_outputs(self) -> usize {
+    if self.0 >= 12 {
+      2
+    } else {
+      1
+    }
+  }
+
+  /// The maximum amount of outputs a transaction may have, if there is one.
+  ///
+  /// This is the limit on the amount of commitments a Bulletproof(+) may prove for, and applies
+  /// once Borromean range proofs are no longer allowed.
+  pub fn maximum_outputs(self) -> Option<usize> {
+    (self.0 >= 9).then_some(MAX_COMMITMENTS)
+  }
+
+  /// The amount of blocks an output must be confirmed for before it may be spent.
+  ///
+  /// This hasn't changed across hard forks.
+  pub fn default_lock_window(self) -> usize {
+    DEFAULT_LOCK_WINDOW
+  }
+
+  /// The amount of blocks the outputs of a miner transaction are locked for.
+  ///
+  /// This hasn't changed across hard forks.
+  pub fn coinbase_lock_window(self) -> usize {
+    COINBASE_LOCK_WINDOW
+  }
+
+  /// Check a (non-miner) transaction follows the rules of this hard fork.
+  ///
+  /// This checks the transaction's version, RingCT type, ring sizes, output types, and amount of
+  /// outputs. It doesn't check the transaction's signatures or proofs (see `Transaction::verify`).
+  pub fn check_transaction<P: PotentiallyPruned>(
+    self,
+    tx: &Transaction<P>,
+  ) -> Result<(), HardForkError> {
+    let prefix = tx.prefix();
+    if prefix.inputs.iter().any(|input| matches!(input, Input::Gen(_))) {
+      Err(HardForkError::MinerTransaction)?;
+    }
+
+    match tx {
+      Transaction::V1 { .. } => {
+        if !self.v1_transactions_allowed() {
+          Err(HardForkError::InvalidVersion)?;
+        }
+      }
+      Transaction::V2 { proofs, .. } => {
+        if !self.v2_transactions_allowed() {
+          Err(HardForkError::InvalidVersion)?;
+        }
+        if let Some(proofs) = proofs {
+          let rct_type = proofs.rct_type();
+          if !self.rct_type_allowed(rct_type) {
+            Err(HardForkError::InvalidRctType(rct_type))?;
+          }
+          if !matches!(rct_type, RctType::AggregateMlsagBorromean | RctType::MlsagBorromean) &&
+            (prefix.outputs.len() > MAX_COMMITMENTS)
+          {
+            Err(HardForkError::TooManyOutputs)?;
+          }
+        }
+        if prefix.outputs.len() < self.minimum_outputs() {
+          Err(HardForkError::TooFewOutputs)?;
+        }
+      }
+    }
+
+    for (i, input) in prefix.inputs.iter().enumerate() {
+      let Input::ToKey { key_offsets, .. } = input else { continue };
+      let ring_size = key_offsets.len();
+      if (ring_size < self.minimum_ring_size()) ||
+        self.maximum_ring_size().is_some_and(|maximum| ring_size > maximum)
+      {
+        Err(HardForkError::InvalidRingSize(i))?;
+      }
+    }
+
+    if self.maximum_outputs().is_some_and(|maximum| prefix.outputs.len() > maximum) {
+      Err(HardForkError::TooManyOutputs)?;
+    }
+
+    let view_tags = prefix.outputs.iter().filter(|output| output.view_tag.is_some()).count();
+    let valid_output_types = if self.view_tags_required() {
+      view_tags == prefix.outputs.len()
+    } else if self.view_tags_allowed() {
+      (view_tags == 0) || (view_tags == prefix.outputs.len())
+    } else {
+      view_tags == 0
+    };
+    if !valid_output_types {
+      Err(HardForkError::InvalidOutputTypes)?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,480 @@
+// monerod の JSON（`as_json` 等）と互換の形式で、トランザクション・ブロック・RingCT の証明を
+// `serde` により（逆）シリアライズする実装です。
+// デシリアライズ時はバイナリ形式へ変換したうえで `read` し、バイナリ形式と同じ検証を適用します。
+#[allow(unused_imports)]
+use std_shims::prelude::*;
+use std_shims::io;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de::Error};
+
+use crate::{
+  io::*,
+  ed25519::*,
+  ringct::{
+    mlsag::Mlsag, clsag::Clsag, borromean::BorromeanRange, bulletproofs::Bulletproof,
+    EncryptedAmount, RctType, RctBase, RctPrunable, RctProofs,
+  },
+  transaction::{Input, Output, Timelock, Transaction},
+  block::{BlockHeader, Block},
+};
+
+#[derive(Serialize, Deserialize)]
+struct HexBytes(#[serde(with = "monero_io::serde_hex")] Vec<u8>);
+
+#[derive(Serialize, Deserialize)]
+struct HexHash(
+  #[serde(
+    serialize_with = "monero_io::serde_hex::serialize",
+    deserialize_with = "monero_io::serde_hex::deserialize_array"
+  )]
+  [u8; 32],
+);
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum InputJson {
+  Gen { height: usize },
+  Key { amount: u64, key_offsets: Vec<u64>, k_image: CompressedPoint },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TargetJson {
+  Key(CompressedPoint),
+  TaggedKey {
+    key: CompressedPoint,
+    #[serde(
+      serialize_with = "monero_io::serde_hex::serialize",
+      deserialize_with = "monero_io::serde_hex::deserialize_array"
+    )]
+    view_tag: [u8; 1],
+  },
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutputJson {
+  amount: u64,
+  target: TargetJson,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EcdhInfoJson {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  mask: Option<HexBytes>,
+  amount: HexBytes,
+}
+
+// `rct_signatures`, which only has its type for a transaction without RingCT proofs
+#[derive(Serialize, Deserialize)]
+struct RctSignaturesJson {
+  #[serde(rename = "type")]
+  rct_type: u8,
+  #[serde(rename = "txnFee", default, skip_serializing_if = "Option::is_none")]
+  fee: Option<u64>,
+  #[serde(rename = "pseudoOuts", default, skip_serializing_if = "Option::is_none")]
+  pseudo_outs: Option<Vec<CompressedPoint>>,
+  #[serde(rename = "ecdhInfo", default, skip_serializing_if = "Option::is_none")]
+  encrypted_amounts: Option<Vec<EcdhInfoJson>>,
+  #[serde(rename = "outPk", default, skip_serializing_if = "Option::is_none")]
+  commitments: Option<Vec<CompressedPoint>>,
+}
+
+// `rctsig_prunable`, whose fields present depend on the RctType
+#[derive(Serialize, Deserialize)]
+struct RctPrunableJson {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  nbp: Option<u64>,
+  #[serde(rename = "rangeSigs", default, skip_serializing_if = "Option::is_none")]
+  borromean: Option<Vec<BorromeanRange>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  bp: Option<Vec<Bulletproof>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  bpp: Option<Vec<Bulletproof>>,
+  #[serde(rename = "MGs", default, skip_serializing_if = "Option::is_none")]
+  mlsags: Option<Vec<Mlsag>>,
+  #[serde(rename = "CLSAGs", default, skip_serializing_if = "Option::is_none")]
+  clsags: Option<Vec<Clsag>>,
+  #[serde(rename = "pseudoOuts", default, skip_serializing_if = "Option::is_none")]
+  pseudo_outs: Option<Vec<CompressedPoint>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RctProofsJson {
+  rct_signatures: RctSignaturesJson,
+  rctsig_prunable: RctPrunableJson,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TransactionJson {
+  version: u64,
+  unlock_time: u64,
+  vin: Vec<InputJson>,
+  vout: Vec<OutputJson>,
+  extra: Vec<u8>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  signatures: Option<Vec<HexBytes>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  rct_signatures: Option<RctSignaturesJson>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  rctsig_prunable: Option<RctPrunableJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockJson {
+  major_version: u8,
+  minor_version: u8,
+  timestamp: u64,
+  prev_id: HexHash,
+  nonce: u32,
+  miner_tx: Transaction,
+  tx_hashes: Vec<HexHash>,
+}
+
+fn required<T, E: Error>(field: Option<T>, name: &'static str) -> Result<T, E> {
+  field.ok_or_else(|| E::missing_field(name))
+}
+
+// Take the sole item of a list, as monerod represents the single Bulletproof/aggregate MLSAG
+fn single<T, E: Error>(field: Option<Vec<T>>, name: &'static str) -> Result<T, E> {
+  let mut list = required(field, name)?;
+  if list.len() != 1 {
+    Err(E::invalid_length(list.len(), &"a list with a single item"))?;
+  }
+  Ok(list.swap_remove(0))
+}
+
+impl From<&RctProofs> for RctProofsJson {
+  fn from(proofs: &RctProofs) -> Self {
+    let rct_type = proofs.rct_type();
+    let base = &proofs.base;
+    let rct_signatures = RctSignaturesJson {
+      rct_type: u8::from(rct_type),
+      fee: Some(base.fee),
+      pseudo_outs: (rct_type == RctType::MlsagBorromean).then(|| base.pseudo_outs.clone()),
+      encrypted_amounts: Some(
+        base
+          .encrypted_amounts
+          .iter()
+          .map(|encrypted_amount| match encrypted_amount {
+            EncryptedAmount::Original { mask, amount } => EcdhInfoJson {
+              mask: Some(HexBytes(mask.to_vec())),
+              amount: HexBytes(amount.to_vec()),
+            },
+            EncryptedAmount::Compact { amount } => {
+              EcdhInfoJson { mask: None, amount: HexBytes(amount.to_vec()) }
+            }
+          })
+          .collect(),
+      ),
+      commitments: Some(base.commitments.clone()),
+    };
+
+    let mut rctsig_prunable = RctPrunableJson {
+      nbp: None,
+      borromean: None,
+      bp: None,
+      bpp: None,
+      mlsags: None,
+      clsags: None,
+      pseudo_outs: None,
+    };
+    match &proofs.prunable {
+      RctPrunable::AggregateMlsagBorromean { mlsag, borromean } => {
+        rctsig_prunable.borromean = Some(borromean.clone());
+        rctsig_prunable.mlsags = Some(vec![mlsag.clone()]);
+      }
+      RctPrunable::MlsagBorromean { mlsags, borromean } => {
+        rctsig_prunable.borromean = Some(borromean.clone());
+        rctsig_prunable.mlsags = Some(mlsags.clone());
+      }
+      RctPrunable::MlsagBulletproofs { mlsags, pseudo_outs, bulletproof } |
+      RctPrunable::MlsagBulletproofsCompactAmount { mlsags, pseudo_outs, bulletproof } => {
+        rctsig_prunable.nbp = Some(1);
+        rctsig_prunable.bp = Some(vec![bulletproof.clone()]);
+        rctsig_prunable.mlsags = Some(mlsags.clone());
+        rctsig_prunable.pseudo_outs = Some(pseudo_outs.clone());
+      }
+      RctPrunable::Clsag { clsags, pseudo_outs, bulletproof } => {
+        rctsig_prunable.nbp = Some(1);
+        if rct_type == RctType::ClsagBulletproof {
+          rctsig_prunable.bp = Some(vec![bulletproof.clone()]);
+        } else {
+          rctsig_prunable.bpp = Some(vec![bulletproof.clone()]);
+        }
+        rctsig_prunable.clsags = Some(clsags.clone());
+        rctsig_prunable.pseudo_outs = Some(pseudo_outs.clone());
+      }
+    }
+
+    RctProofsJson { rct_signatures, rctsig_prunable }
+  }
+}
+
+impl RctProofsJson {
+  fn into_proofs<E: Error>(self) -> Result<RctProofs, E> {
+    let RctProofsJson { rct_signatures: base, rctsig_prunable: prunable } = self;
+    let rct_type =
+      RctType::try_from(base.rct_type).map_err(|()| E::custom("invalid RingCT type"))?;
+
+    let compact = rct_type.compact_encrypted_amounts();
+    let encrypted_amounts = required(base.encrypted_amounts, "ecdhInfo")?
+      .into_iter()
+      .map(|EcdhInfoJson { mask, amount }| {
+        let invalid = || E::custom("ecdhInfo didn't match the RingCT type");
+        Ok(match (compact, mask) {
+          (false, Some(mask)) => EncryptedAmount::Original {
+            mask: mask.0.try_into().map_err(|_| invalid())?,
+            amount: amount.0.try_into().map_err(|_| invalid())?,
+          },
+          (true, None) => {
+            EncryptedAmount::Compact { amount: amount.0.try_into().map_err(|_| invalid())? }
+          }
+          _ => Err(invalid())?,
+        })
+      })
+      .collect::<Result<_, E>>()?;
+    let base = RctBase {
+      fee: required(base.fee, "txnFee")?,
+      pseudo_outs: if rct_type == RctType::MlsagBorromean {
+        required(base.pseudo_outs, "pseudoOuts")?
+      } else {
+        vec![]
+      },
+      encrypted_amounts,
+      commitments: required(base.commitments, "outPk")?,
+    };
+
+    let bulletproof = |bp: Option<Vec<Bulletproof>>, name| -> Result<Bulletproof, E> {
+      if required(prunable.nbp, "nbp")? != 1 {
+        Err(E::custom("n bulletproofs instead of one"))?;
+      }
+      single(bp, name)
+    };
+    let prunable = match rct_type {
+      RctType::AggregateMlsagBorromean => RctPrunable::AggregateMlsagBorromean {
+        borromean: required(prunable.borromean, "rangeSigs")?,
+        mlsag: single(prunable.mlsags, "MGs")?,
+      },
+      RctType::MlsagBorromean => RctPrunable::MlsagBorromean {
+        borromean: required(prunable.borromean, "rangeSigs")?,
+        mlsags: required(prunable.mlsags, "MGs")?,
+      },
+      RctType::MlsagBulletproofs => RctPrunable::MlsagBulletproofs {
+        bulletproof: bulletproof(prunable.bp, "bp")?,
+        mlsags: required(prunable.mlsags, "MGs")?,
+        pseudo_outs: required(prunable.pseudo_outs, "pseudoOuts")?,
+      },
+      RctType::MlsagBulletproofsCompactAmount => RctPrunable::MlsagBulletproofsCompactAmount {
+        bulletproof: bulletproof(prunable.bp, "bp")?,
+        mlsags: required(prunable.mlsags, "MGs")?,
+        pseudo_outs: required(prunable.pseudo_outs, "pseudoOuts")?,
+      },
+      RctType::ClsagBulletproof | RctType::ClsagBulletproofPlus => RctPrunable::Clsag {
+        bulletproof: if rct_type == RctType::ClsagBulletproof {
+          bulletproof(prunable.bp, "bp")?
+        } else {
+          bulletproof(prunable.bpp, "bpp")?
+        },
+        clsags: required(prunable.clsags, "CLSAGs")?,
+        pseudo_outs: required(prunable.pseudo_outs, "pseudoOuts")?,
+      },
+    };
+
+    let proofs = RctProofs { base, prunable };
+    // A Bulletproof may have been specified where a Bulletproof+ was expected, or vice versa
+    if proofs.rct_type() != rct_type {
+      Err(E::custom("Bulletproof didn't match the RingCT type"))?;
+    }
+    Ok(proofs)
+  }
+}
+
+impl Serialize for RctProofs {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    RctProofsJson::from(self).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for RctProofs {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    RctProofsJson::deserialize(deserializer)?.into_proofs()
+  }
+}
+
+impl Serialize for Transaction {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let prefix = self.prefix();
+    let mut tx = TransactionJson {
+      version: self.version().into(),
+      unlock_time: match prefix.additional_timelock {
+        Timelock::None => 0,
+        Timelock::Block(block) => u64::try_from(block).expect("block number exceeded 64 bits"),
+        Timelock::Time(time) => time,
+      },
+      vin: prefix
+        .inputs
+        .iter()
+        .map(|input| match input {
+          Input::Gen(height) => InputJson::Gen { height: *height },
+          Input::ToKey { amount, key_offsets, key_image } => InputJson::Key {
+            amount: amount.unwrap_or(0),
+            key_offsets: key_offsets.clone(),
+            k_image: *key_image,
+          },
+        })
+        .collect(),
+      vout: prefix
+        .outputs
+        .iter()
+        .map(|output| OutputJson {
+          amount: output.amount.unwrap_or(0),
+          target: match output.view_tag {
+            None => TargetJson::Key(output.key),
+            Some(view_tag) => TargetJson::TaggedKey { key: output.key, view_tag: [view_tag] },
+          },
+        })
+        .collect(),
+      extra: prefix.extra.clone(),
+      signatures: None,
+      rct_signatures: None,
+      rctsig_prunable: None,
+    };
+
+    match self {
+      Transaction::V1 { signatures, .. } => {
+        tx.signatures = Some(
+          signatures
+            .iter()
+            .map(|signature| {
+              let mut buf = vec![];
+              signature.write(&mut buf).expect("write failed but <Vec as io::Write> doesn't fail");
+              HexBytes(buf)
+            })
+            .collect(),
+        );
+      }
+      Transaction::V2 { proofs: None, .. } => {
+        tx.rct_signatures = Some(RctSignaturesJson {
+          rct_type: 0,
+          fee: None,
+          pseudo_outs: None,
+          encrypted_amounts: None,
+          commitments: None,
+        });
+      }
+      Transaction::V2 { proofs: Some(proofs), .. } => {
+        let RctProofsJson { rct_signatures, rctsig_prunable } = RctProofsJson::from(proofs);
+        tx.rct_signatures = Some(rct_signatures);
+        tx.rctsig_prunable = Some(rctsig_prunable);
+      }
+    }
+
+    tx.serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Transaction {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let tx = TransactionJson::deserialize(deserializer)?;
+
+    // Encode the transaction, so it's read with the same rules as the binary encoding
+    let write =
+      |res: io::Result<()>| res.expect("write failed but <Vec as io::Write> doesn't fail");
+    let mut buf = vec![];
+    write(VarInt::write(&tx.version, &mut buf));
+    write(VarInt::write(&tx.unlock_time, &mut buf));
+    write(VarInt::write(&tx.vin.len(), &mut buf));
+    let mut ring_lengths = vec![];
+    for input in tx.vin {
+      let input = match input {
+        InputJson::Gen { height } => Input::Gen(height),
+        InputJson::Key { amount, key_offsets, k_image } => {
+          ring_lengths.push(key_offsets.len());
+          Input::ToKey { amount: Some(amount), key_offsets, key_image: k_image }
+        }
+      };
+      write(input.write(&mut buf));
+    }
+    write(VarInt::write(&tx.vout.len(), &mut buf));
+    for OutputJson { amount, target } in tx.vout {
+      let output = match target {
+        TargetJson::Key(key) => Output { amount: Some(amount), key, view_tag: None },
+        TargetJson::TaggedKey { key, view_tag } => {
+          Output { amount: Some(amount), key, view_tag: Some(view_tag[0]) }
+        }
+      };
+      write(output.write(&mut buf));
+    }
+    write(write_vec(write_byte, &tx.extra, &mut buf));
+
+    match tx.version {
+      1 => {
+        let signatures = required(tx.signatures, "signatures")?;
+        if signatures.len() != ring_lengths.len() {
+          Err(D::Error::invalid_length(signatures.len(), &"a signature for each input"))?;
+        }
+        for (signature, ring_length) in signatures.into_iter().zip(ring_lengths) {
+          if signature.0.len() != (ring_length * 64) {
+            Err(D::Error::custom("signature didn't match the input's ring length"))?;
+          }
+          buf.extend(signature.0);
+        }
+      }
+      2 => {
+        let rct_signatures = required(tx.rct_signatures, "rct_signatures")?;
+        if rct_signatures.rct_type == 0 {
+          buf.push(0);
+        } else {
+          let proofs = RctProofsJson {
+            rct_signatures,
+            rctsig_prunable: required(tx.rctsig_prunable, "rctsig_prunable")?,
+          }
+          .into_proofs()?;
+          write(proofs.write(&mut buf));
+        }
+      }
+      _ => Err(D::Error::custom("unknown transaction version"))?,
+    }
+
+    let mut reader = buf.as_slice();
+    let res =
+      Transaction::read(&mut reader).map_err(|_| D::Error::custom("invalid transaction"))?;
+    if !reader.is_empty() {
+      Err(D::Error::custom("transaction had more proofs than expected"))?;
+    }
+    Ok(res)
+  }
+}
+
+impl Serialize for Block {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    BlockJson {
+      major_version: self.header.hardfork_version,
+      minor_version: self.header.hardfork_signal,
+      timestamp: self.header.timestamp,
+      prev_id: HexHash(self.header.previous),
+      nonce: self.header.nonce,
+      miner_tx: self.miner_transaction().clone(),
+      tx_hashes: self.transactions.iter().copied().map(HexHash).collect(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Block {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let block = BlockJson::deserialize(deserializer)?;
+    Block::new(
+      BlockHeader {
+        hardfork_version: block.major_version,
+        hardfork_signal: block.minor_version,
+        timestamp: block.timestamp,
+        previous: block.prev_id.0,
+        nonce: block.nonce,
+      },
+      block.miner_tx,
+      block.tx_hashes.into_iter().map(|hash| hash.0).collect(),
+    )
+    .ok_or_else(|| D::Error::custom("miner transaction didn't have a single `gen` input"))
+  }
+}
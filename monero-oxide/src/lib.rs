@@ -21,6 +21,28 @@ pub mod ringct;
 pub mod transaction;
 /// ブロック関連機能
 pub mod block;
+/// シリアライズ済みのトランザクション・ブロックに対する借用ビュー
+pub mod borrowed;
+/// monerod の `blockchain.raw` ブートストラップ形式の読み書き
+pub mod bootstrap;
+
+/// トランザクション検証機能
+pub mod verify;
+
+/// 難易度関連機能
+pub mod difficulty;
+/// ブロック報酬・ブロック重量の合意規則
+pub mod consensus;
+/// ハードフォークの有効化高さとハードフォークごとの規則
+pub mod hardfork;
+/// 各ネットワークのジェネシスブロック
+pub mod genesis;
+/// ハードコードされたチェックポイント
+pub mod checkpoints;
+
+// monerod の JSON と互換の `serde` 実装
+#[cfg(feature = "serde")]
+mod json;
 
 #[cfg(test)]
 mod tests;
@@ -1,3 +1,5 @@
+use std_shims::{vec, vec::Vec};
+
 use crate::primitives::keccak256;
 
 /// Merkle ルート計算（Monero の `tree_hash` 相当）。
@@ -52,3 +54,114 @@ pub fn merkle_root(mut leaves: impl AsMut<[[u8; 32]]>) -> Option<[u8; 32]> {
     }
   }
 }
+
+// 与えられたリーフ数に対し、最初に（ペアリングされずに）そのまま次の段へ渡されるリーフの数と、
+// 次の段のノード数（2 の累乗）を返す
+fn first_level(leaf_count: usize) -> (usize, usize) {
+  debug_assert!(leaf_count >= 2);
+  // リーフ数未満で最大の 2 の累乗
+  let low_pow_2 = 1 << (usize::BITS - (leaf_count - 1).leading_zeros() - 1);
+  ((2 * low_pow_2) - leaf_count, low_pow_2)
+}
+
+/// Merkle ブランチ（単一リーフの認証パス）の生成。
+///
+/// `merkle_root` と同じ木の形に従い、指定したインデックスのリーフからルートまでの各段の
+/// 兄弟ノードを、リーフ側から順に返します。Monero の木は非対称なため、ブランチの長さは
+/// リーフのインデックスによって異なることがあります。
+///
+/// リーフが空、またはインデックスが範囲外の場合は `None` を返します。
+pub fn merkle_branch(leaves: &[[u8; 32]], index: usize) -> Option<Vec<[u8; 32]>> {
+  if index >= leaves.len() {
+    None?;
+  }
+  if leaves.len() == 1 {
+    return Some(vec![]);
+  }
+
+  let mut branch = vec![];
+  let (passed_through, width) = first_level(leaves.len());
+
+  // 最初の段では、そのまま渡されるリーフ以降のリーフのみがペアリングされる
+  let mut level = leaves[.. passed_through].to_vec();
+  level.extend(leaves[passed_through ..].chunks_exact(2).map(|pair| pair_hash(&pair[0], &pair[1])));
+  debug_assert_eq!(level.len(), width);
+  let mut index = if index < passed_through {
+    index
+  } else {
+    let paired = index - passed_through;
+    branch.push(leaves[passed_through + (paired ^ 1)]);
+    passed_through + (paired / 2)
+  };
+
+  // 以降は完全二分木
+  while level.len() != 1 {
+    branch.push(level[index ^ 1]);
+    level = level.chunks_exact(2).map(|pair| pair_hash(&pair[0], &pair[1])).collect();
+    index /= 2;
+  }
+
+  Some(branch)
+}
+
+/// Merkle ブランチからのルート計算。
+///
+/// `merkle_branch` が返したブランチと、リーフ・そのインデックス・木のリーフ数から
+/// Merkle ルートを再計算します。ブランチの長さが木の形と一致しない場合は `None` を返します。
+pub fn merkle_root_from_branch(
+  leaf: [u8; 32],
+  index: usize,
+  leaf_count: usize,
+  branch: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+  if index >= leaf_count {
+    None?;
+  }
+  if leaf_count == 1 {
+    return branch.is_empty().then_some(leaf);
+  }
+
+  let (passed_through, width) = first_level(leaf_count);
+  let mut branch = branch.iter();
+  let mut hash = leaf;
+  let mut index = if index < passed_through {
+    index
+  } else {
+    let paired = index - passed_through;
+    let sibling = branch.next()?;
+    hash = if (paired % 2) == 0 { pair_hash(&hash, sibling) } else { pair_hash(sibling, &hash) };
+    passed_through + (paired / 2)
+  };
+
+  let mut width = width;
+  while width != 1 {
+    let sibling = branch.next()?;
+    hash = if (index % 2) == 0 { pair_hash(&hash, sibling) } else { pair_hash(sibling, &hash) };
+    index /= 2;
+    width /= 2;
+  }
+
+  // ブランチを使い切っていることを確認する
+  branch.next().is_none().then_some(hash)
+}
+
+/// Merkle ブランチの検証。
+///
+/// リーフがブランチを介して指定されたルートに含まれることを確認します。
+pub fn verify_merkle_branch(
+  leaf: [u8; 32],
+  index: usize,
+  leaf_count: usize,
+  branch: &[[u8; 32]],
+  root: [u8; 32],
+) -> bool {
+  merkle_root_from_branch(leaf, index, leaf_count, branch) == Some(root)
+}
+
+// 左右のノードを連結して keccak256 を計算する
+fn pair_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+  let mut pair = [0; 64];
+  pair[.. 32].copy_from_slice(left);
+  pair[32 ..].copy_from_slice(right);
+  keccak256(pair)
+}
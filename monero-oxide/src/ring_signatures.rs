@@ -5,7 +5,12 @@ use std_shims::{
   vec::Vec,
 };
 
+#[cfg(feature = "legacy-signing")]
+use rand_core::{RngCore, CryptoRng};
+
 use zeroize::Zeroize;
+#[cfg(feature = "legacy-signing")]
+use zeroize::Zeroizing;
 
 use crate::{io::*, ed25519::*};
 
@@ -57,6 +62,75 @@ impl RingSignature {
     Ok(RingSignature { sigs: read_raw_vec(Signature::read, members, r)? })
   }
 
+  /// リング署名を作成する。
+  ///
+  /// `key` は `ring[signer_index]` の離散対数。キーイメージは `key * Point::biased_hash(ring[signer_index])`
+  /// であり、呼び出し側が `msg_hash` に含めておく必要がある。リングや鍵が不正な場合は `None` を返す。
+  ///
+  /// 過去のトランザクション形式のテスト用データを生成するためのもので、新しいトランザクションの作成に
+  /// 使用してはならない。この関数は可変時間で動作する。
+  ///
+  /// 注意: Monero の Fiat-Shamir トランスクリプト形式に従っており、`msg_hash` に何が
+  /// 含まれているべきかの前提があるため、誤用は非常に危険です。
+  #[cfg(feature = "legacy-signing")]
+  pub fn sign<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    msg_hash: &[u8; 32],
+    ring: &[CompressedPoint],
+    signer_index: usize,
+    key: &Zeroizing<Scalar>,
+  ) -> Option<RingSignature> {
+    let key = Zeroizing::new((**key).into());
+    let signer = ring.get(signer_index)?.decompress()?;
+    if curve25519_dalek::EdwardsPoint::mul_base(&key) != signer.into() {
+      None?;
+    }
+    let key_image = *key * Point::biased_hash(ring[signer_index].to_bytes()).into();
+
+    let mut buf = Vec::with_capacity(32 + (2 * 32 * ring.len()));
+    buf.extend_from_slice(msg_hash);
+
+    // 署名者以外のメンバーは (c, s) を乱数で選び、署名者のメンバーはノンスにコミットする
+    let nonce = Zeroizing::new(Scalar::random(&mut *rng).into());
+    let mut sigs = Vec::with_capacity(ring.len());
+    let mut sum = curve25519_dalek::Scalar::ZERO;
+    for (i, ring_member) in ring.iter().enumerate() {
+      #[allow(non_snake_case)]
+      let (Li, Ri) = if i == signer_index {
+        sigs.push(Signature { c: Scalar::ZERO, s: Scalar::ZERO });
+        (
+          curve25519_dalek::EdwardsPoint::mul_base(&nonce),
+          *nonce * Point::biased_hash(ring_member.to_bytes()).into(),
+        )
+      } else {
+        let sig = Signature { c: Scalar::random(&mut *rng), s: Scalar::random(&mut *rng) };
+        let decomp_ring_member = ring_member.decompress()?;
+        let res = (
+          curve25519_dalek::EdwardsPoint::vartime_double_scalar_mul_basepoint(
+            &sig.c.into(),
+            &decomp_ring_member.into(),
+            &sig.s.into(),
+          ),
+          (sig.s.into() * Point::biased_hash(ring_member.to_bytes()).into()) +
+            (sig.c.into() * key_image),
+        );
+        sum += sig.c.into();
+        sigs.push(sig);
+        res
+      };
+      buf.extend_from_slice(Li.compress().as_bytes());
+      buf.extend_from_slice(Ri.compress().as_bytes());
+    }
+
+    // c の合計がハッシュと一致するよう署名者の c を定め、s で環を閉じる
+    let c = Scalar::hash(buf).into() - sum;
+    sigs[signer_index] = Signature { c: Scalar::from(c), s: Scalar::from(*nonce - (c * *key)) };
+
+    let res = RingSignature { sigs };
+    debug_assert!(res.verify(msg_hash, ring, &Point::from(key_image).compress()));
+    Some(res)
+  }
+
   /// リング署名の検証。
   ///
   /// 注意: Monero の Fiat-Shamir トランスクリプト形式に従っており、`msg_hash` に何が
@@ -0,0 +1,120 @@
+use monero_address::Network;
+
+use crate::{transaction::Transaction, block::Block, bootstrap::*, genesis::genesis_block};
+
+#[derive(serde::Deserialize)]
+struct Vector {
+  hex: String,
+}
+
+fn packages() -> Vec<BlockPackage> {
+  let transactions: Vec<Vector> =
+    serde_json::from_str(include_str!("./vectors/transactions.json")).unwrap();
+  let transactions = transactions
+    .into_iter()
+    .map(|v| Transaction::read(&mut hex::decode(v.hex).unwrap().as_slice()).unwrap())
+    .collect::<Vec<_>>();
+
+  let genesis = genesis_block(Network::Mainnet);
+  let block = Block::new(
+    genesis.header.clone(),
+    genesis.miner_transaction().clone(),
+    transactions.iter().map(Transaction::hash).collect(),
+  )
+  .unwrap();
+
+  vec![
+    BlockPackage {
+      block: genesis,
+      transactions: vec![],
+      block_weight: 80,
+      cumulative_difficulty: 1,
+      coins_generated: 17_592_186_044_415,
+    },
+    BlockPackage {
+      block,
+      transactions,
+      block_weight: 300_000,
+      cumulative_difficulty: (1 << 64) + 2,
+      coins_generated: u64::MAX,
+    },
+  ]
+}
+
+fn file(packages: &[BlockPackage]) -> Vec<u8> {
+  let mut writer = BootstrapWriter::new(vec![]).unwrap();
+  for package in packages {
+    writer.write_package(package).unwrap();
+  }
+  writer.into_inner()
+}
+
+#[test]
+fn header() {
+  let file = file(&[]);
+  assert_eq!(file.len(), 4 + usize::try_from(HEADER_SIZE).unwrap());
+  assert_eq!(file[.. 19], hex_literal::hex!("86157228" "04000000" "00018008" "03000000" "000000"));
+  assert!(file[19 ..].iter().all(|byte| *byte == 0));
+
+  let mut reader = BootstrapReader::new(file.as_slice()).unwrap();
+  assert!(reader.read_package().unwrap().is_none());
+  assert!(reader.next().is_none());
+
+  let mut bad_magic = file.clone();
+  bad_magic[0] ^= 1;
+  assert!(BootstrapReader::new(bad_magic.as_slice()).is_err());
+  let mut bad_major_version = file.clone();
+  bad_major_version[8] = 1;
+  assert!(BootstrapReader::new(bad_major_version.as_slice()).is_err());
+  // Only the major version is checked
+  let mut other_minor_version = file.clone();
+  other_minor_version[9] = 2;
+  assert!(BootstrapReader::new(other_minor_version.as_slice()).is_ok());
+  assert!(BootstrapReader::new(&file[.. (file.len() - 1)]).is_err());
+}
+
+#[test]
+fn bootstrap() {
+  let packages = packages();
+  let file = file(&packages);
+
+  let mut reader = BootstrapReader::new(file.as_slice()).unwrap();
+  for package in &packages {
+    assert_eq!(&reader.read_package().unwrap().unwrap(), package);
+  }
+  assert!(reader.read_package().unwrap().is_none());
+
+  let blocks = BootstrapReader::new(file.as_slice()).unwrap().collect::<Result<Vec<_>, _>>();
+  assert_eq!(
+    blocks.unwrap(),
+    packages
+      .iter()
+      .map(|package| (package.block.clone(), package.transactions.clone()))
+      .collect::<Vec<_>>()
+  );
+
+  // A truncated chunk should be rejected
+  let mut reader = BootstrapReader::new(&file[.. (file.len() - 1)]).unwrap();
+  assert!(reader.next().unwrap().is_ok());
+  assert!(reader.next().unwrap().is_err());
+}
+
+#[test]
+fn mismatched_transactions() {
+  let mut packages = packages();
+  let mut package = packages.pop().unwrap();
+  package.transactions.reverse();
+  assert!(BootstrapWriter::new(vec![]).unwrap().write_package(&package).is_err());
+  package.transactions.pop();
+  assert!(package.serialize().is_err());
+
+  // A chunk whose transactions don't match its block should be rejected when read
+  let mut file = file(&[]);
+  let mut chunk = vec![];
+  package.block.write(&mut chunk).unwrap();
+  crate::io::write_vec(Transaction::write, &package.transactions, &mut chunk).unwrap();
+  chunk.extend([0; 4]);
+  file.extend(u32::try_from(chunk.len()).unwrap().to_le_bytes());
+  file.extend(chunk);
+  assert!(BootstrapReader::new(file.as_slice()).unwrap().next().unwrap().is_err());
+}
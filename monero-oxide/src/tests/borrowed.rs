@@ -0,0 +1,102 @@
+use monero_address::Network;
+
+use crate::{
+  transaction::{Pruned, Transaction},
+  block::Block,
+  borrowed::{InputRef, TransactionRef, BlockRef},
+  genesis::genesis_block,
+};
+
+#[derive(serde::Deserialize)]
+struct Vector {
+  hex: String,
+}
+
+fn tx_vectors() -> Vec<Vec<u8>> {
+  let transactions: Vec<Vector> =
+    serde_json::from_str(include_str!("./vectors/transactions.json")).unwrap();
+  let clsag_tx: Vector = serde_json::from_str(include_str!("./vectors/clsag_tx.json")).unwrap();
+  transactions.into_iter().chain([clsag_tx]).map(|v| hex::decode(v.hex).unwrap()).collect()
+}
+
+#[test]
+fn transaction_ref() {
+  for serialized in tx_vectors() {
+    let tx = Transaction::read(&mut serialized.as_slice()).unwrap();
+
+    // Reading should consume exactly the transaction
+    let mut with_trailing = serialized.clone();
+    with_trailing.extend([0xff; 3]);
+    let mut reader = with_trailing.as_slice();
+    let tx_ref = TransactionRef::read(&mut reader).unwrap();
+    assert_eq!(reader, [0xff; 3]);
+    assert_eq!(tx_ref.serialized(), serialized);
+
+    assert_eq!(tx_ref.version(), tx.version());
+    let prefix = tx_ref.prefix();
+    assert_eq!(prefix.additional_timelock(), tx.prefix().additional_timelock);
+    assert_eq!(prefix.inputs().len(), tx.prefix().inputs.len());
+    for (input_ref, input) in prefix.inputs().zip(&tx.prefix().inputs) {
+      if let InputRef::ToKey { key_offsets, .. } = input_ref {
+        assert_eq!(key_offsets.iter().len(), key_offsets.len());
+      }
+      assert_eq!(&input_ref.into_owned(), input);
+    }
+    assert_eq!(prefix.outputs().collect::<Vec<_>>(), tx.prefix().outputs);
+    assert_eq!(prefix.extra(), tx.prefix().extra);
+    assert_eq!(&prefix.into_owned(), tx.prefix());
+
+    assert_eq!(tx_ref.hash(), tx.hash());
+    assert_eq!(tx_ref.prunable_hash(), tx.prunable_hash());
+    assert_eq!(tx_ref.into_owned().unwrap(), tx);
+    assert_eq!(tx_ref.into_pruned().unwrap(), Transaction::<Pruned>::from(tx));
+
+    // Any truncation should be rejected
+    for len in 0 .. serialized.len() {
+      assert!(TransactionRef::read(&mut &serialized[.. len]).is_err());
+    }
+  }
+}
+
+#[test]
+fn block_ref() {
+  for network in [Network::Mainnet, Network::Testnet, Network::Stagenet] {
+    let genesis = genesis_block(network);
+    let block = Block::new(
+      genesis.header.clone(),
+      genesis.miner_transaction().clone(),
+      vec![[1; 32], [2; 32]],
+    )
+    .unwrap();
+
+    for block in [genesis, block] {
+      let serialized = block.serialize();
+      let mut reader = serialized.as_slice();
+      let block_ref = BlockRef::read(&mut reader).unwrap();
+      assert!(reader.is_empty());
+      assert_eq!(block_ref.serialized(), serialized);
+
+      assert_eq!(block_ref.header(), &block.header);
+      assert_eq!(block_ref.number(), block.number());
+      assert_eq!(block_ref.miner_transaction().hash(), block.miner_transaction().hash());
+      assert_eq!(block_ref.transactions().collect::<Vec<_>>(), block.transactions);
+      assert_eq!(block_ref.into_owned().unwrap(), block);
+
+      for len in 0 .. serialized.len() {
+        assert!(BlockRef::read(&mut &serialized[.. len]).is_err());
+      }
+    }
+  }
+
+  // A block whose miner transaction isn't a miner transaction should be rejected
+  let tx = hex::decode(
+    &serde_json::from_str::<Vec<Vector>>(include_str!("./vectors/transactions.json")).unwrap()[1]
+      .hex,
+  )
+  .unwrap();
+  let mut serialized = genesis_block(Network::Mainnet).header.serialize();
+  serialized.extend(tx);
+  serialized.push(0);
+  assert!(Block::read(&mut serialized.as_slice()).is_err());
+  assert!(BlockRef::read(&mut serialized.as_slice()).is_err());
+}
@@ -0,0 +1,75 @@
+use crate::consensus::*;
+
+#[test]
+fn medians() {
+  assert_eq!(median(&[]), 0);
+  assert_eq!(median(&[5]), 5);
+  assert_eq!(median(&[3, 1, 2]), 2);
+  // The mean of the middle values is floored
+  assert_eq!(median(&[4, 1, 2, 3]), 2);
+  assert_eq!(median(&[usize::MAX, usize::MAX]), usize::MAX);
+
+  // The median is never less than the full reward zone
+  assert_eq!(median_block_weight(&[], &[], 1), 20_000);
+  assert_eq!(median_block_weight(&[100], &[], 4), 60_000);
+  assert_eq!(median_block_weight(&[1_000_000, 1, 2_000_000], &[], 9), 1_000_000);
+
+  // The short-term median is bounded by the long-term median
+  let long_term_weights = vec![300_000; 10];
+  assert_eq!(median_block_weight(&[10_000_000], &long_term_weights, 16), 10_000_000);
+  assert_eq!(median_block_weight(&[20_000_000], &long_term_weights, 16), 15_000_000);
+  assert_eq!(median_block_weight(&[20_000_000], &[1_000_000], 16), 20_000_000);
+  // Yet only once the long-term median was introduced
+  assert_eq!(median_block_weight(&[20_000_000], &long_term_weights, 9), 20_000_000);
+
+  // The penalty only uses the bounded median from hard fork 12
+  assert_eq!(reward_median_block_weight(&[20_000_000], &long_term_weights, 9), 20_000_000);
+  assert_eq!(reward_median_block_weight(&[20_000_000], &long_term_weights, 11), 20_000_000);
+  assert_eq!(reward_median_block_weight(&[20_000_000], &long_term_weights, 12), 15_000_000);
+  assert_eq!(reward_median_block_weight(&[100], &long_term_weights, 11), 300_000);
+}
+
+#[test]
+fn long_term_weight() {
+  assert_eq!(long_term_block_weight(1_000_000, &[], 9), 1_000_000);
+  assert_eq!(long_term_block_weight(1_000_000, &[], 10), 420_000);
+  // Prior to hard fork 15, the long-term weight is at most 1.4 times the long-term median
+  assert_eq!(long_term_block_weight(1_000_000, &[500_000, 600_000, 400_000], 14), 700_000);
+  // Afterwards, it's at most 1.7 times the long-term median
+  assert_eq!(long_term_block_weight(1_000_000, &[500_000, 600_000, 400_000], 15), 850_000);
+  assert_eq!(long_term_block_weight(1_000_000, &[500_000, 600_000, 400_000], 16), 850_000);
+  assert_eq!(long_term_block_weight(100_000, &[500_000], 16), 100_000);
+}
+
+#[test]
+fn base() {
+  // The reward of the genesis block
+  assert_eq!(base_reward(0, 1), 17_592_186_044_415);
+  // The block time doubled with hard fork 2, halving the emission speed factor
+  assert_eq!(base_reward(0, 2), 35_184_372_088_831);
+  // The tail emission
+  assert_eq!(base_reward(MONEY_SUPPLY - 1, 1), 300_000_000_000);
+  assert_eq!(base_reward(MONEY_SUPPLY - 1, 16), 600_000_000_000);
+}
+
+#[test]
+fn reward() {
+  let base = base_reward(1 << 62, 16);
+  assert_eq!(block_reward(300_000, 0, 1 << 62, 16), Ok(base));
+  assert_eq!(block_reward(300_000, 300_000, 1 << 62, 16), Ok(base));
+  // The median is treated as at least the full reward zone
+  assert_eq!(block_reward(1, 300_000, 1 << 62, 16), Ok(base));
+
+  // A block 50% larger than the median has its reward reduced by a quarter
+  assert_eq!(block_reward(400_000, 600_000, 1 << 62, 16), Ok((base * 3) / 4));
+  assert!(block_reward(400_000, 400_001, 1 << 62, 16).unwrap() < base);
+  assert_eq!(block_reward(400_000, 800_000, 1 << 62, 16), Ok(0));
+  assert_eq!(
+    block_reward(400_000, 800_001, 1 << 62, 16),
+    Err(ConsensusError::OversizedBlock { weight: 800_001, limit: 800_000 })
+  );
+  assert_eq!(
+    block_reward(1, 600_001, 1 << 62, 16),
+    Err(ConsensusError::OversizedBlock { weight: 600_001, limit: 600_000 })
+  );
+}
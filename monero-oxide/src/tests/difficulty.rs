@@ -0,0 +1,143 @@
+use crate::difficulty::*;
+
+// A chain whose blocks are all `spacing` seconds apart and of the same difficulty
+fn constant_chain(blocks: usize, spacing: u64, difficulty: u128) -> (Vec<u64>, Vec<u128>) {
+  let timestamps = (1 ..= blocks).map(|i| u64::try_from(i).unwrap() * spacing).collect();
+  let cumulative_difficulties =
+    (1 ..= blocks).map(|i| 1 + (u128::try_from(i).unwrap() * difficulty)).collect();
+  (timestamps, cumulative_difficulties)
+}
+
+#[test]
+fn target() {
+  assert_eq!(difficulty_target(1), DIFFICULTY_TARGET_V1);
+  assert_eq!(difficulty_target(2), DIFFICULTY_TARGET_V2);
+  assert_eq!(difficulty_target(16), DIFFICULTY_TARGET_V2);
+}
+
+#[test]
+fn next() {
+  // Without at least two blocks, the difficulty is 1
+  assert_eq!(next_difficulty(&[], &[], DIFFICULTY_TARGET_V2), Ok(1));
+  assert_eq!(next_difficulty(&[100], &[5], DIFFICULTY_TARGET_V2), Ok(1));
+
+  // The difficulty is rounded up
+  assert_eq!(next_difficulty(&[0, 7], &[1, 2], DIFFICULTY_TARGET_V2), Ok(18));
+  // Timestamps are sorted and a time span of 0 is treated as 1
+  assert_eq!(next_difficulty(&[7, 0], &[1, 2], DIFFICULTY_TARGET_V2), Ok(18));
+  assert_eq!(next_difficulty(&[7, 7], &[1, 2], DIFFICULTY_TARGET_V2), Ok(120));
+
+  for blocks in [2, 100, DIFFICULTY_WINDOW, DIFFICULTY_BLOCKS_COUNT] {
+    let (timestamps, cumulative_difficulties) = constant_chain(blocks, 120, 1_000_000);
+    assert_eq!(
+      next_difficulty(&timestamps, &cumulative_difficulties, DIFFICULTY_TARGET_V2),
+      Ok(1_000_000)
+    );
+    assert_eq!(
+      next_difficulty(&timestamps, &cumulative_difficulties, DIFFICULTY_TARGET_V1),
+      Ok(500_000)
+    );
+  }
+}
+
+#[test]
+fn window() {
+  let (mut timestamps, cumulative_difficulties) =
+    constant_chain(DIFFICULTY_BLOCKS_COUNT, 120, 1_000_000);
+
+  // The most recent blocks are lagged and not considered
+  for timestamp in &mut timestamps[DIFFICULTY_WINDOW ..] {
+    *timestamp = u64::MAX;
+  }
+  assert_eq!(
+    next_difficulty(&timestamps, &cumulative_difficulties, DIFFICULTY_TARGET_V2),
+    Ok(1_000_000)
+  );
+
+  // Outlying timestamps are cut
+  timestamps[100] = u64::MAX;
+  timestamps[200] = 0;
+  let expected = next_difficulty(&timestamps, &cumulative_difficulties, DIFFICULTY_TARGET_V2);
+  timestamps[100] = 1;
+  timestamps[200] = u64::MAX - 1;
+  assert_eq!(
+    next_difficulty(&timestamps, &cumulative_difficulties, DIFFICULTY_TARGET_V2),
+    expected
+  );
+  assert!(expected.unwrap() < 1_000_000);
+}
+
+#[test]
+fn overflow() {
+  // 2**128 - 1 is divisible by 3, so this is exactly representable
+  assert_eq!(next_difficulty(&[0, 3], &[0, u128::MAX], 2), Ok((u128::MAX / 3) * 2));
+  assert_eq!(next_difficulty(&[0, 120], &[0, u128::MAX], 120), Ok(u128::MAX));
+  assert_eq!(next_difficulty(&[0, 119], &[0, u128::MAX], 120), Err(DifficultyError::Overflow));
+}
+
+#[test]
+fn errors() {
+  assert_eq!(next_difficulty(&[0, 1], &[1], 120), Err(DifficultyError::InvalidLength));
+  assert_eq!(
+    next_difficulty(&[0, 1], &[2, 2], 120),
+    Err(DifficultyError::NonIncreasingCumulativeDifficulty)
+  );
+  assert_eq!(
+    next_difficulty(&[0, 1], &[2, 1], 120),
+    Err(DifficultyError::NonIncreasingCumulativeDifficulty)
+  );
+}
+
+#[test]
+fn difficulty_window() {
+  let mut window = DifficultyWindow::new();
+  assert_eq!(window.cumulative_difficulty(), 1);
+  assert_eq!(window.next_difficulty(1), Ok(1));
+
+  let mut timestamps = vec![];
+  let mut cumulative_difficulties = vec![];
+  for i in 1 ..= 1000u64 {
+    let hardfork_version = if i < 500 { 1 } else { 2 };
+    let expected = window.next_difficulty(hardfork_version).unwrap();
+    let start = timestamps.len().saturating_sub(DIFFICULTY_BLOCKS_COUNT);
+    assert_eq!(
+      next_difficulty(
+        &timestamps[start ..],
+        &cumulative_difficulties[start ..],
+        difficulty_target(hardfork_version)
+      ),
+      Ok(expected)
+    );
+
+    // Vary the block times around the target
+    let timestamp = (i * 90) + ((i * 7919) % 61);
+    window.push(timestamp, expected).unwrap();
+    timestamps.push(timestamp);
+    cumulative_difficulties.push(window.cumulative_difficulty());
+  }
+  assert_eq!(window.cumulative_difficulty(), *cumulative_difficulties.last().unwrap());
+
+  assert_eq!(window.push(0, u128::MAX), Err(DifficultyError::Overflow));
+}
+
+#[test]
+fn hash() {
+  assert!(check_hash(&[0xff; 32], 1));
+  assert!(!check_hash(&[0xff; 32], 2));
+
+  // 2**255 may be multiplied by 1, yet not 2
+  let mut hash = [0; 32];
+  hash[31] = 0x80;
+  assert!(check_hash(&hash, 1));
+  assert!(!check_hash(&hash, 2));
+
+  // 2**128 may be multiplied by up to 2**128 - 1, yet 2**129 may not be
+  let mut hash = [0; 32];
+  hash[16] = 1;
+  assert!(check_hash(&hash, u128::MAX));
+  hash[16] = 2;
+  assert!(!check_hash(&hash, u128::MAX));
+  assert!(check_hash(&hash, u128::MAX >> 1));
+
+  assert!(check_hash(&[0; 32], u128::MAX));
+}
@@ -0,0 +1,32 @@
+use monero_address::Network;
+
+use crate::{
+  block::Block,
+  genesis::*,
+  checkpoints::{Checkpoint, check_checkpoint},
+};
+
+#[test]
+fn genesis() {
+  for network in [Network::Mainnet, Network::Testnet, Network::Stagenet] {
+    let block = genesis_block(network);
+    assert_eq!(block.number(), 0);
+    assert_eq!(block.hash(), genesis_hash(network));
+    assert_eq!(Block::read(&mut block.serialize().as_slice()).unwrap(), block);
+  }
+}
+
+#[test]
+fn checkpoints() {
+  for network in [Network::Mainnet, Network::Testnet, Network::Stagenet] {
+    let checkpoints = crate::checkpoints::checkpoints(network);
+    assert_eq!(checkpoints[0], Checkpoint { number: 0, hash: genesis_hash(network) });
+    assert!(checkpoints.windows(2).all(|pair| pair[0].number < pair[1].number));
+
+    assert!(check_checkpoint(network, 0, genesis_hash(network)));
+    assert!(!check_checkpoint(network, 0, [0; 32]));
+    // Blocks without a checkpoint are presumed consistent
+    assert!(check_checkpoint(network, 1, [0; 32]));
+  }
+  assert!(!check_checkpoint(Network::Testnet, 0, genesis_hash(Network::Mainnet)));
+}
@@ -0,0 +1,169 @@
+use monero_address::Network;
+
+use crate::{
+  ed25519::CompressedPoint,
+  ringct::{RctType, RctBase, PrunedRctProofs},
+  transaction::{Timelock, Input, Output, TransactionPrefix, Transaction, Pruned},
+  hardfork::*,
+};
+
+fn hardfork(version: u8) -> HardFork {
+  HardFork::new(version).unwrap()
+}
+
+// A pruned transaction with the specified ring size, amount of outputs, and RingCT type
+fn transaction(
+  ring_size: usize,
+  outputs: usize,
+  view_tags: bool,
+  rct_type: Option<RctType>,
+) -> Transaction<Pruned> {
+  let prefix = TransactionPrefix {
+    additional_timelock: Timelock::None,
+    inputs: vec![Input::ToKey {
+      amount: rct_type.is_none().then_some(1),
+      key_offsets: vec![1; ring_size],
+      key_image: CompressedPoint::G,
+    }],
+    outputs: (0 .. outputs)
+      .map(|_| Output {
+        amount: rct_type.is_none().then_some(1),
+        key: CompressedPoint::G,
+        view_tag: view_tags.then_some(0),
+      })
+      .collect(),
+    extra: vec![],
+  };
+  match rct_type {
+    None => Transaction::V1 { prefix, signatures: () },
+    Some(rct_type) => Transaction::V2 {
+      prefix,
+      proofs: Some(PrunedRctProofs {
+        rct_type,
+        base: RctBase {
+          fee: 0,
+          pseudo_outs: vec![],
+          encrypted_amounts: vec![],
+          commitments: vec![],
+        },
+      }),
+    },
+  }
+}
+
+#[test]
+fn heights() {
+  assert_eq!(HardFork::new(0), None);
+  assert_eq!(HardFork::new(17), None);
+  assert_eq!(HardFork::LATEST, hardfork(16));
+
+  for network in [Network::Mainnet, Network::Testnet, Network::Stagenet] {
+    assert_eq!(HardFork::at_height(network, 0), hardfork(1));
+    assert_eq!(HardFork::at_height(network, usize::MAX), HardFork::LATEST);
+    for version in 2 ..= 16 {
+      let activation = hardfork(version).activation_height(network);
+      assert!(activation > hardfork(version - 1).activation_height(network));
+      assert_eq!(HardFork::at_height(network, activation), hardfork(version));
+      assert_eq!(HardFork::at_height(network, activation - 1), hardfork(version - 1));
+    }
+  }
+
+  assert_eq!(HardFork::LATEST.activation_height(Network::Mainnet), 2_689_608);
+  assert_eq!(HardFork::at_height(Network::Mainnet, 3_000_000), hardfork(16));
+  assert_eq!(HardFork::at_height(Network::Mainnet, 2_000_000), hardfork(12));
+}
+
+#[test]
+fn rules() {
+  // One `RctType` is allowed by each RingCT hard fork, except during transitions
+  for version in 1 ..= 16 {
+    let allowed = [
+      RctType::AggregateMlsagBorromean,
+      RctType::MlsagBorromean,
+      RctType::MlsagBulletproofs,
+      RctType::MlsagBulletproofsCompactAmount,
+      RctType::ClsagBulletproof,
+      RctType::ClsagBulletproofPlus,
+    ]
+    .into_iter()
+    .filter(|rct_type| hardfork(version).rct_type_allowed(*rct_type))
+    .count();
+    let expected = match version {
+      1 ..= 3 => 0,
+      4 ..= 7 | 10 | 13 | 15 => 2,
+      8 => 3,
+      _ => 1,
+    };
+    assert_eq!(allowed, expected, "hard fork {version}");
+  }
+
+  assert_eq!(hardfork(1).maximum_ring_size(), None);
+  assert_eq!(hardfork(7).minimum_ring_size(), 7);
+  assert_eq!(hardfork(8).maximum_ring_size(), Some(11));
+  assert_eq!(hardfork(15).maximum_ring_size(), Some(16));
+  assert!(!hardfork(14).view_tags_allowed());
+  assert!(hardfork(15).view_tags_allowed() && !hardfork(15).view_tags_required());
+  assert!(hardfork(16).view_tags_required());
+}
+
+#[test]
+fn check_transaction() {
+  let latest = HardFork::LATEST;
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 2, true, Some(RctType::ClsagBulletproofPlus))),
+    Ok(())
+  );
+  assert_eq!(
+    hardfork(14).check_transaction(&transaction(11, 2, false, Some(RctType::ClsagBulletproof))),
+    Ok(())
+  );
+  assert_eq!(hardfork(5).check_transaction(&transaction(3, 1, false, None)), Ok(()));
+
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 2, true, None)),
+    Err(HardForkError::InvalidVersion)
+  );
+  assert_eq!(
+    hardfork(3).check_transaction(&transaction(3, 2, false, Some(RctType::MlsagBorromean))),
+    Err(HardForkError::InvalidVersion)
+  );
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 2, true, Some(RctType::ClsagBulletproof))),
+    Err(HardForkError::InvalidRctType(RctType::ClsagBulletproof))
+  );
+  for ring_size in [11, 17] {
+    assert_eq!(
+      latest.check_transaction(&transaction(
+        ring_size,
+        2,
+        true,
+        Some(RctType::ClsagBulletproofPlus)
+      )),
+      Err(HardForkError::InvalidRingSize(0))
+    );
+  }
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 2, false, Some(RctType::ClsagBulletproofPlus))),
+    Err(HardForkError::InvalidOutputTypes)
+  );
+  assert_eq!(
+    hardfork(15).check_transaction(&transaction(16, 2, false, Some(RctType::ClsagBulletproofPlus))),
+    Ok(())
+  );
+  assert_eq!(
+    hardfork(14).check_transaction(&transaction(11, 2, true, Some(RctType::ClsagBulletproof))),
+    Err(HardForkError::InvalidOutputTypes)
+  );
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 1, true, Some(RctType::ClsagBulletproofPlus))),
+    Err(HardForkError::TooFewOutputs)
+  );
+  assert_eq!(
+    latest.check_transaction(&transaction(16, 17, true, Some(RctType::ClsagBulletproofPlus))),
+    Err(HardForkError::TooManyOutputs)
+  );
+
+  let mut miner_transaction = transaction(16, 2, true, None);
+  miner_transaction.prefix_mut().inputs = vec![Input::Gen(0)];
+  assert_eq!(latest.check_transaction(&miner_transaction), Err(HardForkError::MinerTransaction));
+}
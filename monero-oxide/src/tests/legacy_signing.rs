@@ -0,0 +1,298 @@
+use zeroize::Zeroizing;
+
+use rand_core::OsRng;
+
+use curve25519_dalek::{scalar::Scalar as DScalar, EdwardsPoint};
+
+use crate::{
+  ed25519::*,
+  ring_signatures::RingSignature,
+  ringct::{
+    mlsag::{MlsagError, Mlsag, RingMatrix, AggregateRingMatrixBuilder},
+    borromean::BorromeanRange,
+    bulletproofs::Bulletproof,
+    EncryptedAmount, RctType, RctBase, RctPrunable, RctProofs,
+  },
+  transaction::{Input, Output, Timelock, TransactionPrefix, NotPruned, Transaction},
+  verify::VerificationError,
+};
+
+const RING_LEN: usize = 11;
+
+fn public_key(key: Scalar) -> CompressedPoint {
+  Point::from(EdwardsPoint::mul_base(&key.into())).compress()
+}
+
+// An output being spent, within a ring of random decoys
+struct Spend {
+  key: Zeroizing<Scalar>,
+  commitment: Commitment,
+  signer_index: usize,
+  ring: Vec<[CompressedPoint; 2]>,
+}
+
+impl Spend {
+  fn new(amount: u64, signer_index: usize) -> Spend {
+    let key = Zeroizing::new(Scalar::random(&mut OsRng));
+    let commitment = Commitment::new(Scalar::random(&mut OsRng), amount);
+    let ring = (0 .. RING_LEN)
+      .map(|i| {
+        if i == signer_index {
+          [public_key(*key), commitment.commit().compress()]
+        } else {
+          [public_key(Scalar::random(&mut OsRng)), public_key(Scalar::random(&mut OsRng))]
+        }
+      })
+      .collect();
+    Spend { key, commitment, signer_index, ring }
+  }
+
+  fn input(&self, amount: Option<u64>) -> Input {
+    let key_image =
+      (*self.key).into() * Point::biased_hash(self.ring[self.signer_index][0].to_bytes()).into();
+    Input::ToKey {
+      amount,
+      key_offsets: vec![1; RING_LEN],
+      key_image: Point::from(key_image).compress(),
+    }
+  }
+}
+
+fn prefix(inputs: Vec<Input>, amounts: &[Option<u64>]) -> TransactionPrefix {
+  TransactionPrefix {
+    additional_timelock: Timelock::None,
+    inputs,
+    outputs: amounts
+      .iter()
+      .map(|amount| Output {
+        amount: *amount,
+        key: public_key(Scalar::random(&mut OsRng)),
+        view_tag: None,
+      })
+      .collect(),
+    extra: vec![],
+  }
+}
+
+fn v1_transaction(spends: &[Spend], amounts: &[u64]) -> Transaction<NotPruned> {
+  let mut tx = Transaction::V1 {
+    prefix: prefix(
+      spends.iter().map(|spend| spend.input(Some(spend.commitment.amount))).collect(),
+      &amounts.iter().copied().map(Some).collect::<Vec<_>>(),
+    ),
+    signatures: vec![],
+  };
+  let signature_hash = tx.signature_hash().unwrap();
+  let Transaction::V1 { signatures, .. } = &mut tx else { unreachable!() };
+  for spend in spends {
+    let ring = spend.ring.iter().map(|member| member[0]).collect::<Vec<_>>();
+    signatures.push(
+      RingSignature::sign(&mut OsRng, &signature_hash, &ring, spend.signer_index, &spend.key)
+        .unwrap(),
+    );
+  }
+  tx
+}
+
+fn rct_transaction(
+  rct_type: RctType,
+  spends: &[Spend],
+  outputs: &[Commitment],
+  fee: u64,
+) -> Transaction<NotPruned> {
+  let commitments = outputs.iter().map(|output| output.commit().compress()).collect::<Vec<_>>();
+
+  // Rerandomize the inputs' commitments so the pseudo-outs' masks sum to the outputs' masks
+  let sum_output_masks = outputs.iter().map(|output| output.mask.into()).sum::<DScalar>();
+  let mut pseudo_masks =
+    (1 .. spends.len()).map(|_| Scalar::random(&mut OsRng).into()).collect::<Vec<DScalar>>();
+  pseudo_masks.push(sum_output_masks - pseudo_masks.iter().sum::<DScalar>());
+  let pseudo_outs = spends
+    .iter()
+    .zip(&pseudo_masks)
+    .map(|(spend, mask)| {
+      Commitment::new(Scalar::from(*mask), spend.commitment.amount).commit().compress()
+    })
+    .collect::<Vec<_>>();
+
+  let sign = |msg: &[u8; 32]| -> Vec<Mlsag> {
+    if rct_type == RctType::AggregateMlsagBorromean {
+      let mut matrix = AggregateRingMatrixBuilder::new(&commitments, fee).unwrap();
+      let mut keys = vec![];
+      for spend in spends {
+        matrix.push_ring(&spend.ring).unwrap();
+        keys.push(spend.key.clone());
+      }
+      let sum_input_masks =
+        spends.iter().map(|spend| spend.commitment.mask.into()).sum::<DScalar>();
+      keys.push(Zeroizing::new(Scalar::from(sum_input_masks - sum_output_masks)));
+      let matrix = matrix.build().unwrap();
+      vec![Mlsag::sign(&mut OsRng, msg, &matrix, spends[0].signer_index, &keys).unwrap()]
+    } else {
+      spends
+        .iter()
+        .zip(&pseudo_outs)
+        .zip(&pseudo_masks)
+        .map(|((spend, pseudo_out), pseudo_mask)| {
+          let matrix = RingMatrix::individual(&spend.ring, *pseudo_out).unwrap();
+          let keys = [
+            spend.key.clone(),
+            Zeroizing::new(Scalar::from(spend.commitment.mask.into() - pseudo_mask)),
+          ];
+          Mlsag::sign(&mut OsRng, msg, &matrix, spend.signer_index, &keys).unwrap()
+        })
+        .collect()
+    }
+  };
+
+  let borromean =
+    || outputs.iter().map(|output| BorromeanRange::prove(&mut OsRng, output)).collect();
+  let bulletproof = || Bulletproof::prove(&mut OsRng, outputs.to_vec()).unwrap();
+  // The MLSAGs aren't bound to by the signature hash, so they're signed over a placeholder message
+  // and then replaced
+  let mlsags = sign(&[0; 32]);
+  let prunable = match rct_type {
+    RctType::AggregateMlsagBorromean => {
+      RctPrunable::AggregateMlsagBorromean { mlsag: mlsags[0].clone(), borromean: borromean() }
+    }
+    RctType::MlsagBorromean => RctPrunable::MlsagBorromean { mlsags, borromean: borromean() },
+    RctType::MlsagBulletproofs => RctPrunable::MlsagBulletproofs {
+      mlsags,
+      pseudo_outs: pseudo_outs.clone(),
+      bulletproof: bulletproof(),
+    },
+    RctType::MlsagBulletproofsCompactAmount => RctPrunable::MlsagBulletproofsCompactAmount {
+      mlsags,
+      pseudo_outs: pseudo_outs.clone(),
+      bulletproof: bulletproof(),
+    },
+    RctType::ClsagBulletproof | RctType::ClsagBulletproofPlus => panic!("non-legacy RctType"),
+  };
+
+  let mut tx = Transaction::V2 {
+    prefix: prefix(
+      spends.iter().map(|spend| spend.input(None)).collect(),
+      &vec![None; outputs.len()],
+    ),
+    proofs: Some(RctProofs {
+      base: RctBase {
+        fee,
+        pseudo_outs: if rct_type == RctType::MlsagBorromean { pseudo_outs.clone() } else { vec![] },
+        encrypted_amounts: outputs
+          .iter()
+          .map(|_| {
+            if rct_type.compact_encrypted_amounts() {
+              EncryptedAmount::Compact { amount: [0; 8] }
+            } else {
+              EncryptedAmount::Original { mask: [0; 32], amount: [0; 32] }
+            }
+          })
+          .collect(),
+        commitments: commitments.clone(),
+      },
+      prunable,
+    }),
+  };
+
+  let signature_hash = tx.signature_hash().unwrap();
+  let Transaction::V2 { proofs: Some(proofs), .. } = &mut tx else { unreachable!() };
+  match &mut proofs.prunable {
+    RctPrunable::AggregateMlsagBorromean { mlsag, .. } => {
+      *mlsag = sign(&signature_hash).swap_remove(0);
+    }
+    RctPrunable::MlsagBorromean { mlsags, .. } |
+    RctPrunable::MlsagBulletproofs { mlsags, .. } |
+    RctPrunable::MlsagBulletproofsCompactAmount { mlsags, .. } => *mlsags = sign(&signature_hash),
+    RctPrunable::Clsag { .. } => unreachable!(),
+  }
+  tx
+}
+
+fn rings(spends: &[Spend]) -> Vec<Vec<[CompressedPoint; 2]>> {
+  spends.iter().map(|spend| spend.ring.clone()).collect()
+}
+
+#[test]
+fn sign_ring_signatures() {
+  let spends = [Spend::new(3000, 0), Spend::new(5000, RING_LEN - 1)];
+  let tx = v1_transaction(&spends, &[6000, 1500]);
+
+  let read = Transaction::read(&mut tx.serialize().as_slice()).unwrap();
+  assert_eq!(read, tx);
+  read.verify(&mut OsRng, &rings(&spends)).unwrap();
+
+  let mut swapped = rings(&spends);
+  swapped[1].swap(0, RING_LEN - 1);
+  assert_eq!(tx.verify(&mut OsRng, &swapped), Err(VerificationError::InvalidRingSignature(1)));
+
+  // The key must be for the signing ring member
+  let ring = spends[0].ring.iter().map(|member| member[0]).collect::<Vec<_>>();
+  assert!(RingSignature::sign(&mut OsRng, &[0; 32], &ring, 1, &spends[0].key).is_none());
+  assert!(RingSignature::sign(&mut OsRng, &[0; 32], &ring, RING_LEN, &spends[0].key).is_none());
+}
+
+#[test]
+fn sign_mlsags() {
+  for rct_type in [
+    RctType::AggregateMlsagBorromean,
+    RctType::MlsagBorromean,
+    RctType::MlsagBulletproofs,
+    RctType::MlsagBulletproofsCompactAmount,
+  ] {
+    // Aggregate MLSAGs require the signer be at the same index in every ring
+    let second_index = if rct_type == RctType::AggregateMlsagBorromean { 3 } else { 7 };
+    let spends = [Spend::new(3000, 3), Spend::new(5000, second_index)];
+    let outputs = [
+      Commitment::new(Scalar::random(&mut OsRng), 6000),
+      Commitment::new(Scalar::random(&mut OsRng), 1500),
+    ];
+    let tx = rct_transaction(rct_type, &spends, &outputs, 500);
+
+    let read = Transaction::read(&mut tx.serialize().as_slice()).unwrap();
+    assert_eq!(read, tx);
+    let Transaction::V2 { proofs: Some(proofs), .. } = &read else { panic!("no RingCT proofs") };
+    assert_eq!(proofs.rct_type(), rct_type);
+    read.verify(&mut OsRng, &rings(&spends)).unwrap();
+
+    let mut swapped = rings(&spends);
+    swapped[1].swap(0, 1);
+    let index = if rct_type == RctType::AggregateMlsagBorromean { 0 } else { 1 };
+    assert_eq!(
+      tx.verify(&mut OsRng, &swapped),
+      Err(VerificationError::InvalidMlsag(index, MlsagError::InvalidCi))
+    );
+  }
+}
+
+#[test]
+fn sign_mlsag_invalid_keys() {
+  let spend = Spend::new(3000, 2);
+  let matrix = RingMatrix::individual(&spend.ring, spend.commitment.commit().compress()).unwrap();
+  let keys = [spend.key.clone(), Zeroizing::new(Scalar::ZERO)];
+  Mlsag::sign(&mut OsRng, &[0; 32], &matrix, 2, &keys).unwrap();
+  assert_eq!(Mlsag::sign(&mut OsRng, &[0; 32], &matrix, 1, &keys), Err(MlsagError::InvalidKey));
+  assert_eq!(
+    Mlsag::sign(&mut OsRng, &[0; 32], &matrix, RING_LEN, &keys),
+    Err(MlsagError::InvalidRing)
+  );
+  assert_eq!(
+    Mlsag::sign(&mut OsRng, &[0; 32], &matrix, 2, &keys[.. 1]),
+    Err(MlsagError::InvalidKey)
+  );
+}
+
+#[test]
+fn prove_borromean() {
+  for amount in [0, 1, 0xdead_beef, u64::MAX] {
+    let commitment = Commitment::new(Scalar::random(&mut OsRng), amount);
+    let proof = BorromeanRange::prove(&mut OsRng, &commitment);
+    assert!(proof.verify(&commitment.commit().compress()));
+
+    let mut serialized = vec![];
+    proof.write(&mut serialized).unwrap();
+    assert_eq!(BorromeanRange::read(&mut serialized.as_slice()).unwrap(), proof);
+
+    let other = Commitment::new(commitment.mask, amount.wrapping_add(1));
+    assert!(!proof.verify(&other.commit().compress()));
+  }
+}
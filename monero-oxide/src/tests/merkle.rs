@@ -2,7 +2,10 @@ use std_shims::vec::Vec;
 
 use rand_core::{RngCore, OsRng};
 
-use crate::{primitives::keccak256, merkle::merkle_root};
+use crate::{
+  primitives::keccak256,
+  merkle::{merkle_root, merkle_branch, merkle_root_from_branch, verify_merkle_branch},
+};
 
 fn old_merkle_root(mut leafs: Vec<[u8; 32]>) -> Option<[u8; 32]> {
   match leafs.len() {
@@ -72,6 +75,53 @@ fn merkle() {
   }
 }
 
+#[test]
+fn branch() {
+  assert!(merkle_branch(&[], 0).is_none());
+  assert!(merkle_branch(&[[0; 32]], 1).is_none());
+  assert_eq!(merkle_branch(&[[1; 32]], 0), Some(vec![]));
+  assert_eq!(merkle_root_from_branch([1; 32], 0, 1, &[]), Some([1; 32]));
+
+  for i in 1 .. 66 {
+    let mut leaves = Vec::with_capacity(i);
+    for _ in 0 .. i {
+      let mut leaf = [0; 32];
+      OsRng.fill_bytes(&mut leaf);
+      leaves.push(leaf);
+    }
+    let root = merkle_root(leaves.clone()).unwrap();
+
+    for (index, leaf) in leaves.iter().enumerate() {
+      let branch = merkle_branch(&leaves, index).unwrap();
+      // The branch is of the depth of this leaf within the tree
+      let depth = usize::try_from(i.next_power_of_two().ilog2()).unwrap();
+      assert!((branch.len() == depth) || (branch.len() == (depth - 1)));
+      assert!(verify_merkle_branch(*leaf, index, i, &branch, root));
+
+      // A distinct leaf or index shouldn't verify
+      let mut other_leaf = *leaf;
+      other_leaf[0] ^= 1;
+      assert!(!verify_merkle_branch(other_leaf, index, i, &branch, root));
+      if i != 1 {
+        assert!(!verify_merkle_branch(*leaf, (index + 1) % i, i, &branch, root));
+      }
+      // The branch's length binds the leaf count, so it shouldn't verify with another
+      assert!(!verify_merkle_branch(*leaf, index, 2 * i, &branch, root));
+
+      // Nor should a modified or truncated branch
+      if let Some(last) = branch.last() {
+        let mut modified = branch.clone();
+        modified[0][0] ^= 1;
+        assert!(!verify_merkle_branch(*leaf, index, i, &modified, root));
+        assert!(!verify_merkle_branch(*leaf, index, i, &branch[.. (branch.len() - 1)], root));
+        let mut extended = branch.clone();
+        extended.push(*last);
+        assert!(merkle_root_from_branch(*leaf, index, i, &extended).is_none());
+      }
+    }
+  }
+}
+
 /*
   Monero's Merkle tree code historically had a bug in it where it would produce an incorrect tree
   hash. Unfortunately, this condition arose on the Monero mainnet, with the decision being made to
@@ -1,2 +1,11 @@
 mod merkle;
+mod difficulty;
+mod consensus;
 mod transaction;
+mod verify;
+mod hardfork;
+mod genesis;
+mod borrowed;
+mod bootstrap;
+#[cfg(feature = "legacy-signing")]
+mod legacy_signing;
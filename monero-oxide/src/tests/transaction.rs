@@ -298,3 +298,67 @@ fn pruned_with_prunable() {
     assert_eq!([pruned.serialize(), prunable].concat(), tx_bytes);
   }
 }
+
+#[test]
+fn from_pruned_with_prunable() {
+  for v in tx_vectors() {
+    let tx = Transaction::read(&mut hex::decode(v.hex.clone()).unwrap().as_slice()).unwrap();
+    let hash = tx.hash();
+    let prunable_hash = tx.prunable_hash();
+
+    let (pruned, prunable) = tx.clone().pruned_with_prunable();
+    match &tx {
+      Transaction::V1 { .. } => {
+        assert_eq!(prunable_hash, None);
+        assert_eq!(pruned.hash_with_prunable_hash([0; 32]), None);
+      }
+      Transaction::V2 { proofs: None, .. } => {
+        assert!(prunable.is_empty());
+        assert_eq!(prunable_hash, Some([0; 32]));
+        assert_eq!(pruned.hash_with_prunable_hash([0; 32]), Some(hash));
+      }
+      Transaction::V2 { proofs: Some(_), .. } => {
+        assert_eq!(prunable_hash, Some(crate::primitives::keccak256(&prunable)));
+        assert_eq!(pruned.hash_with_prunable_hash(prunable_hash.unwrap()), Some(hash));
+      }
+    }
+
+    assert_eq!(Transaction::from_pruned_with_prunable(pruned.clone(), &prunable).unwrap(), tx);
+
+    // Extra bytes, or missing bytes, should be rejected
+    if !prunable.is_empty() {
+      let mut extended = prunable.clone();
+      extended.push(0);
+      assert!(Transaction::from_pruned_with_prunable(pruned.clone(), &extended).is_err());
+      assert!(
+        Transaction::from_pruned_with_prunable(pruned, &prunable[.. (prunable.len() - 1)]).is_err()
+      );
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+  #[derive(serde::Deserialize)]
+  struct TxData {
+    hex: String,
+    tx: Value,
+  }
+  let clsag_tx: TxData = serde_json::from_str(CLSAG_TX).unwrap();
+
+  let vectors = tx_vectors().into_iter().map(|v| (v.hex, v.tx));
+  for (hex, json) in vectors.chain([(clsag_tx.hex, clsag_tx.tx)]) {
+    let tx = Transaction::read(&mut hex::decode(hex).unwrap().as_slice()).unwrap();
+
+    // Our JSON should match monerod's
+    assert_eq!(serde_json::to_value(&tx).unwrap(), json);
+    // And monerod's JSON should deserialize to the same transaction
+    assert_eq!(serde_json::from_value::<Transaction>(json).unwrap(), tx);
+  }
+
+  let block = crate::genesis::genesis_block(monero_address::Network::Mainnet);
+  let json = serde_json::to_value(&block).unwrap();
+  assert_eq!(json["prev_id"], hex::encode([0; 32]));
+  assert_eq!(serde_json::from_value::<crate::block::Block>(json).unwrap(), block);
+}
@@ -0,0 +1,205 @@
+use serde_json::Value;
+
+use rand_core::OsRng;
+
+use crate::{
+  ed25519::{CompressedPoint, Point},
+  ringct::clsag::ClsagError,
+  transaction::{NotPruned, Transaction, TransactionPrefix, Timelock, Input},
+  block::{BlockHeader, Block},
+  verify::{VerificationError, BlockVerificationError, BatchVerifier},
+};
+
+const CLSAG_TX: &str = include_str!("./vectors/clsag_tx.json");
+const RING_DATA: &str = include_str!("./vectors/ring_data.json");
+
+fn compressed_point(hex: &Value) -> CompressedPoint {
+  CompressedPoint::from(<[u8; 32]>::try_from(hex::decode(hex.as_str().unwrap()).unwrap()).unwrap())
+}
+
+fn clsag_tx() -> (Transaction<NotPruned>, Vec<Vec<[CompressedPoint; 2]>>) {
+  #[derive(serde::Deserialize)]
+  struct TxData {
+    hex: String,
+  }
+  #[derive(serde::Deserialize)]
+  struct OutData {
+    key: Value,
+    mask: Value,
+  }
+  let tx_data = serde_json::from_str::<TxData>(CLSAG_TX).unwrap();
+  let tx = Transaction::read(&mut hex::decode(tx_data.hex).unwrap().as_slice()).unwrap();
+  let rings = serde_json::from_str::<Vec<Vec<OutData>>>(RING_DATA)
+    .unwrap()
+    .into_iter()
+    .map(|ring| {
+      ring.iter().map(|out| [compressed_point(&out.key), compressed_point(&out.mask)]).collect()
+    })
+    .collect();
+  (tx, rings)
+}
+
+#[test]
+fn verify_clsag_tx() {
+  let (tx, rings) = clsag_tx();
+  tx.verify(&mut OsRng, &rings).unwrap();
+}
+
+#[test]
+fn verify_invalid_rings() {
+  let (tx, mut rings) = clsag_tx();
+
+  assert_eq!(tx.verify(&mut OsRng, &rings[.. 1]), Err(VerificationError::InvalidAmountOfRings));
+
+  let mut short_rings = rings.clone();
+  short_rings[1].pop();
+  assert_eq!(tx.verify(&mut OsRng, &short_rings), Err(VerificationError::InvalidRingLength(1)));
+
+  rings[1].swap(0, 1);
+  assert_eq!(
+    tx.verify(&mut OsRng, &rings),
+    Err(VerificationError::InvalidClsag(1, ClsagError::InvalidC1))
+  );
+}
+
+#[test]
+fn verify_unbalanced() {
+  let (mut tx, rings) = clsag_tx();
+  let Transaction::V2 { proofs: Some(ref mut proofs), .. } = tx else { panic!("non-RCT vector") };
+  proofs.base.fee += 1;
+  assert_eq!(tx.verify(&mut OsRng, &rings), Err(VerificationError::Unbalanced));
+}
+
+#[test]
+fn verify_key_images() {
+  let (tx, rings) = clsag_tx();
+
+  let set_second_key_image = |replacement| {
+    let mut tx = tx.clone();
+    let Input::ToKey { key_image, .. } = &mut tx.prefix_mut().inputs[1] else {
+      panic!("miner transaction vector")
+    };
+    *key_image = replacement;
+    tx
+  };
+
+  assert_eq!(
+    set_second_key_image(CompressedPoint::IDENTITY).verify(&mut OsRng, &rings),
+    Err(VerificationError::InvalidKeyImage(1))
+  );
+
+  let Input::ToKey { key_image: first, .. } = tx.prefix().inputs[0] else {
+    panic!("miner transaction vector")
+  };
+  assert_eq!(
+    set_second_key_image(first).verify(&mut OsRng, &rings),
+    Err(VerificationError::DuplicateKeyImage)
+  );
+}
+
+#[test]
+fn verify_range_proof() {
+  let (mut tx, rings) = clsag_tx();
+  let Transaction::V2 { proofs: Some(ref mut proofs), .. } = tx else { panic!("non-RCT vector") };
+  // Shift value from one output to another, preserving the balance yet breaking the range proof
+  let h = CompressedPoint::H.decompress().unwrap().into();
+  let commitments = &mut proofs.base.commitments;
+  commitments[0] = Point::from(commitments[0].decompress().unwrap().into() + h).compress();
+  commitments[1] = Point::from(commitments[1].decompress().unwrap().into() - h).compress();
+  assert_eq!(tx.verify(&mut OsRng, &rings), Err(VerificationError::InvalidRangeProof));
+}
+
+#[test]
+fn verify_miner_transaction() {
+  let miner = Transaction::<NotPruned>::V2 {
+    prefix: TransactionPrefix {
+      additional_timelock: Timelock::None,
+      inputs: vec![Input::Gen(0)],
+      outputs: vec![],
+      extra: vec![],
+    },
+    proofs: None,
+  };
+  assert_eq!(miner.verify(&mut OsRng, &[]), Err(VerificationError::MinerTransaction));
+}
+
+fn block_with(transactions: &[Transaction<NotPruned>]) -> Block {
+  let miner_transaction = Transaction::V2 {
+    prefix: TransactionPrefix {
+      additional_timelock: Timelock::Block(60),
+      inputs: vec![Input::Gen(1)],
+      outputs: vec![],
+      extra: vec![],
+    },
+    proofs: None,
+  };
+  Block::new(
+    BlockHeader {
+      hardfork_version: 16,
+      hardfork_signal: 16,
+      timestamp: 0,
+      previous: [0; 32],
+      nonce: 0,
+    },
+    miner_transaction,
+    transactions.iter().map(Transaction::hash).collect(),
+  )
+  .unwrap()
+}
+
+#[test]
+fn batch_verify_block() {
+  let (tx, rings) = clsag_tx();
+
+  let transactions = vec![tx.clone(), tx.clone()];
+  let block = block_with(&transactions);
+  block.verify_transactions(&mut OsRng, &transactions, &[rings.clone(), rings.clone()]).unwrap();
+
+  assert_eq!(
+    block.verify_transactions(&mut OsRng, &transactions[.. 1], core::slice::from_ref(&rings)),
+    Err(BlockVerificationError::TransactionsMismatch)
+  );
+  assert_eq!(
+    block.verify_transactions(&mut OsRng, &transactions, core::slice::from_ref(&rings)),
+    Err(BlockVerificationError::InvalidAmountOfRings)
+  );
+
+  // The transaction's fee is bound to by its signatures, so this is detected when queued
+  let mut unbalanced = tx.clone();
+  let Transaction::V2 { proofs: Some(ref mut proofs), .. } = unbalanced else {
+    panic!("non-RCT vector")
+  };
+  proofs.base.fee += 1;
+  let transactions = vec![tx.clone(), unbalanced.clone()];
+  let block = block_with(&transactions);
+  assert_eq!(
+    block.verify_transactions(&mut OsRng, &transactions, &[rings.clone(), rings.clone()]),
+    Err(BlockVerificationError::InvalidTransaction {
+      index: 1,
+      error: VerificationError::InvalidClsag(0, ClsagError::InvalidC1)
+    })
+  );
+
+  // The ring signatures are verified in parallel, yet the first invalid transaction is reported
+  let transactions = vec![tx.clone(), tx.clone(), unbalanced.clone(), tx, unbalanced];
+  let block = block_with(&transactions);
+  assert_eq!(
+    block.verify_transactions(&mut OsRng, &transactions, &vec![rings; 5]),
+    Err(BlockVerificationError::InvalidTransaction {
+      index: 2,
+      error: VerificationError::InvalidClsag(0, ClsagError::InvalidC1)
+    })
+  );
+}
+
+#[test]
+fn batch_verify() {
+  let (tx, rings) = clsag_tx();
+
+  assert!(BatchVerifier::new().verify());
+
+  let mut batch = BatchVerifier::new();
+  batch.queue(&mut OsRng, &tx, &rings).unwrap();
+  batch.queue(&mut OsRng, &tx, &rings).unwrap();
+  assert!(batch.verify());
+}
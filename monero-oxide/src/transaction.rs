@@ -10,7 +10,7 @@ use crate::{
   ed25519::*,
   primitives::{UpperBound, LowerBound, keccak256},
   ring_signatures::RingSignature,
-  ringct::{bulletproofs::Bulletproof, PrunedRctProofs},
+  ringct::{bulletproofs::Bulletproof, RctPrunable, RctProofs, PrunedRctProofs},
 };
 
 /// An input in the Monero protocol.
@@ -547,7 +547,7 @@ impl<P: PotentiallyPruned> Transaction<P> {
 
   // The hash of the transaction.
   #[allow(clippy::needless_pass_by_value)]
-  fn hash_with_prunable_hash(&self, prunable: PrunableHash<'_>) -> [u8; 32] {
+  fn hash_with_prunable(&self, prunable: PrunableHash<'_>) -> [u8; 32] {
     match self {
       Transaction::V1 { prefix, .. } => {
         let mut buf = Vec::with_capacity(512);
@@ -598,20 +598,29 @@ impl Transaction<NotPruned> {
   /// The hash of the transaction.
   pub fn hash(&self) -> [u8; 32] {
     match self {
-      Transaction::V1 { signatures, .. } => {
-        self.hash_with_prunable_hash(PrunableHash::V1(signatures))
-      }
-      Transaction::V2 { proofs, .. } => {
-        self.hash_with_prunable_hash(PrunableHash::V2(if let Some(proofs) = proofs {
-          let mut buf = Vec::with_capacity(1024);
-          proofs
-            .prunable
-            .write(&mut buf, proofs.rct_type())
-            .expect("write failed but <Vec as io::Write> doesn't fail");
-          keccak256(buf)
-        } else {
-          [0; 32]
-        }))
+      Transaction::V1 { signatures, .. } => self.hash_with_prunable(PrunableHash::V1(signatures)),
+      Transaction::V2 { .. } => self.hash_with_prunable(PrunableHash::V2(
+        self.prunable_hash().expect("v2 transaction didn't have a prunable hash"),
+      )),
+    }
+  }
+
+  /// The hash of the prunable part of the transaction.
+  ///
+  /// This is the value pruned nodes retain in place of the prunable data, and is all zeroes for a
+  /// version 2 transaction without RingCT proofs. This returns None for version 1 transactions,
+  /// which can't be hashed without their (prunable) ring signatures.
+  pub fn prunable_hash(&self) -> Option<[u8; 32]> {
+    match self {
+      Transaction::V1 { .. } => None,
+      Transaction::V2 { proofs: None, .. } => Some([0; 32]),
+      Transaction::V2 { proofs: Some(proofs), .. } => {
+        let mut buf = Vec::with_capacity(1024);
+        proofs
+          .prunable
+          .write(&mut buf, proofs.rct_type())
+          .expect("write failed but <Vec as io::Write> doesn't fail");
+        Some(keccak256(buf))
       }
     }
   }
@@ -625,9 +634,9 @@ impl Transaction<NotPruned> {
         if (prefix.inputs.len() == 1) && matches!(prefix.inputs[0], Input::Gen(_)) {
           None?;
         }
-        self.hash_with_prunable_hash(PrunableHash::V1(&[]))
+        self.hash_with_prunable(PrunableHash::V1(&[]))
       }
-      Transaction::V2 { proofs, .. } => self.hash_with_prunable_hash({
+      Transaction::V2 { proofs, .. } => self.hash_with_prunable({
         let Some(proofs) = proofs else { None? };
         let mut buf = Vec::with_capacity(1024);
         proofs
@@ -669,6 +678,53 @@ impl Transaction<NotPruned> {
     }
   }
 
+  /// Reassemble a transaction from its pruned and serialized prunable part.
+  ///
+  /// This is the inverse of `Transaction::pruned_with_prunable`. The prunable part must be
+  /// exactly the serialization of the transaction's prunable data.
+  pub fn from_pruned_with_prunable(
+    pruned: Transaction<Pruned>,
+    mut prunable: &[u8],
+  ) -> io::Result<Self> {
+    let r = &mut prunable;
+    let tx = match pruned {
+      Transaction::V1 { prefix, .. } => {
+        let signatures = if (prefix.inputs.len() == 1) && matches!(prefix.inputs[0], Input::Gen(_))
+        {
+          vec![]
+        } else {
+          Vec::<RingSignature>::read_signatures(&prefix.inputs, r)?
+        };
+        Transaction::V1 { prefix, signatures }
+      }
+      Transaction::V2 { prefix, proofs } => {
+        let proofs = proofs
+          .map(|PrunedRctProofs { rct_type, base }| {
+            let ring_length = prefix.inputs.first().map_or(0, |input| match input {
+              Input::Gen(_) => 0,
+              Input::ToKey { key_offsets, .. } => key_offsets.len(),
+            });
+            Ok::<_, io::Error>(RctProofs {
+              base,
+              prunable: RctPrunable::read(
+                rct_type,
+                ring_length,
+                prefix.inputs.len(),
+                prefix.outputs.len(),
+                r,
+              )?,
+            })
+          })
+          .transpose()?;
+        Transaction::V2 { prefix, proofs }
+      }
+    };
+    if !r.is_empty() {
+      Err(io::Error::other("prunable data had extra bytes after it"))?;
+    }
+    Ok(tx)
+  }
+
   fn is_rct_bulletproof(&self) -> bool {
     match self {
       Transaction::V1 { .. } => false,
@@ -711,6 +767,20 @@ impl Transaction<NotPruned> {
   }
 }
 
+impl Transaction<Pruned> {
+  /// The hash of the transaction, given the hash of its prunable part.
+  ///
+  /// The prunable hash is as returned by `Transaction::prunable_hash`, and as retained by pruned
+  /// nodes. This returns None for version 1 transactions, which can't be hashed without their
+  /// (prunable) ring signatures.
+  pub fn hash_with_prunable_hash(&self, prunable_hash: [u8; 32]) -> Option<[u8; 32]> {
+    match self {
+      Transaction::V1 { .. } => None,
+      Transaction::V2 { .. } => Some(self.hash_with_prunable(PrunableHash::V2(prunable_hash))),
+    }
+  }
+}
+
 impl From<Transaction<NotPruned>> for Transaction<Pruned> {
   fn from(tx: Transaction<NotPruned>) -> Transaction<Pruned> {
     match tx {
@@ -0,0 +1,634 @@
+// トランザクションの合意検証（署名・範囲証明・残高・キーイメージ）。
+// 各証明の検証器を RctType ごとに束ね、どの検査に失敗したかを型付きエラーで返します。
+#[allow(unused_imports)]
+use std_shims::prelude::*;
+use std_shims::sync::LazyLock;
+
+use rand_core::{RngCore, CryptoRng};
+
+use curve25519_dalek::{
+  traits::{IsIdentity, VartimeMultiscalarMul},
+  scalar::Scalar as DScalar,
+  EdwardsPoint,
+};
+
+use crate::{
+  ed25519::*,
+  ringct::{
+    mlsag::{MlsagError, RingMatrix, AggregateRingMatrixBuilder},
+    clsag::ClsagError,
+    borromean::BorromeanRange,
+    bulletproofs::Bulletproof,
+    RctType, RctPrunable, RctProofs,
+  },
+  transaction::{Input, Transaction, NotPruned},
+  block::Block,
+};
+
+// A static for `H` as it's frequently used yet this decompression is expensive.
+static H: LazyLock<EdwardsPoint> = LazyLock::new(|| {
+  CompressedPoint::H.decompress().expect("couldn't decompress `CompressedPoint::H`").into()
+});
+
+/// An error from verifying a transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum VerificationError {
+  /// The transaction was a miner transaction, which has no signatures to verify.
+  #[error("miner transactions can't be verified as signed transactions")]
+  MinerTransaction,
+  /// The transaction had no inputs.
+  #[error("transaction had no inputs")]
+  NoInputs,
+  /// The transaction was a version 2 transaction without RingCT proofs.
+  #[error("transaction was missing its proofs")]
+  MissingProofs,
+  /// The amount of rings provided didn't match the amount of inputs.
+  #[error("amount of rings didn't match the amount of inputs")]
+  InvalidAmountOfRings,
+  /// The ring for the specified input didn't match the input's amount of key offsets.
+  #[error("ring for input {0} had an invalid length")]
+  InvalidRingLength(usize),
+  /// The proofs were inconsistent with the transaction's amount of inputs/outputs.
+  #[error("proofs were inconsistent with the transaction's inputs/outputs")]
+  MalformedProofs,
+  /// The key image for the specified input was invalid (such as being identity or torsioned).
+  #[error("key image for input {0} was invalid")]
+  InvalidKeyImage(usize),
+  /// A key image was present in the transaction multiple times.
+  #[error("transaction spent the same key image multiple times")]
+  DuplicateKeyImage,
+  /// A commitment (either an output's or a pseudo-out) wasn't a valid point.
+  #[error("invalid commitment")]
+  InvalidCommitment,
+  /// The amounts within the transaction didn't balance.
+  #[error("amounts didn't balance")]
+  Unbalanced,
+  /// The range proof(s) were invalid.
+  #[error("invalid range proof")]
+  InvalidRangeProof,
+  /// The ring signature for the specified input was invalid.
+  #[error("ring signature for input {0} was invalid")]
+  InvalidRingSignature(usize),
+  /// The MLSAG for the specified input was invalid.
+  ///
+  /// For `RctType::AggregateMlsagBorromean`, which has a single MLSAG, the index is always 0.
+  #[error("MLSAG for input {0} was invalid: {1}")]
+  InvalidMlsag(usize, MlsagError),
+  /// The CLSAG for the specified input was invalid.
+  #[error("CLSAG for input {0} was invalid: {1}")]
+  InvalidClsag(usize, ClsagError),
+}
+
+/// The key images and key offsets of a transaction's inputs, as needed to verify it.
+fn inputs(
+  tx: &Transaction<NotPruned>,
+) -> Result<Vec<(Option<u64>, usize, CompressedPoint)>, VerificationError> {
+  let inputs = &tx.prefix().inputs;
+  if inputs.is_empty() {
+    Err(VerificationError::NoInputs)?;
+  }
+
+  let mut res = Vec::with_capacity(inputs.len());
+  for (i, input) in inputs.iter().enumerate() {
+    let Input::ToKey { amount, key_offsets, key_image } = input else {
+      Err(VerificationError::MinerTransaction)?
+    };
+    // Ensure the key image is canonically encoded, non-identity, and torsion-free
+    if key_image.decompress().and_then(Point::key_image).is_none() {
+      Err(VerificationError::InvalidKeyImage(i))?;
+    }
+    res.push((*amount, key_offsets.len(), *key_image));
+  }
+
+  // Ensure no key image is spent twice within this transaction
+  let mut key_images = res.iter().map(|(_, _, key_image)| *key_image).collect::<Vec<_>>();
+  key_images.sort_unstable();
+  if key_images.windows(2).any(|pair| pair[0] == pair[1]) {
+    Err(VerificationError::DuplicateKeyImage)?;
+  }
+
+  Ok(res)
+}
+
+/// The pseudo-outs for these proofs, if they use pseudo-outs.
+fn pseudo_outs(proofs: &RctProofs) -> Option<&[CompressedPoint]> {
+  match &proofs.prunable {
+    RctPrunable::AggregateMlsagBorromean { .. } => None,
+    RctPrunable::MlsagBorromean { .. } => Some(&proofs.base.pseudo_outs),
+    RctPrunable::MlsagBulletproofs { pseudo_outs, .. } |
+    RctPrunable::MlsagBulletproofsCompactAmount { pseudo_outs, .. } |
+    RctPrunable::Clsag { pseudo_outs, .. } => Some(pseudo_outs),
+  }
+}
+
+/// Decompress a list of commitments.
+fn decompress_commitments(
+  commitments: &[CompressedPoint],
+) -> Result<Vec<EdwardsPoint>, VerificationError> {
+  commitments
+    .iter()
+    .map(|commitment| commitment.decompress().map(Point::into))
+    .collect::<Option<Vec<_>>>()
+    .ok_or(VerificationError::InvalidCommitment)
+}
+
+/// Check the proofs' lengths are consistent with the transaction's inputs and outputs.
+///
+/// `RctProofs::read` enforces this when deserializing, yet `RctProofs` may be constructed
+/// manually.
+fn check_proofs_shape(
+  proofs: &RctProofs,
+  inputs: usize,
+  outputs: usize,
+) -> Result<(), VerificationError> {
+  let base = &proofs.base;
+  if (base.commitments.len() != outputs) || (base.encrypted_amounts.len() != outputs) {
+    Err(VerificationError::MalformedProofs)?;
+  }
+  let consistent = match &proofs.prunable {
+    RctPrunable::AggregateMlsagBorromean { borromean, .. } => {
+      base.pseudo_outs.is_empty() && (borromean.len() == outputs)
+    }
+    RctPrunable::MlsagBorromean { borromean, mlsags } => {
+      (base.pseudo_outs.len() == inputs) && (borromean.len() == outputs) && (mlsags.len() == inputs)
+    }
+    RctPrunable::MlsagBulletproofs { mlsags, pseudo_outs, .. } |
+    RctPrunable::MlsagBulletproofsCompactAmount { mlsags, pseudo_outs, .. } => {
+      base.pseudo_outs.is_empty() && (mlsags.len() == inputs) && (pseudo_outs.len() == inputs)
+    }
+    RctPrunable::Clsag { clsags, pseudo_outs, .. } => {
+      base.pseudo_outs.is_empty() && (clsags.len() == inputs) && (pseudo_outs.len() == inputs)
+    }
+  };
+  if !consistent {
+    Err(VerificationError::MalformedProofs)?;
+  }
+  Ok(())
+}
+
+/// Verify the Borromean range proofs for each output.
+fn verify_borromean(
+  borromean: &[BorromeanRange],
+  commitments: &[CompressedPoint],
+) -> Result<(), VerificationError> {
+  for (range, commitment) in borromean.iter().zip(commitments) {
+    if !range.verify(commitment) {
+      Err(VerificationError::InvalidRangeProof)?;
+    }
+  }
+  Ok(())
+}
+
+/// The Bulletproof(+) within these proofs, if they use one.
+fn bulletproof(proofs: &RctProofs) -> Option<&Bulletproof> {
+  match &proofs.prunable {
+    RctPrunable::AggregateMlsagBorromean { .. } | RctPrunable::MlsagBorromean { .. } => None,
+    RctPrunable::MlsagBulletproofs { bulletproof, .. } |
+    RctPrunable::MlsagBulletproofsCompactAmount { bulletproof, .. } |
+    RctPrunable::Clsag { bulletproof, .. } => Some(bulletproof),
+  }
+}
+
+/// Verify the ring signatures (MLSAGs/CLSAGs) within the proofs.
+///
+/// This assumes `check_proofs_shape` has already been called.
+fn verify_rct_signatures(
+  proofs: &RctProofs,
+  signature_hash: &[u8; 32],
+  key_images: &[CompressedPoint],
+  rings: &[Vec<[CompressedPoint; 2]>],
+) -> Result<(), VerificationError> {
+  match &proofs.prunable {
+    RctPrunable::AggregateMlsagBorromean { mlsag, .. } => {
+      let mut matrix = AggregateRingMatrixBuilder::new(&proofs.base.commitments, proofs.base.fee)
+        .map_err(|e| VerificationError::InvalidMlsag(0, e))?;
+      for ring in rings {
+        matrix.push_ring(ring).map_err(|e| VerificationError::InvalidMlsag(0, e))?;
+      }
+      let matrix = matrix.build().map_err(|e| VerificationError::InvalidMlsag(0, e))?;
+      mlsag
+        .verify(signature_hash, &matrix, key_images)
+        .map_err(|e| VerificationError::InvalidMlsag(0, e))
+    }
+    RctPrunable::MlsagBorromean { mlsags, .. } |
+    RctPrunable::MlsagBulletproofs { mlsags, .. } |
+    RctPrunable::MlsagBulletproofsCompactAmount { mlsags, .. } => {
+      let pseudo_outs = pseudo_outs(proofs).expect("MLSAG proofs without pseudo-outs");
+      for (i, mlsag) in mlsags.iter().enumerate() {
+        let matrix = RingMatrix::individual(&rings[i], pseudo_outs[i])
+          .map_err(|e| VerificationError::InvalidMlsag(i, e))?;
+        mlsag
+          .verify(signature_hash, &matrix, &[key_images[i]])
+          .map_err(|e| VerificationError::InvalidMlsag(i, e))?;
+      }
+      Ok(())
+    }
+    RctPrunable::Clsag { clsags, pseudo_outs, .. } => {
+      for (i, clsag) in clsags.iter().enumerate() {
+        clsag
+          .verify(rings[i].clone(), &key_images[i], &pseudo_outs[i], signature_hash)
+          .map_err(|e| VerificationError::InvalidClsag(i, e))?;
+      }
+      Ok(())
+    }
+  }
+}
+
+/// The checks which may either be performed immediately or deferred to a batch.
+trait Checks {
+  /// Check the sum of the pseudo-outs equals the sum of the output commitments plus the fee.
+  fn balance(
+    &mut self,
+    pseudo_outs: Vec<EdwardsPoint>,
+    commitments: Vec<EdwardsPoint>,
+    fee: u64,
+  ) -> Result<(), VerificationError>;
+  /// Check a Bulletproof(+) for the specified commitments.
+  fn bulletproof(
+    &mut self,
+    bulletproof: &Bulletproof,
+    commitments: &[CompressedPoint],
+  ) -> Result<(), VerificationError>;
+}
+
+struct Immediate<'a, R: RngCore + CryptoRng>(&'a mut R);
+impl<R: RngCore + CryptoRng> Checks for Immediate<'_, R> {
+  fn balance(
+    &mut self,
+    pseudo_outs: Vec<EdwardsPoint>,
+    commitments: Vec<EdwardsPoint>,
+    fee: u64,
+  ) -> Result<(), VerificationError> {
+    let balance = pseudo_outs.iter().sum::<EdwardsPoint>() -
+      commitments.iter().sum::<EdwardsPoint>() -
+      Commitment::new(Scalar::ZERO, fee).commit().into();
+    if !balance.is_identity() {
+      Err(VerificationError::Unbalanced)?;
+    }
+    Ok(())
+  }
+
+  fn bulletproof(
+    &mut self,
+    bulletproof: &Bulletproof,
+    commitments: &[CompressedPoint],
+  ) -> Result<(), VerificationError> {
+    if !bulletproof.verify(self.0, commitments) {
+      Err(VerificationError::InvalidRangeProof)?;
+    }
+    Ok(())
+  }
+}
+
+struct Deferred<'a, R: RngCore + CryptoRng> {
+  rng: &'a mut R,
+  batch: &'a mut BatchVerifier,
+}
+impl<R: RngCore + CryptoRng> Checks for Deferred<'_, R> {
+  fn balance(
+    &mut self,
+    pseudo_outs: Vec<EdwardsPoint>,
+    commitments: Vec<EdwardsPoint>,
+    fee: u64,
+  ) -> Result<(), VerificationError> {
+    // Weight this equation by a random scalar so equations can't cancel each other out
+    let weight = Scalar::random(self.rng).into();
+    self.batch.balance_h_scalar -= weight * DScalar::from(fee);
+    self.batch.balance.extend(pseudo_outs.into_iter().map(|pseudo_out| (weight, pseudo_out)));
+    self.batch.balance.extend(commitments.into_iter().map(|commitment| (-weight, commitment)));
+    Ok(())
+  }
+
+  fn bulletproof(
+    &mut self,
+    bulletproof: &Bulletproof,
+    commitments: &[CompressedPoint],
+  ) -> Result<(), VerificationError> {
+    if !bulletproof.batch_verify(self.rng, &mut self.batch.bulletproofs, commitments) {
+      Err(VerificationError::InvalidRangeProof)?;
+    }
+    Ok(())
+  }
+}
+
+/// A transaction's ring signatures, to be verified after the rest of the transaction.
+struct RingSignatures<'a> {
+  tx: &'a Transaction<NotPruned>,
+  rings: &'a [Vec<[CompressedPoint; 2]>],
+  signature_hash: [u8; 32],
+  key_images: Vec<CompressedPoint>,
+}
+
+/// Verify everything within a transaction except its ring signatures, which are returned to be
+/// verified with `verify_ring_signatures`.
+fn verify_transaction<'a>(
+  tx: &'a Transaction<NotPruned>,
+  rings: &'a [Vec<[CompressedPoint; 2]>],
+  checks: &mut impl Checks,
+) -> Result<RingSignatures<'a>, VerificationError> {
+  let inputs = inputs(tx)?;
+  if rings.len() != inputs.len() {
+    Err(VerificationError::InvalidAmountOfRings)?;
+  }
+  for (i, ((_, ring_len, _), ring)) in inputs.iter().zip(rings).enumerate() {
+    if ring.len() != *ring_len {
+      Err(VerificationError::InvalidRingLength(i))?;
+    }
+  }
+  let key_images = inputs.iter().map(|(_, _, key_image)| *key_image).collect::<Vec<_>>();
+
+  let signature_hash =
+    tx.signature_hash().expect("non-miner transaction didn't have a signature hash");
+
+  match tx {
+    Transaction::V1 { prefix, signatures } => {
+      if signatures.len() != inputs.len() {
+        Err(VerificationError::MalformedProofs)?;
+      }
+
+      // Version 1 transactions have their amounts in the clear, with an implicit fee
+      let mut sum_inputs = 0u64;
+      for (amount, _, _) in &inputs {
+        sum_inputs = amount
+          .and_then(|amount| sum_inputs.checked_add(amount))
+          .ok_or(VerificationError::Unbalanced)?;
+      }
+      let mut sum_outputs = 0u64;
+      for output in &prefix.outputs {
+        sum_outputs = output
+          .amount
+          .and_then(|amount| sum_outputs.checked_add(amount))
+          .ok_or(VerificationError::Unbalanced)?;
+      }
+      if sum_inputs < sum_outputs {
+        Err(VerificationError::Unbalanced)?;
+      }
+    }
+    Transaction::V2 { prefix, proofs } => {
+      let Some(proofs) = proofs else { Err(VerificationError::MissingProofs)? };
+      check_proofs_shape(proofs, inputs.len(), prefix.outputs.len())?;
+
+      let commitments = decompress_commitments(&proofs.base.commitments)?;
+
+      // AggregateMlsagBorromean has its balance checked within its MLSAG
+      if let Some(pseudo_outs) = pseudo_outs(proofs) {
+        let pseudo_outs = decompress_commitments(pseudo_outs)?;
+        checks.balance(pseudo_outs, commitments, proofs.base.fee)?;
+      }
+
+      match proofs.rct_type() {
+        RctType::AggregateMlsagBorromean | RctType::MlsagBorromean => {
+          let (RctPrunable::AggregateMlsagBorromean { borromean, .. } |
+          RctPrunable::MlsagBorromean { borromean, .. }) = &proofs.prunable
+          else {
+            unreachable!("Borromean RctType without Borromean range proofs")
+          };
+          verify_borromean(borromean, &proofs.base.commitments)?;
+        }
+        RctType::MlsagBulletproofs |
+        RctType::MlsagBulletproofsCompactAmount |
+        RctType::ClsagBulletproof |
+        RctType::ClsagBulletproofPlus => {
+          let bulletproof = bulletproof(proofs).expect("Bulletproof RctType without a proof");
+          checks.bulletproof(bulletproof, &proofs.base.commitments)?;
+        }
+      }
+    }
+  }
+
+  Ok(RingSignatures { tx, rings, signature_hash, key_images })
+}
+
+/// Verify a transaction's ring signatures.
+fn verify_ring_signatures(ring_signatures: &RingSignatures<'_>) -> Result<(), VerificationError> {
+  let RingSignatures { tx, rings, signature_hash, key_images } = ring_signatures;
+  match tx {
+    Transaction::V1 { signatures, .. } => {
+      for (i, (signature, ring)) in signatures.iter().zip(rings.iter()).enumerate() {
+        let ring = ring.iter().map(|member| member[0]).collect::<Vec<_>>();
+        if !signature.verify(signature_hash, &ring, &key_images[i]) {
+          Err(VerificationError::InvalidRingSignature(i))?;
+        }
+      }
+      Ok(())
+    }
+    Transaction::V2 { proofs, .. } => verify_rct_signatures(
+      proofs.as_ref().expect("verified transaction was missing its proofs"),
+      signature_hash,
+      key_images,
+      rings,
+    ),
+  }
+}
+
+/// Verify the ring signatures of several transactions, returning the first invalid transaction.
+///
+/// The ring signatures are verified across as many threads as are available.
+#[cfg(feature = "std")]
+fn verify_all_ring_signatures(
+  ring_signatures: &[RingSignatures<'_>],
+) -> Result<(), (usize, VerificationError)> {
+  use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+  };
+
+  let threads =
+    thread::available_parallelism().map_or(1, NonZeroUsize::get).min(ring_signatures.len());
+  if threads <= 1 {
+    return verify_all_ring_signatures_sequentially(ring_signatures);
+  }
+
+  // Each thread takes the next transaction to verify until none remain
+  let next = AtomicUsize::new(0);
+  let invalid = thread::scope(|scope| {
+    let handles = (0 .. threads)
+      .map(|_| {
+        scope.spawn(|| {
+          let mut invalid = vec![];
+          loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(tx) = ring_signatures.get(index) else { break invalid };
+            if let Err(e) = verify_ring_signatures(tx) {
+              invalid.push((index, e));
+            }
+          }
+        })
+      })
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .flat_map(|handle| handle.join().expect("ring signature verification panicked"))
+      .collect::<Vec<_>>()
+  });
+  invalid.into_iter().min_by_key(|(index, _)| *index).map_or(Ok(()), Err)
+}
+
+#[cfg(not(feature = "std"))]
+fn verify_all_ring_signatures(
+  ring_signatures: &[RingSignatures<'_>],
+) -> Result<(), (usize, VerificationError)> {
+  verify_all_ring_signatures_sequentially(ring_signatures)
+}
+
+fn verify_all_ring_signatures_sequentially(
+  ring_signatures: &[RingSignatures<'_>],
+) -> Result<(), (usize, VerificationError)> {
+  for (index, tx) in ring_signatures.iter().enumerate() {
+    verify_ring_signatures(tx).map_err(|e| (index, e))?;
+  }
+  Ok(())
+}
+
+impl Transaction<NotPruned> {
+  /// Verify this transaction's signatures and proofs.
+  ///
+  /// `rings` is the resolved ring for each input, with each ring member represented as
+  /// `[output key, commitment]`. For outputs without a commitment (pre-RingCT outputs), the
+  /// commitment is expected to be the commitment to the output's amount with a mask of `1`, as
+  /// Monero defines. For version 1 transactions, the commitments are ignored.
+  ///
+  /// This checks the key images are valid and unique within this transaction, that the amounts
+  /// balance (including the fee), the range proofs, and the ring signatures against
+  /// [`Transaction::signature_hash`]. It does not check the key images haven't been spent on-chain,
+  /// the rings were correctly resolved, timelocks, or any rules dependent on the hard fork (such
+  /// as which `RctType`s are allowed, checked by [`crate::hardfork::HardFork::check_transaction`]).
+  ///
+  /// This function runs in variable time.
+  pub fn verify(
+    &self,
+    rng: &mut (impl RngCore + CryptoRng),
+    rings: &[Vec<[CompressedPoint; 2]>],
+  ) -> Result<(), VerificationError> {
+    verify_ring_signatures(&verify_transaction(self, rings, &mut Immediate(rng))?)
+  }
+}
+
+/// An error from verifying the transactions within a block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum BlockVerificationError {
+  /// The transactions provided didn't match the block's list of transactions.
+  #[error("transactions provided didn't match the block")]
+  TransactionsMismatch,
+  /// The amount of rings provided didn't match the amount of transactions.
+  #[error("amount of rings didn't match the amount of transactions")]
+  InvalidAmountOfRings,
+  /// The transaction at the specified index (within `Block::transactions`) was invalid.
+  #[error("transaction {index} was invalid: {error}")]
+  InvalidTransaction {
+    /// The index of the invalid transaction within `Block::transactions`.
+    index: usize,
+    /// The reason the transaction was invalid.
+    error: VerificationError,
+  },
+  /// The batch verification failed, yet no transaction was individually found to be invalid.
+  ///
+  /// This should only occur with negligible probability.
+  #[error("batch verification failed yet no invalid transaction was found")]
+  BatchFailed,
+}
+
+/// A batch verifier for transactions.
+///
+/// All Bulletproofs(+) are accumulated into a single batch verification and all balance equations
+/// (`sum(pseudo_outs) - sum(commitments) - fee H = 0`) are accumulated into a single randomly
+/// weighted multiscalar multiplication. The ring signatures (CLSAGs/MLSAGs) and Borromean range
+/// proofs are verified when queued, as their challenges are chained through hashes of the
+/// intermediate points and cannot be expressed as a multiscalar multiplication.
+///
+/// [`Block::verify_transactions`] additionally verifies the ring signatures of a block's
+/// transactions in parallel.
+#[derive(Default)]
+pub struct BatchVerifier {
+  bulletproofs: crate::ringct::bulletproofs::BatchVerifier,
+  balance: Vec<(DScalar, EdwardsPoint)>,
+  balance_h_scalar: DScalar,
+}
+
+impl BatchVerifier {
+  /// Create a new batch verifier.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Queue a transaction for verification.
+  ///
+  /// `rings` is as documented in [`Transaction::verify`]. An error is returned if any check which
+  /// isn't batched fails. If an error is returned, the batch verifier is left in an undefined
+  /// state.
+  ///
+  /// The batch verifier must have its verification function executed to actually verify this
+  /// transaction.
+  pub fn queue(
+    &mut self,
+    rng: &mut (impl RngCore + CryptoRng),
+    tx: &Transaction<NotPruned>,
+    rings: &[Vec<[CompressedPoint; 2]>],
+  ) -> Result<(), VerificationError> {
+    verify_ring_signatures(&verify_transaction(tx, rings, &mut Deferred { rng, batch: self })?)
+  }
+
+  /// Verify all of the transactions queued within this batch verifier.
+  ///
+  /// This uses variable-time multiscalar multiplications internally.
+  #[must_use]
+  pub fn verify(self) -> bool {
+    let mut balance = self.balance;
+    balance.push((self.balance_h_scalar, *H));
+    let (scalars, points): (Vec<_>, Vec<_>) = balance.into_iter().unzip();
+    EdwardsPoint::vartime_multiscalar_mul(scalars, points).is_identity() &&
+      self.bulletproofs.verify()
+  }
+}
+
+impl Block {
+  /// Verify the transactions within this block, excluding the miner transaction.
+  ///
+  /// `transactions` is expected to be the transactions of [`Block::transactions`], in order, and
+  /// `rings` the resolved rings for each transaction (as documented in [`Transaction::verify`]).
+  ///
+  /// The transactions' Bulletproofs(+) and balance equations are verified within a single
+  /// [`BatchVerifier`]. Their ring signatures (CLSAGs/MLSAGs) are not part of this batch, as each
+  /// challenge is the hash of the points the prior member's equation produces, and are instead
+  /// verified individually, in parallel when the `std` feature is enabled. If the batch fails,
+  /// each transaction is individually verified to identify the invalid transaction.
+  ///
+  /// This function runs in variable time.
+  pub fn verify_transactions(
+    &self,
+    rng: &mut (impl RngCore + CryptoRng),
+    transactions: &[Transaction<NotPruned>],
+    rings: &[Vec<Vec<[CompressedPoint; 2]>>],
+  ) -> Result<(), BlockVerificationError> {
+    if (transactions.len() != self.transactions.len()) ||
+      transactions.iter().zip(&self.transactions).any(|(tx, hash)| tx.hash() != *hash)
+    {
+      Err(BlockVerificationError::TransactionsMismatch)?;
+    }
+    if rings.len() != transactions.len() {
+      Err(BlockVerificationError::InvalidAmountOfRings)?;
+    }
+
+    let mut batch = BatchVerifier::new();
+    let mut ring_signatures = Vec::with_capacity(transactions.len());
+    for (index, (tx, rings)) in transactions.iter().zip(rings).enumerate() {
+      ring_signatures.push(
+        verify_transaction(tx, rings, &mut Deferred { rng, batch: &mut batch })
+          .map_err(|error| BlockVerificationError::InvalidTransaction { index, error })?,
+      );
+    }
+    verify_all_ring_signatures(&ring_signatures)
+      .map_err(|(index, error)| BlockVerificationError::InvalidTransaction { index, error })?;
+    if batch.verify() {
+      return Ok(());
+    }
+
+    // Find the offending transaction, whose ring signatures have already been verified
+    for (index, (tx, rings)) in transactions.iter().zip(rings).enumerate() {
+      verify_transaction(tx, rings, &mut Immediate(rng))
+        .map_err(|error| BlockVerificationError::InvalidTransaction { index, error })?;
+    }
+    Err(BlockVerificationError::BatchFailed)
+  }
+}
@@ -0,0 +1,109 @@
+// サブアドレスの一括導出・一括登録と、1 つずつの導出・登録との比較のベンチマーク。
+// `cargo bench -p monero-wallet --bench subaddresses` で実行します。
+use core::hint::black_box;
+use std::time::{Duration, Instant};
+
+use zeroize::Zeroizing;
+
+use rand_core::OsRng;
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+
+use monero_wallet::{
+  ed25519::{Scalar, Point},
+  address::{Network, SubaddressIndex},
+  ViewPair, Scanner,
+};
+
+const ACCOUNTS: u32 = 10;
+const ADDRESSES: u32 = 1_000;
+
+const WARMUP_RUNS: usize = 2;
+const RUNS: usize = 10;
+
+// Time `f`, with a fresh input from `setup` for each run, returning the median duration
+fn bench<S, T>(
+  name: &str,
+  baseline: Option<Duration>,
+  mut setup: impl FnMut() -> S,
+  mut f: impl FnMut(S) -> T,
+) -> Duration {
+  for _ in 0 .. WARMUP_RUNS {
+    black_box(f(setup()));
+  }
+
+  let mut durations = (0 .. RUNS)
+    .map(|_| {
+      let input = setup();
+      let start = Instant::now();
+      black_box(f(input));
+      start.elapsed()
+    })
+    .collect::<Vec<_>>();
+  durations.sort();
+  let median = durations[RUNS / 2];
+
+  print!("{name}: {median:?} (min {:?}, max {:?})", durations[0], durations[RUNS - 1]);
+  match baseline {
+    Some(baseline) => {
+      println!(", {:.2}x faster", baseline.as_secs_f64() / median.as_secs_f64())
+    }
+    None => println!(),
+  }
+  median
+}
+
+fn main() {
+  let spend = Point::from(Scalar::random(&mut OsRng).into() * ED25519_BASEPOINT_POINT);
+  let pair = ViewPair::new(spend, Zeroizing::new(Scalar::random(&mut OsRng))).unwrap();
+  let subaddresses = (0 .. ACCOUNTS)
+    .flat_map(|account| {
+      (0 .. ADDRESSES).filter_map(move |address| SubaddressIndex::new(account, address))
+    })
+    .collect::<Vec<_>>();
+  println!("{} subaddresses, median of {RUNS} runs", subaddresses.len());
+
+  println!();
+  let baseline = bench(
+    "ViewPair::subaddress",
+    None,
+    || (),
+    |()| {
+      subaddresses
+        .iter()
+        .map(|subaddress| pair.subaddress(Network::Mainnet, *subaddress))
+        .collect::<Vec<_>>()
+    },
+  );
+  bench(
+    "ViewPair::subaddresses",
+    Some(baseline),
+    || (),
+    |()| pair.subaddresses(Network::Mainnet, &subaddresses),
+  );
+
+  println!();
+  let scanner = || Scanner::new(pair.clone());
+  // Registration alone, for reference, as `register_subaddresses` also derives the addresses
+  bench("Scanner::register_subaddress", None, scanner, |mut scanner| {
+    for subaddress in &subaddresses {
+      scanner.register_subaddress(*subaddress);
+    }
+    scanner
+  });
+  let baseline =
+    bench("Scanner::register_subaddress and ViewPair::subaddress", None, scanner, |mut scanner| {
+      let addresses = subaddresses
+        .iter()
+        .map(|subaddress| {
+          scanner.register_subaddress(*subaddress);
+          pair.subaddress(Network::Mainnet, *subaddress)
+        })
+        .collect::<Vec<_>>();
+      (scanner, addresses)
+    });
+  bench("Scanner::register_subaddresses", Some(baseline), scanner, |mut scanner| {
+    let addresses = scanner.register_subaddresses(Network::Mainnet, &subaddresses);
+    (scanner, addresses)
+  });
+}
@@ -0,0 +1,250 @@
+// 補助プルーフ・オブ・ワーク（マージマイニング）の証明を生成・検証するモジュールです。
+// 補助チェーンのブロックハッシュはマージマイニング木の所定のスロットに置かれ、その木のルートが
+// Monero ブロックのマイナートランザクションの `extra`（マージマイニングタグ）に含まれます。
+use std_shims::{
+  vec,
+  vec::Vec,
+  io::{self, Read, Write},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+  io::*,
+  block::{BlockHeader, Block},
+  transaction::Transaction,
+  merkle::{merkle_root, merkle_branch, merkle_root_from_branch},
+  difficulty::check_hash,
+  extra::{ExtraField, Extra},
+};
+
+/// The maximum amount of auxiliary chains a merge-mining tree may commit to.
+pub const MAX_AUX_CHAINS: u32 = 256;
+// The domain separator used when selecting a chain's slot
+const HASH_KEY_MM_SLOT: u8 = b'm';
+// The maximum length of a Merkle branch we'll read
+const MAX_BRANCH_LENGTH: usize = 64;
+
+/// An error when verifying an auxiliary proof of work.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum AuxPowError {
+  /// The miner transaction's extra didn't have a merge-mining tag.
+  #[error("miner transaction didn't have a merge-mining tag")]
+  MissingMergeMiningTag,
+  /// The merge-mining tag's depth didn't encode a valid amount of auxiliary chains.
+  #[error("merge-mining tag had an invalid depth")]
+  InvalidDepth,
+  /// The miner transaction's Merkle branch was invalid.
+  #[error("miner transaction's Merkle branch was invalid")]
+  InvalidMinerTransactionBranch,
+  /// The auxiliary block wasn't within the merge-mining tree at its chain's slot.
+  #[error("auxiliary block wasn't committed to at its chain's slot")]
+  InvalidAuxBranch,
+  /// The parent block's proof of work didn't meet the difficulty.
+  #[error("parent block's proof of work didn't meet the difficulty")]
+  InsufficientWork,
+}
+
+/// Encode the amount of auxiliary chains and the nonce into the depth of a merge-mining tag.
+///
+/// Returns `None` if the amount of auxiliary chains is zero or exceeds [`MAX_AUX_CHAINS`].
+pub fn encode_depth(aux_chains: u32, nonce: u32) -> Option<u64> {
+  if (aux_chains == 0) || (aux_chains > MAX_AUX_CHAINS) {
+    None?;
+  }
+  // The amount of bits needed to represent `aux_chains - 1`, with a minimum of 1
+  let bits = (u32::BITS - (aux_chains - 1).leading_zeros()).max(1);
+  Some(u64::from(bits - 1) | (u64::from(aux_chains - 1) << 3) | (u64::from(nonce) << (3 + bits)))
+}
+
+/// Decode the amount of auxiliary chains and the nonce from the depth of a merge-mining tag.
+///
+/// Returns `None` if the depth is invalid.
+pub fn decode_depth(depth: u64) -> Option<(u32, u32)> {
+  let bits = 1 + (depth & 7);
+  let aux_chains = 1 + ((depth >> 3) & ((1 << bits) - 1));
+  let nonce = u32::try_from(depth >> (3 + bits)).ok()?;
+  let aux_chains = u32::try_from(aux_chains).ok().filter(|chains| *chains <= MAX_AUX_CHAINS)?;
+  Some((aux_chains, nonce))
+}
+
+/// The slot within the merge-mining tree for the auxiliary chain with the specified ID.
+pub fn aux_slot(chain_id: &[u8; 32], nonce: u32, aux_chains: u32) -> u32 {
+  if aux_chains == 0 {
+    return 0;
+  }
+  let mut hasher = Sha256::new();
+  hasher.update(chain_id);
+  hasher.update(nonce.to_le_bytes());
+  hasher.update([HASH_KEY_MM_SLOT]);
+  let hash = hasher.finalize();
+  u32::from_le_bytes(hash[.. 4].try_into().unwrap()) % aux_chains
+}
+
+/// A merge-mining tree, committing to the blocks of several auxiliary chains.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MergeMiningTree {
+  nonce: u32,
+  chain_ids: Vec<[u8; 32]>,
+  leaves: Vec<[u8; 32]>,
+}
+
+impl MergeMiningTree {
+  /// Create a merge-mining tree for the specified auxiliary chains' IDs and block hashes.
+  ///
+  /// This searches for the first nonce, up to `max_nonce`, which places every chain in a distinct
+  /// slot. Returns `None` if no such nonce was found, if no chains were specified, if too many
+  /// chains were specified, or if a chain was specified multiple times.
+  pub fn new(aux_blocks: &[([u8; 32], [u8; 32])], max_nonce: u32) -> Option<Self> {
+    let aux_chains = u32::try_from(aux_blocks.len())
+      .ok()
+      .filter(|chains| (*chains != 0) && (*chains <= MAX_AUX_CHAINS))?;
+
+    for nonce in 0 ..= max_nonce {
+      let mut chain_ids = vec![[0; 32]; aux_blocks.len()];
+      let mut leaves = vec![None; aux_blocks.len()];
+      for (chain_id, block_hash) in aux_blocks {
+        let slot = usize::try_from(aux_slot(chain_id, nonce, aux_chains)).unwrap();
+        if leaves[slot].is_some() {
+          break;
+        }
+        chain_ids[slot] = *chain_id;
+        leaves[slot] = Some(*block_hash);
+      }
+      if let Some(leaves) = leaves.into_iter().collect::<Option<Vec<_>>>() {
+        return Some(Self { nonce, chain_ids, leaves });
+      }
+    }
+    None
+  }
+
+  /// The depth to include within the merge-mining tag.
+  pub fn depth(&self) -> u64 {
+    encode_depth(u32::try_from(self.leaves.len()).unwrap(), self.nonce)
+      .expect("merge-mining tree had an invalid amount of chains")
+  }
+
+  /// The Merkle root to include within the merge-mining tag.
+  pub fn root(&self) -> [u8; 32] {
+    merkle_root(self.leaves.clone()).expect("merge-mining tree had no leaves")
+  }
+
+  /// The Merkle branch for the auxiliary chain with the specified ID.
+  pub fn branch(&self, chain_id: &[u8; 32]) -> Option<Vec<[u8; 32]>> {
+    let slot = self.chain_ids.iter().position(|id| id == chain_id)?;
+    merkle_branch(&self.leaves, slot)
+  }
+}
+
+/// A proof an auxiliary chain's block was merge-mined by a Monero block.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AuxPow {
+  /// The header of the parent Monero block.
+  pub header: BlockHeader,
+  /// The miner transaction of the parent Monero block.
+  pub miner_transaction: Transaction,
+  /// The amount of transactions within the parent Monero block, including the miner transaction.
+  pub transaction_count: usize,
+  /// The Merkle branch for the miner transaction within the parent Monero block.
+  pub miner_transaction_branch: Vec<[u8; 32]>,
+  /// The Merkle branch for the auxiliary block within the merge-mining tree.
+  pub aux_branch: Vec<[u8; 32]>,
+}
+
+impl AuxPow {
+  /// Create a proof from the parent Monero block and the auxiliary block's Merkle branch within
+  /// the merge-mining tree.
+  pub fn new(block: &Block, aux_branch: Vec<[u8; 32]>) -> AuxPow {
+    let mut transactions = Vec::with_capacity(1 + block.transactions.len());
+    transactions.push(block.miner_transaction().hash());
+    transactions.extend(&block.transactions);
+    AuxPow {
+      header: block.header.clone(),
+      miner_transaction: block.miner_transaction().clone(),
+      transaction_count: transactions.len(),
+      miner_transaction_branch: merkle_branch(&transactions, 0)
+        .expect("couldn't get the branch for a present leaf"),
+      aux_branch,
+    }
+  }
+
+  /// The blob hashed for the parent Monero block's proof of work.
+  ///
+  /// Returns `None` if the miner transaction's Merkle branch is invalid.
+  pub fn hashing_blob(&self) -> Option<Vec<u8>> {
+    let root = merkle_root_from_branch(
+      self.miner_transaction.hash(),
+      0,
+      self.transaction_count,
+      &self.miner_transaction_branch,
+    )?;
+    let mut blob = self.header.serialize();
+    blob.extend(root);
+    VarInt::write(&self.transaction_count, &mut blob)
+      .expect("write failed but <Vec as io::Write> doesn't fail");
+    Some(blob)
+  }
+
+  /// Verify this proof for the specified auxiliary chain's block.
+  ///
+  /// `pow_hash` calculates the proof-of-work hash of the parent block's hashing blob, as
+  /// appropriate to its hard fork, which must meet the specified difficulty.
+  pub fn verify(
+    &self,
+    chain_id: &[u8; 32],
+    aux_block_hash: [u8; 32],
+    difficulty: u128,
+    pow_hash: impl FnOnce(&[u8]) -> [u8; 32],
+  ) -> Result<(), AuxPowError> {
+    let extra = Extra::read(&mut self.miner_transaction.prefix().extra.as_slice())
+      .map_err(|_| AuxPowError::MissingMergeMiningTag)?;
+    let (depth, root) = extra
+      .0
+      .iter()
+      .find_map(|field| match field {
+        ExtraField::MergeMining(depth, root) => Some((*depth, *root)),
+        _ => None,
+      })
+      .ok_or(AuxPowError::MissingMergeMiningTag)?;
+
+    let (aux_chains, nonce) = decode_depth(depth).ok_or(AuxPowError::InvalidDepth)?;
+    let slot = usize::try_from(aux_slot(chain_id, nonce, aux_chains)).unwrap();
+    let aux_chains = usize::try_from(aux_chains).unwrap();
+    if merkle_root_from_branch(aux_block_hash, slot, aux_chains, &self.aux_branch) != Some(root) {
+      Err(AuxPowError::InvalidAuxBranch)?;
+    }
+
+    let hashing_blob = self.hashing_blob().ok_or(AuxPowError::InvalidMinerTransactionBranch)?;
+    if !check_hash(&pow_hash(&hashing_blob), difficulty) {
+      Err(AuxPowError::InsufficientWork)?;
+    }
+    Ok(())
+  }
+
+  /// Write the AuxPow.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    self.header.write(w)?;
+    self.miner_transaction.write(w)?;
+    VarInt::write(&self.transaction_count, w)?;
+    write_vec(|hash, w| w.write_all(hash), &self.miner_transaction_branch, w)?;
+    write_vec(|hash, w| w.write_all(hash), &self.aux_branch, w)
+  }
+
+  /// Serialize the AuxPow to a `Vec<u8>`.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut serialized = vec![];
+    self.write(&mut serialized).expect("write failed but <Vec as io::Write> doesn't fail");
+    serialized
+  }
+
+  /// Read an AuxPow.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<AuxPow> {
+    Ok(AuxPow {
+      header: BlockHeader::read(r)?,
+      miner_transaction: Transaction::read(r)?,
+      transaction_count: VarInt::read(r)?,
+      miner_transaction_branch: read_vec(read_bytes, Some(MAX_BRANCH_LENGTH), r)?,
+      aux_branch: read_vec(read_bytes, Some(MAX_BRANCH_LENGTH), r)?,
+    })
+  }
+}
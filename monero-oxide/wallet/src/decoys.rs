@@ -1,4 +1,3 @@
-
 // このモジュールは、送金で使用するデコイ（リングメンバー）を RPC 経由で取得・選択するロジックを実装します。
 // Monero のプライバシー特性を保つため、出力のランダム選択（ガンマ分布に基づく）や
 // ブロック分布の扱い、デコイの検証（アンロック状態やトーションの除去等）を行います。
@@ -45,30 +44,53 @@ async fn select_n(
     ))?;
   }
 
-  // Get the distribution
-  let distribution = rpc.get_output_distribution(.. height).await?;
-  if distribution.len() < DEFAULT_LOCK_WINDOW {
-    Err(RpcError::InternalError("not enough blocks to select decoys".to_string()))?;
-  }
-  let highest_output_exclusive_bound = distribution[distribution.len() - DEFAULT_LOCK_WINDOW];
-  // This assumes that each miner TX had one output (as sane) and checks we have sufficient
-  // outputs even when excluding them (due to their own timelock requirements)
-  // Considering this a temporal error for very new chains, it's sufficiently sane to have
-  if highest_output_exclusive_bound.saturating_sub(
-    u64::try_from(COINBASE_LOCK_WINDOW).expect("coinbase lock window exceeds 2^{64}"),
-  ) < u64::from(ring_len)
-  {
-    Err(RpcError::InternalError("not enough decoy candidates".to_string()))?;
-  }
+  // Pre-RingCT outputs have their decoys selected from the pool of outputs with the same amount
+  let pre_ringct_amount = output_being_spent.data.pre_ringct_amount();
+  let amount = pre_ringct_amount.unwrap_or(0);
+
+  // Restricted RPCs only serve the output distribution for RingCT outputs. For pre-RingCT pools,
+  // we instead use the amount of unlocked outputs within the pool, as Monero does
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/wallet/wallet2.cpp
+  let (distribution, highest_output_exclusive_bound, per_second) =
+    if let Some(amount) = pre_ringct_amount {
+      let histogram = rpc.get_output_histogram(&[amount]).await?;
+      let entry = histogram.first().ok_or_else(|| {
+        RpcError::InvalidNode("requested an output histogram and got none".to_string())
+      })?;
+      // Pre-RingCT pools are no longer being added to, so we don't have to consider miner outputs
+      if entry.unlocked_instances < u64::from(ring_len) {
+        Err(RpcError::InternalError("not enough decoy candidates".to_string()))?;
+      }
+      (Vec::new(), entry.unlocked_instances, 0.0)
+    } else {
+      // Get the distribution
+      let distribution = rpc.get_output_distribution_for_amount(0, .. height).await?;
+      if distribution.len() < DEFAULT_LOCK_WINDOW {
+        Err(RpcError::InternalError("not enough blocks to select decoys".to_string()))?;
+      }
+      let highest_output_exclusive_bound = distribution[distribution.len() - DEFAULT_LOCK_WINDOW];
+      // This assumes that each miner TX had one output (as sane) and checks we have sufficient
+      // outputs even when excluding them (due to their own timelock requirements)
+      // Considering this a temporal error for very new chains, it's sufficiently sane to have
+      if highest_output_exclusive_bound.saturating_sub(
+        u64::try_from(COINBASE_LOCK_WINDOW).expect("coinbase lock window exceeds 2^{64}"),
+      ) < u64::from(ring_len)
+      {
+        Err(RpcError::InternalError("not enough decoy candidates".to_string()))?;
+      }
 
-  // Determine the outputs per second
-  #[allow(clippy::cast_precision_loss)]
-  let per_second = {
-    let blocks = distribution.len().min(BLOCKS_PER_YEAR);
-    let initial = distribution[distribution.len().saturating_sub(blocks + 1)];
-    let outputs = distribution[distribution.len() - 1].saturating_sub(initial);
-    (outputs as f64) / ((blocks * BLOCK_TIME) as f64)
-  };
+      // Determine the outputs per second
+      #[allow(clippy::cast_precision_loss)]
+      let per_second = {
+        let blocks = distribution.len().min(BLOCKS_PER_YEAR);
+        let initial = distribution[distribution.len().saturating_sub(blocks + 1)];
+        let outputs = distribution[distribution.len() - 1].saturating_sub(initial);
+        (outputs as f64) / ((blocks * BLOCK_TIME) as f64)
+      };
+
+      (distribution, highest_output_exclusive_bound, per_second)
+    };
 
   let output_being_spent_index = output_being_spent.relative_id.index_on_blockchain;
 
@@ -110,41 +132,61 @@ async fn select_n(
     let remaining = decoy_count - res.len();
     let mut candidates = Vec::with_capacity(remaining);
     while candidates.len() != remaining {
-      // Use a gamma distribution, as Monero does
-      // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c45
-      //   /src/wallet/wallet2.cpp#L142-L143
-      let mut age = Gamma::<f64>::new(19.28, 1.0 / 1.61)
-        .expect("constant Gamma distribution could no longer be created")
-        .sample(rng)
-        .exp();
-      #[allow(clippy::cast_precision_loss)]
-      if age > TIP_APPLICATION {
-        age -= TIP_APPLICATION;
-      } else {
+      let candidate = if pre_ringct_amount.is_some() {
+        // Selecting by age is meaningless for pre-RingCT pools, as they haven't been added to since
+        // RingCT was introduced. We instead use a triangular distribution over the pool, favoring
+        // more recent outputs, as Monero does for pre-RingCT outputs
         // f64 does not have try_from available, which is why these are written with `as`
-        age = (rng.next_u64() %
-          (RECENT_WINDOW * u64::try_from(BLOCK_TIME).expect("BLOCK_TIME exceeded u64::MAX")))
-          as f64;
-      }
+        #[allow(
+          clippy::cast_precision_loss,
+          clippy::cast_sign_loss,
+          clippy::cast_possible_truncation
+        )]
+        let o = {
+          let frac = (((rng.next_u64() >> 11) as f64) / ((1u64 << 53) as f64)).sqrt();
+          (frac * (highest_output_exclusive_bound as f64)) as u64
+        };
+        Some(o.min(highest_output_exclusive_bound - 1))
+      } else {
+        // Use a gamma distribution, as Monero does
+        // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c45
+        //   /src/wallet/wallet2.cpp#L142-L143
+        let mut age = Gamma::<f64>::new(19.28, 1.0 / 1.61)
+          .expect("constant Gamma distribution could no longer be created")
+          .sample(rng)
+          .exp();
+        #[allow(clippy::cast_precision_loss)]
+        if age > TIP_APPLICATION {
+          age -= TIP_APPLICATION;
+        } else {
+          // f64 does not have try_from available, which is why these are written with `as`
+          age = (rng.next_u64() %
+            (RECENT_WINDOW * u64::try_from(BLOCK_TIME).expect("BLOCK_TIME exceeded u64::MAX")))
+            as f64;
+        }
 
-      #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-      let o = (age * per_second) as u64;
-      if o < highest_output_exclusive_bound {
-        // Find which block this points to
-        let i = distribution.partition_point(|s| *s < (highest_output_exclusive_bound - 1 - o));
-        let prev = i.saturating_sub(1);
-        let n = distribution[i].checked_sub(distribution[prev]).ok_or_else(|| {
-          RpcError::InternalError("RPC returned non-monotonic distribution".to_string())
-        })?;
-        if n != 0 {
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let o = (age * per_second) as u64;
+        if o < highest_output_exclusive_bound {
+          // Find which block this points to
+          let i = distribution.partition_point(|s| *s < (highest_output_exclusive_bound - 1 - o));
+          let prev = i.saturating_sub(1);
+          let n = distribution[i].checked_sub(distribution[prev]).ok_or_else(|| {
+            RpcError::InternalError("RPC returned non-monotonic distribution".to_string())
+          })?;
           // Select an output from within this block
-          let o = distribution[prev] + (rng.next_u64() % n);
-          if !do_not_select.contains(&o) {
-            candidates.push(o);
-            // This output will either be used or is unusable
-            // In either case, we should not try it again
-            do_not_select.insert(o);
-          }
+          (n != 0).then(|| distribution[prev] + (rng.next_u64() % n))
+        } else {
+          None
+        }
+      };
+
+      if let Some(o) = candidate {
+        if !do_not_select.contains(&o) {
+          candidates.push(o);
+          // This output will either be used or is unusable
+          // In either case, we should not try it again
+          do_not_select.insert(o);
         }
       }
     }
@@ -168,7 +210,7 @@ async fn select_n(
     };
 
     for (i, output) in rpc
-      .get_unlocked_outputs(&candidates, height, fingerprintable_deterministic)
+      .get_unlocked_outputs_for_amount(amount, &candidates, height, fingerprintable_deterministic)
       .await?
       .iter_mut()
       .enumerate()
@@ -340,6 +382,14 @@ impl OutputWithDecoys {
     &self.decoys
   }
 
+  /// If this is a pre-RingCT output.
+  ///
+  /// Such outputs are spent with their amount specified within the input, and a ring of outputs
+  /// from the pool of outputs with the same amount.
+  pub fn pre_ringct(&self) -> bool {
+    self.output.pre_ringct
+  }
+
   /// Write the OutputWithDecoys.
   ///
   /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
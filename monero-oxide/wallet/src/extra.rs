@@ -15,7 +15,7 @@ use monero_oxide::{
 };
 
 pub(crate) const MAX_TX_EXTRA_PADDING_COUNT: usize = 255;
-const MAX_TX_EXTRA_NONCE_SIZE: usize = 255;
+pub(crate) const MAX_TX_EXTRA_NONCE_SIZE: usize = 255;
 
 const PAYMENT_ID_MARKER: u8 = 0;
 const ENCRYPTED_PAYMENT_ID_MARKER: u8 = 1;
@@ -9,6 +9,7 @@
 // - 取引スキャン/出力表現 (`scan`, `output`)
 // - デコイ選択ロジック (`decoys`)
 // - 送金用の高レベル API (`send`)
+// - マイナートランザクション・ブロックテンプレートの構築 (`miner`)
 
 use core::ops::Deref;
 use std_shims::vec::Vec;
@@ -28,6 +29,9 @@ pub use monero_address as address;
 mod view_pair;
 pub use view_pair::{ViewPairError, ViewPair, GuaranteedViewPair};
 
+mod wallet_keys;
+pub use wallet_keys::WalletKeys;
+
 /// Structures and functionality for working with transactions' extra fields.
 pub mod extra;
 pub(crate) use extra::{PaymentId, Extra};
@@ -41,9 +45,21 @@ pub use scan::{Timelocked, ScanError, Scanner, GuaranteedScanner};
 mod decoys;
 pub use decoys::OutputWithDecoys;
 
+mod output_indexer;
+pub use output_indexer::{OutputIndexerError, OutputIndexer};
+
 /// Structs and functionality for sending transactions.
 pub mod send;
 
+/// Structs and functionality for building miner transactions and block templates.
+pub mod miner;
+
+/// Structs and functionality for creating and verifying auxiliary proofs of work (merge-mining).
+pub mod aux_pow;
+
+/// Mnemonic seeds, encoding a wallet's keys as words.
+pub mod seed;
+
 #[cfg(test)]
 mod tests;
 
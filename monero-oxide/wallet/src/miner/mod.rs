@@ -0,0 +1,204 @@
+// マイナートランザクション（コインベース）を構築するモジュールです。
+// ブロック報酬を 1 つ以上のアドレスへ分配し、ハードフォークに応じてビュータグを付与します。
+// `extra` にはトランザクション公開鍵と、任意で nonce・マージマイニングタグを含めます。
+use core::ops::Deref;
+use std_shims::{vec, vec::Vec};
+
+use zeroize::Zeroizing;
+
+use rand_core::{RngCore, CryptoRng};
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::*,
+  transaction::{Input, Output, Timelock, TransactionPrefix, Transaction},
+  address::MoneroAddress,
+  extra::{MAX_TX_EXTRA_NONCE_SIZE, ExtraField, Extra},
+  SharedKeyDerivations, COINBASE_LOCK_WINDOW,
+};
+
+mod template;
+pub use template::{
+  COINBASE_BLOB_RESERVED_SIZE, BlockTemplateError, BlockTemplateBuilder, BlockTemplate,
+};
+
+/// The first hard fork whose miner transactions are version 2 transactions.
+pub const MINER_TRANSACTION_V2_HARDFORK_VERSION: u8 = 4;
+/// The first hard fork whose outputs have view tags.
+pub const VIEW_TAGS_HARDFORK_VERSION: u8 = 15;
+
+/// An error while building a miner transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum MinerTransactionError {
+  /// The hard fork doesn't use version 2 miner transactions.
+  #[error("hard fork {0} doesn't use version 2 miner transactions")]
+  UnsupportedHardFork(u8),
+  /// No payouts were specified.
+  #[error("no payouts were specified")]
+  NoPayouts,
+  /// The shares of the payouts summed to zero (or exceeded `u64::MAX`).
+  #[error("the shares of the payouts were invalid")]
+  InvalidShares,
+  /// A payout was to a subaddress, which miner transactions don't support.
+  #[error("miner transactions can't pay to subaddresses")]
+  SubaddressPayout,
+  /// The nonce exceeded the maximum size of a nonce.
+  #[error("nonce was too large ({0} bytes)")]
+  NonceTooLarge(usize),
+}
+
+/// A builder for the miner transaction of a block.
+///
+/// The reward is split between the payouts proportionally to their shares, with any remainder
+/// from the division paid to the first payout. Payment IDs within integrated addresses are
+/// ignored.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MinerTransactionBuilder {
+  number: usize,
+  hardfork_version: u8,
+  reward: u64,
+  payouts: Vec<(MoneroAddress, u64)>,
+  nonce: Option<Vec<u8>>,
+  merge_mining: Option<(u64, [u8; 32])>,
+  padding: usize,
+}
+
+impl MinerTransactionBuilder {
+  /// Create a new builder for the miner transaction of the block with the specified number.
+  ///
+  /// The reward is the amount paid out by the miner transaction, including any fees.
+  pub fn new(number: usize, hardfork_version: u8, reward: u64) -> Self {
+    Self {
+      number,
+      hardfork_version,
+      reward,
+      payouts: vec![],
+      nonce: None,
+      merge_mining: None,
+      padding: 0,
+    }
+  }
+
+  /// Pay the specified share of the reward to an address.
+  pub fn payout(mut self, address: MoneroAddress, share: u64) -> Self {
+    self.payouts.push((address, share));
+    self
+  }
+
+  /// Include a nonce within the extra field.
+  ///
+  /// This is commonly used to reserve space for miners to vary.
+  pub fn nonce(mut self, nonce: Vec<u8>) -> Self {
+    self.nonce = Some(nonce);
+    self
+  }
+
+  /// Include a merge-mining tag within the extra field.
+  ///
+  /// This commits to the Merkle root of the merge-mined chains' blocks, with the specified depth of
+  /// the Merkle tree.
+  pub fn merge_mining(mut self, depth: u64, merkle_root: [u8; 32]) -> Self {
+    self.merge_mining = Some((depth, merkle_root));
+    self
+  }
+
+  // Pad the end of the extra field with the specified amount of zero bytes
+  pub(crate) fn padding(mut self, padding: usize) -> Self {
+    self.padding = padding;
+    self
+  }
+
+  // The amount paid to each payout
+  fn amounts(&self) -> Result<Vec<u64>, MinerTransactionError> {
+    let shares = self
+      .payouts
+      .iter()
+      .try_fold(0u64, |shares, (_, share)| shares.checked_add(*share))
+      .filter(|shares| *shares != 0)
+      .ok_or(MinerTransactionError::InvalidShares)?;
+
+    let mut amounts = self
+      .payouts
+      .iter()
+      .map(|(_, share)| {
+        let amount = (u128::from(self.reward) * u128::from(*share)) / u128::from(shares);
+        u64::try_from(amount).expect("share of the reward exceeded the reward")
+      })
+      .collect::<Vec<_>>();
+    amounts[0] += self.reward - amounts.iter().sum::<u64>();
+    Ok(amounts)
+  }
+
+  /// Build the miner transaction.
+  ///
+  /// The RNG is used to generate the transaction key.
+  pub fn build(
+    self,
+    rng: &mut (impl RngCore + CryptoRng),
+  ) -> Result<Transaction, MinerTransactionError> {
+    if self.hardfork_version < MINER_TRANSACTION_V2_HARDFORK_VERSION {
+      Err(MinerTransactionError::UnsupportedHardFork(self.hardfork_version))?;
+    }
+    if self.payouts.is_empty() {
+      Err(MinerTransactionError::NoPayouts)?;
+    }
+    if self.payouts.iter().any(|(address, _)| address.is_subaddress()) {
+      Err(MinerTransactionError::SubaddressPayout)?;
+    }
+    if let Some(nonce) = &self.nonce {
+      if nonce.len() > MAX_TX_EXTRA_NONCE_SIZE {
+        Err(MinerTransactionError::NonceTooLarge(nonce.len()))?;
+      }
+    }
+    let amounts = self.amounts()?;
+
+    let inputs = vec![Input::Gen(self.number)];
+    let uniqueness = SharedKeyDerivations::uniqueness(&inputs);
+
+    let tx_key = Zeroizing::new(Scalar::random(rng));
+    let tx_key = Zeroizing::new((*tx_key).into());
+    let tx_key_pub = Point::from(tx_key.deref() * ED25519_BASEPOINT_TABLE);
+
+    let mut outputs = Vec::with_capacity(self.payouts.len());
+    for (o, ((address, _), amount)) in self.payouts.iter().zip(amounts).enumerate() {
+      let ecdh = Zeroizing::new(Point::from(tx_key.deref() * address.view().into()));
+      let derivations = SharedKeyDerivations::output_derivations(
+        address.is_guaranteed().then_some(uniqueness),
+        ecdh,
+        o,
+      );
+      let key = (&derivations.shared_key.into() * ED25519_BASEPOINT_TABLE) + address.spend().into();
+      outputs.push(Output {
+        key: Point::from(key).compress(),
+        amount: Some(amount),
+        view_tag: (self.hardfork_version >= VIEW_TAGS_HARDFORK_VERSION)
+          .then_some(derivations.view_tag),
+      });
+    }
+
+    let mut extra = Extra::new(tx_key_pub.compress(), vec![]);
+    if let Some(nonce) = self.nonce {
+      extra.push_nonce(nonce);
+    }
+    if let Some((depth, merkle_root)) = self.merge_mining {
+      extra.0.push(ExtraField::MergeMining(depth, merkle_root));
+    }
+    if self.padding != 0 {
+      extra.0.push(ExtraField::Padding(self.padding));
+    }
+
+    Ok(Transaction::V2 {
+      prefix: TransactionPrefix {
+        additional_timelock: Timelock::Block(self.number + COINBASE_LOCK_WINDOW),
+        inputs,
+        outputs,
+        extra: extra.serialize(),
+      },
+      proofs: None,
+    })
+  }
+}
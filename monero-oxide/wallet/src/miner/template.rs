@@ -0,0 +1,350 @@
+// ブロックテンプレートを組み立てるモジュールです。
+// 候補トランザクションを重量あたりの手数料順に、中央値によるペナルティを考慮しつつ選択し、
+// 報酬を計算してマイナートランザクションを構築します。`get_block_template` 相当の結果を返します。
+use std_shims::{vec, vec::Vec, collections::HashSet};
+
+use rand_core::{RngCore, CryptoRng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{
+  transaction::{Input, Transaction},
+  block::{BlockHeader, Block},
+  consensus::{ConsensusError, full_reward_zone, block_reward},
+  address::MoneroAddress,
+  miner::{MinerTransactionError, MinerTransactionBuilder},
+};
+
+/// The weight reserved for the miner transaction when selecting transactions.
+pub const COINBASE_BLOB_RESERVED_SIZE: usize = 600;
+
+/// An error while building a block template.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum BlockTemplateError {
+  /// The miner transaction couldn't be built.
+  #[error("couldn't build the miner transaction: {0}")]
+  MinerTransaction(MinerTransactionError),
+  /// The block would violate the consensus rules.
+  #[error("block violated the consensus rules: {0}")]
+  Consensus(ConsensusError),
+}
+
+/// A builder for a block template, selecting transactions and building the miner transaction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BlockTemplateBuilder {
+  previous: [u8; 32],
+  number: usize,
+  hardfork_version: u8,
+  timestamp: u64,
+  median_weight: usize,
+  already_generated_coins: u64,
+  payout: MoneroAddress,
+  reserve_size: usize,
+  transactions: Vec<Transaction>,
+}
+
+impl BlockTemplateBuilder {
+  /// Create a new builder for the block with the specified number, built on `previous`.
+  ///
+  /// `median_weight` should be the result of `consensus::median_block_weight` for this block and
+  /// `already_generated_coins` the amount of coins emitted by all prior blocks. Prior to hard fork
+  /// 12, the reward may be penalized by more than necessary, as the penalty was calculated with
+  /// `consensus::reward_median_block_weight`.
+  pub fn new(
+    previous: [u8; 32],
+    number: usize,
+    hardfork_version: u8,
+    timestamp: u64,
+    median_weight: usize,
+    already_generated_coins: u64,
+    payout: MoneroAddress,
+  ) -> Self {
+    Self {
+      previous,
+      number,
+      hardfork_version,
+      timestamp,
+      median_weight,
+      already_generated_coins,
+      payout,
+      reserve_size: 0,
+      transactions: vec![],
+    }
+  }
+
+  /// Reserve space within the miner transaction's extra, for the miner to vary.
+  pub fn reserve_size(mut self, reserve_size: usize) -> Self {
+    self.reserve_size = reserve_size;
+    self
+  }
+
+  /// Add candidate transactions to potentially include within the block.
+  ///
+  /// These are presumed valid, as checked when they entered the mempool. Candidates which are
+  /// miner transactions, or which spend a key image already spent by a prior-selected candidate,
+  /// will be skipped.
+  pub fn transactions(mut self, transactions: impl IntoIterator<Item = Transaction>) -> Self {
+    self.transactions.extend(transactions);
+    self
+  }
+
+  // Select the transactions to include, returning their hashes, total weight, and total fee
+  fn select(&self, median_weight: usize) -> Result<(Vec<[u8; 32]>, usize, u64), ConsensusError> {
+    let mut candidates = self
+      .transactions
+      .iter()
+      .filter_map(|tx| Some((tx, tx.weight(), fee(tx)?)))
+      .collect::<Vec<_>>();
+    // Sort by fee per weight, descending, without losing precision
+    let fee_times_weight = |fee: u64, weight: usize| {
+      u128::from(fee) * u128::try_from(weight).expect("weight exceeded 128 bits")
+    };
+    candidates.sort_by(|(_, weight_a, fee_a), (_, weight_b, fee_b)| {
+      fee_times_weight(*fee_b, *weight_a).cmp(&fee_times_weight(*fee_a, *weight_b))
+    });
+
+    let max_weight =
+      if self.hardfork_version >= 5 { 2 * median_weight } else { (130 * median_weight) / 100 }
+        .saturating_sub(COINBASE_BLOB_RESERVED_SIZE);
+
+    let mut best_reward =
+      block_reward(median_weight, 0, self.already_generated_coins, self.hardfork_version)?;
+    let (mut hashes, mut total_weight, mut total_fee) = (vec![], 0, 0u64);
+    let mut key_images = HashSet::new();
+    for (tx, weight, fee) in candidates {
+      if (total_weight + weight) > max_weight {
+        continue;
+      }
+
+      // Only include transactions which increase the reward, after any penalty
+      let mut reward = best_reward;
+      if self.hardfork_version >= 5 {
+        let Ok(base) = block_reward(
+          median_weight,
+          total_weight + weight,
+          self.already_generated_coins,
+          self.hardfork_version,
+        ) else {
+          continue;
+        };
+        let Some(with_tx) = total_fee.checked_add(fee).and_then(|fees| fees.checked_add(base))
+        else {
+          continue;
+        };
+        if with_tx < best_reward {
+          continue;
+        }
+        reward = with_tx;
+      } else if total_weight > median_weight {
+        break;
+      }
+
+      let tx_key_images = tx
+        .prefix()
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+          Input::Gen(_) => None,
+          Input::ToKey { key_image, .. } => Some(*key_image),
+        })
+        .collect::<Vec<_>>();
+      if tx_key_images.iter().any(|key_image| key_images.contains(key_image)) {
+        continue;
+      }
+      key_images.extend(tx_key_images);
+
+      hashes.push(tx.hash());
+      total_weight += weight;
+      total_fee += fee;
+      best_reward = reward;
+    }
+    Ok((hashes, total_weight, total_fee))
+  }
+
+  /// Build the block template.
+  ///
+  /// The RNG is used to generate the miner transaction's key.
+  pub fn build(
+    self,
+    rng: &mut (impl RngCore + CryptoRng),
+  ) -> Result<BlockTemplate, BlockTemplateError> {
+    let median_weight = self.median_weight.max(full_reward_zone(self.hardfork_version));
+    let (transactions, transactions_weight, fee) =
+      self.select(median_weight).map_err(BlockTemplateError::Consensus)?;
+
+    // The miner transaction's weight depends on the reward it pays out, which depends on the
+    // block's weight. Since monerod requires the reward be exactly the amount claimable, iterate
+    // until the miner transaction's weight is exactly the weight the reward was calculated with,
+    // padding its extra when it's lighter, as monerod's `Blockchain::create_block_template` does
+    // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+    //   /src/cryptonote_core/blockchain.cpp
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+    let build_miner_transaction = |reward, padding| {
+      let mut builder = MinerTransactionBuilder::new(self.number, self.hardfork_version, reward)
+        .payout(self.payout, 1)
+        .padding(padding);
+      if self.reserve_size != 0 {
+        builder = builder.nonce(vec![0; self.reserve_size]);
+      }
+      builder.build(&mut ChaCha20Rng::from_seed(seed)).map_err(BlockTemplateError::MinerTransaction)
+    };
+    let mut miner_transaction_weight = 0;
+    let (miner_transaction, reward, padding) = 'outer: loop {
+      let reward = block_reward(
+        median_weight,
+        transactions_weight + miner_transaction_weight,
+        self.already_generated_coins,
+        self.hardfork_version,
+      )
+      .map_err(BlockTemplateError::Consensus)? +
+        fee;
+
+      let miner_transaction = build_miner_transaction(reward, 0)?;
+      let weight = miner_transaction.weight();
+      if weight > miner_transaction_weight {
+        miner_transaction_weight = weight;
+        continue;
+      }
+      if weight == miner_transaction_weight {
+        break (miner_transaction, reward, 0);
+      }
+
+      // Pad the miner transaction by the difference, or one byte less if the padding increased
+      // the length of the extra's length prefix
+      let difference = miner_transaction_weight - weight;
+      for padding in [difference, difference - 1] {
+        if padding == 0 {
+          continue;
+        }
+        let miner_transaction = build_miner_transaction(reward, padding)?;
+        if miner_transaction.weight() == miner_transaction_weight {
+          break 'outer (miner_transaction, reward, padding);
+        }
+      }
+      // If neither padding was exact, try again with a heavier miner transaction
+      miner_transaction_weight += 1;
+    };
+
+    let header = BlockHeader {
+      hardfork_version: self.hardfork_version,
+      hardfork_signal: self.hardfork_version,
+      timestamp: self.timestamp,
+      previous: self.previous,
+      nonce: 0,
+    };
+    // The reserved space is at the end of the miner transaction's extra, before any padding,
+    // with the extra followed by a single byte for the lack of RingCT proofs
+    let reserved_offset = header.serialize().len() + miner_transaction.serialize().len() -
+      1 -
+      padding -
+      self.reserve_size;
+    let block = Block::new(header, miner_transaction, transactions)
+      .expect("built a miner transaction which didn't correctly define the block's number");
+
+    Ok(BlockTemplate {
+      block,
+      reward,
+      fee,
+      weight: transactions_weight + miner_transaction_weight,
+      reserved_offset,
+      reserve_size: self.reserve_size,
+    })
+  }
+}
+
+// The fee paid by a transaction, if it isn't a miner transaction
+fn fee(tx: &Transaction) -> Option<u64> {
+  let inputs = &tx.prefix().inputs;
+  if inputs.iter().any(|input| matches!(input, Input::Gen(_))) {
+    None?;
+  }
+  match tx {
+    Transaction::V1 { prefix, .. } => {
+      let mut inputs = 0u64;
+      for input in &prefix.inputs {
+        if let Input::ToKey { amount, .. } = input {
+          inputs = inputs.checked_add((*amount)?)?;
+        }
+      }
+      let mut outputs = 0u64;
+      for output in &prefix.outputs {
+        outputs = outputs.checked_add(output.amount?)?;
+      }
+      inputs.checked_sub(outputs)
+    }
+    Transaction::V2 { proofs, .. } => Some(proofs.as_ref()?.base.fee),
+  }
+}
+
+/// A block template, ready to be mined.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BlockTemplate {
+  block: Block,
+  reward: u64,
+  fee: u64,
+  weight: usize,
+  reserved_offset: usize,
+  reserve_size: usize,
+}
+
+impl BlockTemplate {
+  /// The block, with a nonce of 0 and the reserved space zeroed.
+  pub fn block(&self) -> &Block {
+    &self.block
+  }
+
+  /// The amount paid out by the miner transaction, including fees.
+  pub fn reward(&self) -> u64 {
+    self.reward
+  }
+
+  /// The sum of the fees of the included transactions.
+  pub fn fee(&self) -> u64 {
+    self.fee
+  }
+
+  /// The weight of the block, as used to calculate the reward.
+  pub fn weight(&self) -> usize {
+    self.weight
+  }
+
+  /// The serialized block, as `blocktemplate_blob` is by `get_block_template`.
+  pub fn blob(&self) -> Vec<u8> {
+    self.block.serialize()
+  }
+
+  /// The offset of the reserved space within the serialized block.
+  ///
+  /// This is only meaningful if space was reserved.
+  pub fn reserved_offset(&self) -> usize {
+    self.reserved_offset
+  }
+
+  /// The size of the reserved space.
+  pub fn reserve_size(&self) -> usize {
+    self.reserve_size
+  }
+
+  /// The blob to hash for the proof of work, as `blockhashing_blob` is by `get_block_template`.
+  pub fn hashing_blob(&self) -> Vec<u8> {
+    self.block.serialize_pow_hash()
+  }
+
+  /// The offset of the header's nonce within the hashing blob (and the serialized block).
+  pub fn nonce_offset(&self) -> usize {
+    self.block.header.serialize().len() - 4
+  }
+
+  /// The block with the specified data written to the reserved space.
+  ///
+  /// Returns `None` if the data is larger than the reserved space.
+  pub fn block_with_extra_nonce(&self, extra_nonce: &[u8]) -> Option<Block> {
+    if extra_nonce.len() > self.reserve_size {
+      None?;
+    }
+    let mut blob = self.blob();
+    blob[self.reserved_offset .. (self.reserved_offset + extra_nonce.len())]
+      .copy_from_slice(extra_nonce);
+    Some(Block::read(&mut blob.as_slice()).expect("couldn't read the block template's block"))
+  }
+}
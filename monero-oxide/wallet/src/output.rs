@@ -96,8 +96,13 @@ impl RelativeId {
   }
 }
 
+// RingCT 以前の出力のシリアライズで、key の前に書き込むマーカー
+// これは標準的でない（reduce されていない）点のエンコーディングで、`CompressedPoint::decompress` は
+// これを拒否するため、key から始まるそれ以前のシリアライズと衝突しない
+const PRE_RINGCT_MARKER: [u8; 32] = [0xff; 32];
+
 // --- OutputData: 出力を消費するために必要なデータ ---
-/// 出力の中身: 出力鍵、鍵オフセット、コミットメントを保持する
+/// 出力の中身: 出力鍵、鍵オフセット、コミットメント、RingCT 以前の出力かどうかを保持する
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub(crate) struct OutputData {
   // 出力の公開鍵（Point）
@@ -106,6 +111,9 @@ pub(crate) struct OutputData {
   pub(crate) key_offset: Scalar,
   // 出力で使用されたコミットメント
   pub(crate) commitment: Commitment,
+  // RingCT 以前（バージョン 1 トランザクション）の出力か
+  // その場合、出力は同じ金額の出力プールに属し、コミットメントのマスクは 1 となる
+  pub(crate) pre_ringct: bool,
 }
 
 impl core::fmt::Debug for OutputData {
@@ -115,16 +123,18 @@ impl core::fmt::Debug for OutputData {
       .debug_struct("OutputData")
       .field("key", &hex::encode(self.key.compress().to_bytes()))
       .field("commitment", &self.commitment)
+      .field("pre_ringct", &self.pre_ringct)
       .finish_non_exhaustive()
   }
 }
 
 impl OutputData {
-  // 定数時間比較: key, key_offset, commitment をすべて比較
+  // 定数時間比較: key, key_offset, commitment, pre_ringct をすべて比較
   pub(crate) fn ct_eq(&self, other: &Self) -> Choice {
     self.key.ct_eq(&other.key) &
       self.key_offset.ct_eq(&other.key_offset) &
-      self.commitment.ct_eq(&other.commitment)
+      self.commitment.ct_eq(&other.commitment) &
+      Choice::from(u8::from(self.pre_ringct == other.pre_ringct))
   }
 
   // key を返す（コピー可能な Point）
@@ -142,21 +152,38 @@ impl OutputData {
     &self.commitment
   }
 
+  // RingCT 以前の出力であれば、その出力が属するプールの金額を返す
+  pub(crate) fn pre_ringct_amount(&self) -> Option<u64> {
+    self.pre_ringct.then_some(self.commitment.amount)
+  }
+
   // シリアライズ: key の圧縮バイト列、key_offset、commitment を順に書き込む
+  // RingCT 以前の出力は、key の前に `PRE_RINGCT_MARKER` を書き込む。RingCT の出力のシリアライズは
+  // RingCT 以前の出力に対応する前のものと同一
   pub(crate) fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    if self.pre_ringct {
+      w.write_all(&PRE_RINGCT_MARKER)?;
+    }
     w.write_all(&self.key.compress().to_bytes())?;
     self.key_offset.write(w)?;
     self.commitment.write(w)
   }
 
   // デシリアライズ: 圧縮点から復元、失敗した場合はエラー
+  // 最初の 32 バイトが `PRE_RINGCT_MARKER` であれば、RingCT 以前の出力として続く key を読み込む
   pub(crate) fn read<R: Read>(r: &mut R) -> io::Result<OutputData> {
+    let mut key = CompressedPoint::read(r)?;
+    let pre_ringct = key.to_bytes() == PRE_RINGCT_MARKER;
+    if pre_ringct {
+      key = CompressedPoint::read(r)?;
+    }
     Ok(OutputData {
-      key: CompressedPoint::read(r)?
+      key: key
         .decompress()
         .ok_or_else(|| io::Error::other("output data included an invalid key"))?,
       key_offset: Scalar::read(r)?,
       commitment: Commitment::read(r)?,
+      pre_ringct,
     })
   }
 }
@@ -314,10 +341,19 @@ impl WalletOutput {
   }
 
   /// ブロックチェーン上のインデックスを返す
+  ///
+  /// RingCT 以前の出力の場合、これは同じ金額の出力プール内でのインデックスとなる
   pub fn index_on_blockchain(&self) -> u64 {
     self.relative_id.index_on_blockchain
   }
 
+  /// RingCT 以前（バージョン 1 トランザクション）の出力かどうかを返す
+  ///
+  /// そのような出力は金額が平文であり、同じ金額の出力プールからデコイを選択して消費される
+  pub fn pre_ringct(&self) -> bool {
+    self.data.pre_ringct
+  }
+
   /// 出力鍵を返す
   pub fn key(&self) -> Point {
     self.data.key()
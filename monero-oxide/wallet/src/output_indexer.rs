@@ -0,0 +1,191 @@
+// 連続するブロックから出力のグローバルインデックスを計算するモジュールです。
+// 金額ごとの出力プールの出力数を数え続けることで、ノードに `get_o_indexes` を要求せずに
+// `ScannableBlock` を生成します。ディスク上のブロックをインデックス化する用途を想定しています。
+use std_shims::{
+  vec,
+  vec::Vec,
+  io::{self, Read, Write},
+  collections::HashMap,
+};
+
+use monero_rpc::ScannableBlock;
+use monero_oxide::{
+  io::*,
+  transaction::{Pruned, Transaction},
+  block::Block,
+};
+
+/// An error when indexing a block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum OutputIndexerError {
+  /// The block wasn't the block after the last indexed block.
+  #[error("block didn't follow the last indexed block")]
+  NonConsecutiveBlock,
+  /// The transactions provided didn't match the block's list of transactions.
+  #[error("transactions provided didn't match the block")]
+  TransactionsMismatch,
+}
+
+/// A tracker of the global output indexes, producing `ScannableBlock`s without an RPC.
+///
+/// Monero indexes outputs within pools of outputs sharing an amount. All RingCT outputs, which
+/// are the outputs of version 2 transactions (including version 2 miner transactions), are within
+/// the pool for amount `0`. The outputs of version 1 transactions are within the pool for their
+/// amount.
+///
+/// This keeps a running count of the outputs within each pool. It must be provided every block, in
+/// order, starting from either the genesis block or the state it was last written with.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct OutputIndexer {
+  next_block_number: usize,
+  last_block_hash: Option<[u8; 32]>,
+  pools: HashMap<u64, u64>,
+}
+
+impl OutputIndexer {
+  /// Create a new indexer, which expects the genesis block to be indexed first.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The number of the next block to be indexed.
+  pub fn next_block_number(&self) -> usize {
+    self.next_block_number
+  }
+
+  /// The amount of outputs within the pool for the specified amount.
+  ///
+  /// This is also the global index the next output created within the pool will have. Use an
+  /// amount of `0` for the amount of RingCT outputs.
+  pub fn outputs(&self, amount: u64) -> u64 {
+    self.pools.get(&amount).copied().unwrap_or(0)
+  }
+
+  /// Index a block, returning it in its scannable form.
+  ///
+  /// `transactions` must be the block's non-miner transactions, in the order the block lists them.
+  /// They're checked against the hashes committed to by the block, which is why they must not be
+  /// pruned, before being pruned for the returned `ScannableBlock`.
+  ///
+  /// The block is checked to follow the last indexed block. If an error is returned, the indexer's
+  /// state is left unchanged.
+  pub fn index(
+    &mut self,
+    block: Block,
+    transactions: Vec<Transaction>,
+  ) -> Result<ScannableBlock, OutputIndexerError> {
+    if (block.number() != self.next_block_number) ||
+      self.last_block_hash.is_some_and(|hash| hash != block.header.previous)
+    {
+      Err(OutputIndexerError::NonConsecutiveBlock)?;
+    }
+    if (transactions.len() != block.transactions.len()) ||
+      block.transactions.iter().zip(&transactions).any(|(hash, tx)| *hash != tx.hash())
+    {
+      Err(OutputIndexerError::TransactionsMismatch)?;
+    }
+    let transactions =
+      transactions.into_iter().map(Transaction::<Pruned>::from).collect::<Vec<_>>();
+
+    let mut output_index_for_first_ringct_output = None;
+    let mut pre_ringct_output_indexes = vec![];
+    let miner_transaction = Transaction::<Pruned>::from(block.miner_transaction().clone());
+    for tx in core::iter::once(&miner_transaction).chain(&transactions) {
+      match tx {
+        Transaction::V1 { prefix, .. } => {
+          let mut indexes = Vec::with_capacity(prefix.outputs.len());
+          for output in &prefix.outputs {
+            let pool = self.pools.entry(output.amount.unwrap_or(0)).or_insert(0);
+            indexes.push(*pool);
+            *pool += 1;
+          }
+          pre_ringct_output_indexes.push(indexes);
+        }
+        Transaction::V2 { prefix, .. } => {
+          // Version 2 miner transactions' outputs are also RingCT outputs, despite having their
+          // amounts in the clear
+          if prefix.outputs.is_empty() {
+            continue;
+          }
+          let pool = self.pools.entry(0).or_insert(0);
+          output_index_for_first_ringct_output.get_or_insert(*pool);
+          *pool += u64::try_from(prefix.outputs.len()).expect("more than 2**64 outputs");
+        }
+      }
+    }
+
+    self.next_block_number += 1;
+    self.last_block_hash = Some(block.hash());
+
+    Ok(ScannableBlock {
+      block,
+      transactions,
+      output_index_for_first_ringct_output,
+      pre_ringct_output_indexes,
+    })
+  }
+
+  /// Write the indexer's state.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    w.write_all(
+      &u64::try_from(self.next_block_number).expect("block number exceeded 64 bits").to_le_bytes(),
+    )?;
+    match self.last_block_hash {
+      None => w.write_all(&[0]),
+      Some(hash) => {
+        w.write_all(&[1])?;
+        w.write_all(&hash)
+      }
+    }?;
+
+    let mut pools =
+      self.pools.iter().map(|(amount, outputs)| (*amount, *outputs)).collect::<Vec<_>>();
+    pools.sort_unstable();
+    w.write_all(&u64::try_from(pools.len()).expect("more than 2**64 pools").to_le_bytes())?;
+    for (amount, outputs) in pools {
+      w.write_all(&amount.to_le_bytes())?;
+      w.write_all(&outputs.to_le_bytes())?;
+    }
+    Ok(())
+  }
+
+  /// Serialize the indexer's state to a `Vec<u8>`.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut serialized = Vec::with_capacity(8 + 1 + 32 + 8);
+    self.write(&mut serialized).expect("write failed but <Vec as io::Write> doesn't fail");
+    serialized
+  }
+
+  /// Read an indexer's state.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<OutputIndexer> {
+    let next_block_number = usize::try_from(read_u64(r)?)
+      .map_err(|_| io::Error::other("block number exceeded usize::MAX"))?;
+    let last_block_hash = match read_byte(r)? {
+      0 => None,
+      1 => Some(read_bytes(r)?),
+      _ => Err(io::Error::other("invalid option for the last block's hash"))?,
+    };
+    if last_block_hash.is_some() != (next_block_number != 0) {
+      Err(io::Error::other("last block's hash didn't match the next block's number"))?;
+    }
+
+    let pools_len = read_u64(r)?;
+    let mut pools = HashMap::new();
+    for _ in 0 .. pools_len {
+      if pools.insert(read_u64(r)?, read_u64(r)?).is_some() {
+        Err(io::Error::other("pool for the same amount present multiple times"))?;
+      }
+    }
+
+    Ok(OutputIndexer { next_block_number, last_block_hash, pools })
+  }
+}
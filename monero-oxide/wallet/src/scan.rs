@@ -18,10 +18,13 @@ use monero_rpc::ScannableBlock;
 use monero_oxide::{
   ed25519::{Scalar, CompressedPoint, Point, Commitment},
   transaction::{Timelock, Pruned, Transaction},
+  hardfork::HardFork,
 };
 use crate::{
-  address::SubaddressIndex, ViewPair, GuaranteedViewPair, output::*, PaymentId, Extra,
-  SharedKeyDerivations,
+  address::{Network, AddressType, SubaddressIndex, MoneroAddress},
+  ViewPair, GuaranteedViewPair,
+  output::*,
+  PaymentId, Extra, SharedKeyDerivations,
 };
 
 /// A collection of potentially additionally timelocked outputs.
@@ -85,11 +88,42 @@ pub enum ScanError {
   InvalidScannableBlock(&'static str),
 }
 
+// The indexes of a transaction's outputs on the blockchain
+#[derive(Clone, Copy)]
+enum OutputIndexes<'a> {
+  // RingCT outputs are indexed sequentially, so we only need the index of the first output
+  RingCt(u64),
+  // Pre-RingCT outputs are indexed within the pool of outputs with the same amount
+  PreRingCt(&'a [u64]),
+}
+
+impl OutputIndexes<'_> {
+  fn index(self, o: usize) -> Result<u64, ScanError> {
+    match self {
+      OutputIndexes::RingCt(first) => first
+        .checked_add(u64::try_from(o).expect("couldn't convert output index (usize) to u64"))
+        .ok_or(ScanError::InvalidScannableBlock(
+          "transaction's output's index isn't representable as a u64",
+        )),
+      OutputIndexes::PreRingCt(indexes) => indexes
+        .get(o)
+        .copied()
+        .ok_or(ScanError::InvalidScannableBlock("pre-RingCT transaction's output lacked an index")),
+    }
+  }
+}
+
 #[derive(Clone)]
 struct InternalScanner {
   pair: ViewPair,
   guaranteed: bool,
   subaddresses: HashMap<CompressedPoint, Option<SubaddressIndex>>,
+  // The amount of accounts, and addresses within an account, to register beyond those received to
+  lookahead: Option<(u32, u32)>,
+  // The amount of addresses registered by the lookahead, from the start of each account it expanded
+  lookahead_extents: HashMap<u32, u32>,
+  // The subaddresses registered by the lookahead which have yet to be taken
+  new_subaddresses: Vec<SubaddressIndex>,
 }
 
 impl Zeroize for InternalScanner {
@@ -102,6 +136,12 @@ impl Zeroize for InternalScanner {
       key.zeroize();
       value.zeroize();
     }
+    self.lookahead.zeroize();
+    for (mut account, mut extent) in self.lookahead_extents.drain() {
+      account.zeroize();
+      extent.zeroize();
+    }
+    self.new_subaddresses.zeroize();
   }
 }
 impl Drop for InternalScanner {
@@ -115,26 +155,105 @@ impl InternalScanner {
   fn new(pair: ViewPair, guaranteed: bool) -> Self {
     let mut subaddresses = HashMap::new();
     subaddresses.insert(pair.spend().compress(), None);
-    Self { pair, guaranteed, subaddresses }
+    Self {
+      pair,
+      guaranteed,
+      subaddresses,
+      lookahead: None,
+      lookahead_extents: HashMap::new(),
+      new_subaddresses: vec![],
+    }
   }
 
-  fn register_subaddress(&mut self, subaddress: SubaddressIndex) {
-    let (spend, _) = self.pair.subaddress_keys(subaddress);
-    self.subaddresses.insert(spend.compress(), Some(subaddress));
+  // Register subaddresses by their spend keys
+  //
+  // Returns the subaddresses which weren't already registered.
+  fn register_subaddress_spend_keys(
+    &mut self,
+    subaddresses: &[SubaddressIndex],
+    spend_keys: impl IntoIterator<Item = Point>,
+  ) -> Vec<SubaddressIndex> {
+    self.subaddresses.reserve(subaddresses.len());
+    let mut new = vec![];
+    for (subaddress, spend) in subaddresses.iter().zip(spend_keys) {
+      if self.subaddresses.insert(spend.compress(), Some(*subaddress)).is_none() {
+        new.push(*subaddress);
+      }
+    }
+    new
+  }
+
+  // Returns the subaddresses which weren't already registered
+  fn register_subaddresses(&mut self, subaddresses: &[SubaddressIndex]) -> Vec<SubaddressIndex> {
+    let spend_keys = self.pair.subaddress_spend_keys(subaddresses);
+    self.register_subaddress_spend_keys(subaddresses, spend_keys)
+  }
+
+  // Register subaddresses, returning their addresses
+  fn register_subaddresses_with_addresses(
+    &mut self,
+    network: Network,
+    subaddresses: &[SubaddressIndex],
+  ) -> Vec<MoneroAddress> {
+    let keys = self.pair.subaddresses_keys(subaddresses);
+    self.register_subaddress_spend_keys(subaddresses, keys.iter().map(|(spend, _)| *spend));
+    let address_type = if self.guaranteed {
+      AddressType::Featured { subaddress: true, payment_id: None, guaranteed: true }
+    } else {
+      AddressType::Subaddress
+    };
+    keys
+      .into_iter()
+      .map(|(spend, view)| MoneroAddress::new(network, address_type, spend, view))
+      .collect()
+  }
+
+  fn set_subaddress_lookahead(&mut self, accounts: u32, addresses: u32) {
+    self.lookahead = Some((accounts, addresses));
+    // Expand from the primary address, as wallet2 does when creating a wallet
+    self.expand_subaddresses(0, 0);
+  }
+
+  // Register every subaddress within the lookahead of the specified subaddress
+  //
+  // This registers the first `addresses` addresses of every account from `account` to
+  // `account + accounts`, and within `account`, every address before `address + addresses`.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/wallet/wallet2.cpp
+  //
+  // Unlike wallet2, the accounts before `account` aren't expanded. wallet2 only receives to
+  // subaddresses within its lookahead, yet subaddresses may be explicitly registered here at any
+  // account, and expanding every prior account would make the cost of receiving to such a
+  // subaddress proportional to its account.
+  fn expand_subaddresses(&mut self, account: u32, address: u32) {
+    let Some((lookahead_accounts, lookahead_addresses)) = self.lookahead else { return };
+    for this_account in account .. account.saturating_add(lookahead_accounts) {
+      let end = if this_account == account {
+        address.saturating_add(lookahead_addresses)
+      } else {
+        lookahead_addresses
+      };
+
+      let start = self.lookahead_extents.get(&this_account).copied().unwrap_or(0);
+      if start >= end {
+        continue;
+      }
+      // This will skip the primary address, which is always registered
+      let subaddresses = (start .. end)
+        .filter_map(|this_address| SubaddressIndex::new(this_account, this_address))
+        .collect::<Vec<_>>();
+      let new = self.register_subaddresses(&subaddresses);
+      self.new_subaddresses.extend(new);
+      self.lookahead_extents.insert(this_account, end);
+    }
   }
 
   fn scan_transaction(
     &self,
-    output_index_for_first_ringct_output: u64,
+    output_indexes: OutputIndexes<'_>,
     tx_hash: [u8; 32],
     tx: &Transaction<Pruned>,
   ) -> Result<Timelocked, ScanError> {
-    // Only scan TXs creating RingCT outputs
-    // For the full details on why this check is equivalent, please see the documentation in `scan`
-    if tx.version() != 2 {
-      return Ok(Timelocked(vec![]));
-    }
-
     // Read the extra field
     let Ok(extra) = Extra::read(&mut tx.prefix().extra.as_slice()) else {
       return Ok(Timelocked(vec![]));
@@ -207,7 +326,7 @@ impl InternalScanner {
         // Since we've found an output to us, get its amount
         let mut commitment = Commitment::zero();
 
-        // Miner transaction
+        // Miner transaction, or pre-RingCT transaction
         if let Some(amount) = output.amount {
           commitment.amount = amount;
         // Regular transaction
@@ -234,18 +353,19 @@ impl InternalScanner {
         // Decrypt the payment ID
         let payment_id = payment_id.map(|id| id ^ SharedKeyDerivations::payment_id_xor(ecdh));
 
-        let o = u64::try_from(o).expect("couldn't convert output index (usize) to u64");
-
         res.push(WalletOutput {
-          absolute_id: AbsoluteId { transaction: tx_hash, index_in_transaction: o },
-          relative_id: RelativeId {
-            index_on_blockchain: output_index_for_first_ringct_output.checked_add(o).ok_or(
-              ScanError::InvalidScannableBlock(
-                "transaction's output's index isn't representable as a u64",
-              ),
-            )?,
+          absolute_id: AbsoluteId {
+            transaction: tx_hash,
+            index_in_transaction: u64::try_from(o)
+              .expect("couldn't convert output index (usize) to u64"),
+          },
+          relative_id: RelativeId { index_on_blockchain: output_indexes.index(o)? },
+          data: OutputData {
+            key: output_key,
+            key_offset: Scalar::from(key_offset),
+            commitment,
+            pre_ringct: matches!(tx, Transaction::V1 { .. }),
           },
-          data: OutputData { key: output_key, key_offset: Scalar::from(key_offset), commitment },
           metadata: Metadata {
             additional_timelock: tx.prefix().additional_timelock,
             subaddress,
@@ -266,18 +386,19 @@ impl InternalScanner {
   fn scan(&mut self, block: ScannableBlock) -> Result<Timelocked, ScanError> {
     // This is the output index for the first RingCT output within the block
     // We mutate it to be the output index for the first RingCT for each transaction
-    let ScannableBlock { block, transactions, output_index_for_first_ringct_output } = block;
+    let ScannableBlock {
+      block,
+      transactions,
+      mut output_index_for_first_ringct_output,
+      pre_ringct_output_indexes,
+    } = block;
     if block.transactions.len() != transactions.len() {
       Err(ScanError::InvalidScannableBlock(
         "scanning a ScannableBlock with more/less transactions than it should have",
       ))?;
     }
-    let Some(mut output_index_for_first_ringct_output) = output_index_for_first_ringct_output
-    else {
-      return Ok(Timelocked(vec![]));
-    };
 
-    if block.header.hardfork_version > 16 {
+    if block.header.hardfork_version > HardFork::LATEST.version() {
       Err(ScanError::UnsupportedProtocol(block.header.hardfork_version))?;
     }
 
@@ -290,27 +411,65 @@ impl InternalScanner {
       txs_with_hashes.push((*hash, tx));
     }
 
+    let mut pre_ringct_output_indexes = pre_ringct_output_indexes.iter();
     let mut res = Timelocked(vec![]);
     for (hash, tx) in txs_with_hashes {
+      let outputs = tx.prefix().outputs.len();
+
+      let output_indexes = match tx {
+        Transaction::V1 { .. } => {
+          let indexes = pre_ringct_output_indexes.next().ok_or(
+            ScanError::InvalidScannableBlock("pre-RingCT transaction without output indexes"),
+          )?;
+          if indexes.len() != outputs {
+            Err(ScanError::InvalidScannableBlock(
+              "pre-RingCT transaction had more/less output indexes than outputs",
+            ))?;
+          }
+          OutputIndexes::PreRingCt(indexes)
+        }
+        Transaction::V2 { .. } => {
+          if outputs == 0 {
+            continue;
+          }
+          let Some(index) = output_index_for_first_ringct_output else {
+            Err(ScanError::InvalidScannableBlock(
+              "RingCT outputs without the index of the first RingCT output",
+            ))?
+          };
+          // Update the RingCT starting index for the next TX
+          output_index_for_first_ringct_output = Some(
+            index
+              .checked_add(
+                u64::try_from(outputs).expect("couldn't convert amount of outputs (usize) to u64"),
+              )
+              .ok_or(ScanError::InvalidScannableBlock("RingCT output indexes exceeded u64::MAX"))?,
+          );
+          OutputIndexes::RingCt(index)
+        }
+      };
+
       // Push all outputs into our result
-      {
-        let mut this_txs_outputs = vec![];
-        core::mem::swap(
-          &mut self.scan_transaction(output_index_for_first_ringct_output, hash, &tx)?.0,
-          &mut this_txs_outputs,
+      let mut this_txs_outputs = vec![];
+      core::mem::swap(
+        &mut self.scan_transaction(output_indexes, hash, &tx)?.0,
+        &mut this_txs_outputs,
+      );
+      // Expand the lookahead so later transactions are scanned with the newly registered
+      // subaddresses, as wallet2 does
+      for output in &this_txs_outputs {
+        let subaddress = output.subaddress();
+        self.expand_subaddresses(
+          subaddress.map_or(0, |subaddress| subaddress.account()),
+          subaddress.map_or(0, |subaddress| subaddress.address()),
         );
-        res.0.extend(this_txs_outputs);
-      }
-
-      // Update the RingCT starting index for the next TX
-      if matches!(tx, Transaction::V2 { .. }) {
-        output_index_for_first_ringct_output = output_index_for_first_ringct_output
-          .checked_add(
-            u64::try_from(tx.prefix().outputs.len())
-              .expect("couldn't convert amount of outputs (usize) to u64"),
-          )
-          .ok_or(ScanError::InvalidScannableBlock("RingCT output indexes exceeded u64::MAX"))?;
       }
+      res.0.extend(this_txs_outputs);
+    }
+    if pre_ringct_output_indexes.next().is_some() {
+      Err(ScanError::InvalidScannableBlock(
+        "more pre-RingCT output indexes than pre-RingCT transactions",
+      ))?;
     }
 
     // If the block's version is >= 12, drop all unencrypted payment IDs
@@ -339,6 +498,9 @@ impl InternalScanner {
 ///
 /// Once checked, the output key MUST be saved to the local database so future checks can be
 /// performed.
+///
+/// Outputs created by version 1 (pre-RingCT) transactions are also scanned, as identified by
+/// `WalletOutput::pre_ringct`.
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct Scanner(InternalScanner);
 
@@ -350,9 +512,45 @@ impl Scanner {
 
   /// Register a subaddress to scan for.
   ///
-  /// Subaddresses must be explicitly registered ahead of time in order to be successfully scanned.
+  /// Subaddresses must be explicitly registered ahead of time in order to be successfully scanned,
+  /// either with this function or by a lookahead.
   pub fn register_subaddress(&mut self, subaddress: SubaddressIndex) {
-    self.0.register_subaddress(subaddress)
+    self.0.register_subaddresses(&[subaddress]);
+  }
+
+  /// Register many subaddresses to scan for, returning their addresses.
+  ///
+  /// This is equivalent to calling `register_subaddress` and `ViewPair::subaddress` for each
+  /// subaddress, yet faster, as each subaddress's keys are only derived once, as
+  /// `ViewPair::subaddresses` does.
+  pub fn register_subaddresses(
+    &mut self,
+    network: Network,
+    subaddresses: &[SubaddressIndex],
+  ) -> Vec<MoneroAddress> {
+    self.0.register_subaddresses_with_addresses(network, subaddresses)
+  }
+
+  /// Set the subaddress lookahead, as wallet2 has.
+  ///
+  /// This registers the first `addresses` addresses of the first `accounts` accounts. Whenever an
+  /// output is received to a subaddress, the subaddresses within the lookahead of it are then
+  /// registered, being the first `addresses` addresses of the `accounts` accounts starting with
+  /// its own, and the `addresses` addresses after it within its account. wallet2 defaults to a
+  /// lookahead of 50 accounts and 200 addresses.
+  ///
+  /// The subaddresses registered are returned by `take_new_subaddresses`.
+  pub fn set_subaddress_lookahead(&mut self, accounts: u32, addresses: u32) {
+    self.0.set_subaddress_lookahead(accounts, addresses);
+  }
+
+  /// Take the subaddresses newly registered by the lookahead.
+  ///
+  /// This returns the subaddresses registered since this function was last called, which the
+  /// caller may persist. Subaddresses already registered (including those explicitly registered)
+  /// are not returned.
+  pub fn take_new_subaddresses(&mut self) -> Vec<SubaddressIndex> {
+    core::mem::take(&mut self.0.new_subaddresses)
   }
 
   /// Scan a block.
@@ -379,9 +577,45 @@ impl GuaranteedScanner {
 
   /// Register a subaddress to scan for.
   ///
-  /// Subaddresses must be explicitly registered ahead of time in order to be successfully scanned.
+  /// Subaddresses must be explicitly registered ahead of time in order to be successfully scanned,
+  /// either with this function or by a lookahead.
   pub fn register_subaddress(&mut self, subaddress: SubaddressIndex) {
-    self.0.register_subaddress(subaddress)
+    self.0.register_subaddresses(&[subaddress]);
+  }
+
+  /// Register many subaddresses to scan for, returning their addresses.
+  ///
+  /// This is equivalent to calling `register_subaddress` for each subaddress and deriving its
+  /// address with `GuaranteedViewPair::address` (without a payment ID), yet faster, as each
+  /// subaddress's keys are only derived once, as `GuaranteedViewPair::subaddresses` does.
+  pub fn register_subaddresses(
+    &mut self,
+    network: Network,
+    subaddresses: &[SubaddressIndex],
+  ) -> Vec<MoneroAddress> {
+    self.0.register_subaddresses_with_addresses(network, subaddresses)
+  }
+
+  /// Set the subaddress lookahead, as wallet2 has.
+  ///
+  /// This registers the first `addresses` addresses of the first `accounts` accounts. Whenever an
+  /// output is received to a subaddress, the subaddresses within the lookahead of it are then
+  /// registered, being the first `addresses` addresses of the `accounts` accounts starting with
+  /// its own, and the `addresses` addresses after it within its account. wallet2 defaults to a
+  /// lookahead of 50 accounts and 200 addresses.
+  ///
+  /// The subaddresses registered are returned by `take_new_subaddresses`.
+  pub fn set_subaddress_lookahead(&mut self, accounts: u32, addresses: u32) {
+    self.0.set_subaddress_lookahead(accounts, addresses);
+  }
+
+  /// Take the subaddresses newly registered by the lookahead.
+  ///
+  /// This returns the subaddresses registered since this function was last called, which the
+  /// caller may persist. Subaddresses already registered (including those explicitly registered)
+  /// are not returned.
+  pub fn take_new_subaddresses(&mut self) -> Vec<SubaddressIndex> {
+    core::mem::take(&mut self.0.new_subaddresses)
   }
 
   /// Scan a block.
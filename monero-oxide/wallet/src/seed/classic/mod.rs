@@ -0,0 +1,286 @@
+// wallet2 の古典的な（Electrum 形式の）ニーモニックシード。
+// 4 バイトごとに 3 語へ変換し、単語の先頭（一意な接頭辞）の CRC32 から選ばれるチェックサム語を
+// 末尾に付与します。32 バイトの 25 語シードに加え、MyMonero の 16 バイトの 13 語シードも扱います。
+use std_shims::{vec::Vec, string::String};
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use rand_core::{RngCore, CryptoRng};
+
+use crate::{ed25519::Scalar, primitives::keccak256, ViewPair, WalletKeys};
+
+mod english;
+
+/// The length of a classic seed, in words, including its checksum word.
+pub const CLASSIC_SEED_LENGTH: usize = 25;
+/// The length of a legacy MyMonero seed, in words, including its checksum word.
+pub const LEGACY_SEED_LENGTH: usize = 13;
+
+/// A language with a word list for classic seeds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Zeroize)]
+pub enum Language {
+  /// English.
+  English,
+}
+
+impl Language {
+  // Every language, in the order wallet2 attempts to detect them in
+  const ALL: [Language; 1] = [Language::English];
+
+  /// Detect the language of a seed's words.
+  ///
+  /// This returns the first language whose word list has every word, preferring a language with
+  /// every word in full over one with every word as a unique prefix, as wallet2 does. `None` is
+  /// returned if no language has every word.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/mnemonics/electrum-words.cpp
+  pub fn detect(words: &str) -> Option<Language> {
+    let has_every_word = |language: Language, full: bool| {
+      words.split_whitespace().all(|word| {
+        if full {
+          language.words().contains(&word)
+        } else {
+          index(language, word).is_some()
+        }
+      })
+    };
+    Self::ALL
+      .into_iter()
+      .find(|language| has_every_word(*language, true))
+      .or_else(|| Self::ALL.into_iter().find(|language| has_every_word(*language, false)))
+  }
+
+  fn words(self) -> &'static [&'static str] {
+    match self {
+      Language::English => &english::ENGLISH,
+    }
+  }
+
+  // The amount of characters a word is uniquely identified by
+  fn unique_prefix_length(self) -> usize {
+    match self {
+      Language::English => 3,
+    }
+  }
+}
+
+/// An error while decoding a classic seed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum SeedError {
+  /// The seed had an invalid amount of words.
+  #[error("invalid amount of words ({0})")]
+  InvalidLength(usize),
+  /// The seed had a word not present within the language's word list.
+  #[error("word {0} wasn't present within the word list")]
+  UnknownWord(usize),
+  /// The seed's words were of another language than the one specified.
+  #[error("words were of another language ({0:?})")]
+  WrongLanguage(Language),
+  /// The seed's checksum word was invalid.
+  #[error("invalid checksum")]
+  InvalidChecksum,
+  /// The seed's words didn't encode a valid seed.
+  #[error("words didn't encode a valid seed")]
+  InvalidSeed,
+}
+
+// The first `len` characters of a word
+fn prefix(word: &str, len: usize) -> &str {
+  word.char_indices().nth(len).map_or(word, |(i, _)| &word[.. i])
+}
+
+// The index of a word within a word list, matching on the full word or its unique prefix
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/mnemonics/electrum-words.cpp
+fn index(language: Language, word: &str) -> Option<usize> {
+  let words = language.words();
+  words.iter().position(|candidate| *candidate == word).or_else(|| {
+    let prefix_len = language.unique_prefix_length();
+    if word.chars().count() < prefix_len {
+      return None;
+    }
+    let word = prefix(word, prefix_len);
+    words.iter().position(|candidate| prefix(candidate, prefix_len) == word)
+  })
+}
+
+// The CRC-32 (as used by zlib and Boost's `crc_32_type`)
+fn crc32(data: &[u8]) -> u32 {
+  let mut crc = u32::MAX;
+  for byte in data {
+    crc ^= u32::from(*byte);
+    for _ in 0 .. 8 {
+      crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+    }
+  }
+  !crc
+}
+
+// The index of the checksum word, within the words, for the specified words
+// https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+//   /src/mnemonics/electrum-words.cpp
+fn checksum_index(language: Language, indexes: &[usize]) -> usize {
+  let mut trimmed = Zeroizing::new(String::new());
+  for index in indexes {
+    trimmed.push_str(prefix(language.words()[*index], language.unique_prefix_length()));
+  }
+  usize::try_from(crc32(trimmed.as_bytes())).expect("usize couldn't represent a u32") %
+    indexes.len()
+}
+
+/// A classic seed, as used by wallet2.
+///
+/// This is either a 25-word seed, encoding the private spend key, or a 13-word legacy MyMonero
+/// seed, encoding 16 bytes the private spend key is derived from.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct ClassicSeed {
+  language: Language,
+  entropy: Zeroizing<Vec<u8>>,
+}
+
+impl core::fmt::Debug for ClassicSeed {
+  fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    fmt.debug_struct("ClassicSeed").field("language", &self.language).finish_non_exhaustive()
+  }
+}
+
+impl ClassicSeed {
+  /// Generate a new seed.
+  pub fn new(rng: &mut (impl RngCore + CryptoRng), language: Language) -> Self {
+    Self::from_spend_key(language, &Zeroizing::new(Scalar::random(rng)))
+  }
+
+  /// Create the seed for a private spend key.
+  pub fn from_spend_key(language: Language, spend_key: &Zeroizing<Scalar>) -> Self {
+    ClassicSeed {
+      language,
+      entropy: Zeroizing::new(Zeroizing::new(<[u8; 32]>::from(**spend_key)).to_vec()),
+    }
+  }
+
+  /// Decode a seed from its words.
+  ///
+  /// Words may be abbreviated to their unique prefix, as wallet2 allows. Private spend keys which
+  /// aren't reduced are accepted, and reduced, as wallet2 does. If the words aren't of the
+  /// specified language, yet are of another, `SeedError::WrongLanguage` is returned. Use
+  /// [`Language::detect`] to decode a seed of an unknown language.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/mnemonics/electrum-words.cpp
+  pub fn from_string(language: Language, words: &str) -> Result<Self, SeedError> {
+    let mut indexes = Zeroizing::new(Vec::with_capacity(CLASSIC_SEED_LENGTH));
+    for (i, word) in words.split_whitespace().enumerate() {
+      let Some(index) = index(language, word) else {
+        Err(match Language::detect(words) {
+          Some(other) if other != language => SeedError::WrongLanguage(other),
+          _ => SeedError::UnknownWord(i),
+        })?
+      };
+      indexes.push(index);
+    }
+    if (indexes.len() != CLASSIC_SEED_LENGTH) && (indexes.len() != LEGACY_SEED_LENGTH) {
+      Err(SeedError::InvalidLength(indexes.len()))?;
+    }
+
+    let checksum = indexes.pop().expect("checked there were words");
+    if indexes[checksum_index(language, &indexes)] != checksum {
+      Err(SeedError::InvalidChecksum)?;
+    }
+
+    let n = u32::try_from(language.words().len()).expect("word list exceeded u32::MAX");
+    let mut entropy = Zeroizing::new(Vec::with_capacity(32));
+    for chunk in indexes.chunks(3) {
+      let [w1, w2, w3] = [chunk[0], chunk[1], chunk[2]]
+        .map(|index| u32::try_from(index).expect("index exceeded the length of the word list"));
+      // This intentionally wraps, as wallet2 does, with the following check rejecting overflows
+      let value = Zeroizing::new(
+        w1.wrapping_add(n.wrapping_mul(((n - w1) + w2) % n))
+          .wrapping_add(n.wrapping_mul(n).wrapping_mul(((n - w2) + w3) % n)),
+      );
+      if (*value % n) != w1 {
+        Err(SeedError::InvalidSeed)?;
+      }
+      entropy.extend(value.to_le_bytes());
+    }
+
+    Ok(ClassicSeed { language, entropy })
+  }
+
+  /// Encode this seed as its words, including its checksum word.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/mnemonics/electrum-words.cpp
+  pub fn to_string(&self) -> Zeroizing<String> {
+    let words = self.language.words();
+    let n = u32::try_from(words.len()).expect("word list exceeded u32::MAX");
+
+    let mut indexes = Zeroizing::new(Vec::with_capacity(CLASSIC_SEED_LENGTH));
+    for chunk in self.entropy.chunks(4) {
+      let value =
+        Zeroizing::new(u32::from_le_bytes(chunk.try_into().expect("entropy wasn't 4-byte chunks")));
+      let w1 = *value % n;
+      let w2 = ((*value / n) + w1) % n;
+      let w3 = (((*value / n) / n) + w2) % n;
+      for index in [w1, w2, w3] {
+        indexes.push(usize::try_from(index).expect("usize couldn't represent a u32"));
+      }
+    }
+    let checksum = indexes[checksum_index(self.language, &indexes)];
+    indexes.push(checksum);
+
+    let mut res = Zeroizing::new(String::new());
+    for (i, index) in indexes.iter().enumerate() {
+      if i != 0 {
+        res.push(' ');
+      }
+      res.push_str(words[*index]);
+    }
+    res
+  }
+
+  /// The language of this seed.
+  pub fn language(&self) -> Language {
+    self.language
+  }
+
+  /// If this is a legacy (13-word) MyMonero seed.
+  pub fn is_legacy(&self) -> bool {
+    self.entropy.len() == 16
+  }
+
+  /// The private spend key for this seed.
+  ///
+  /// For legacy MyMonero seeds, this is `Hs(seed)`.
+  pub fn spend_key(&self) -> Zeroizing<Scalar> {
+    if self.is_legacy() {
+      return Zeroizing::new(Scalar::hash(self.entropy.as_slice()));
+    }
+    let mut entropy = Zeroizing::new([0; 32]);
+    entropy.copy_from_slice(&self.entropy);
+    Zeroizing::new(Scalar::from(curve25519_dalek::Scalar::from_bytes_mod_order(*entropy)))
+  }
+
+  /// The keys of the wallet for this seed.
+  // https://github.com/mymonero/mymonero-core-cpp/blob/master/src/monero_wallet_utils.cpp
+  pub fn wallet_keys(&self) -> WalletKeys {
+    if self.is_legacy() {
+      // MyMonero derives the private view key from the hash of the seed prior to its reduction
+      let hash = Zeroizing::new(keccak256(self.entropy.as_slice()));
+      return WalletKeys::from_keys(
+        self.spend_key(),
+        Zeroizing::new(Scalar::hash(hash.as_slice())),
+      );
+    }
+    WalletKeys::new(self.spend_key())
+  }
+
+  /// The private view key for this seed.
+  ///
+  /// This is `Hs(spend_key)`, or `Hs(keccak256(seed))` for legacy MyMonero seeds.
+  pub fn view_key(&self) -> Zeroizing<Scalar> {
+    self.wallet_keys().view_key().clone()
+  }
+
+  /// The ViewPair for this seed.
+  pub fn view_pair(&self) -> ViewPair {
+    self.wallet_keys().view_pair().clone()
+  }
+}
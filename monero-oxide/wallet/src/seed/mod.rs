@@ -0,0 +1,7 @@
+// ウォレットの鍵を単語列として表すニーモニックシード。
+
+/// Classic seeds, as used by wallet2.
+pub mod classic;
+
+/// Polyseeds, as used by Feather and Cake Wallet.
+pub mod polyseed;
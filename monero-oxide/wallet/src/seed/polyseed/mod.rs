@@ -0,0 +1,371 @@
+// Polyseed（Feather や Cake Wallet などが用いる 16 語のニーモニックシード）。
+// 各語は GF(2048) 上の多項式の係数で、先頭の語がチェックサム、残りの 15 語が 150 ビットの秘密と、
+// 10 ビットの誕生日（作成月）、5 ビットの機能フラグを保持します。鍵は秘密から PBKDF2-HMAC-SHA256
+// で導出され、任意のパスフレーズで秘密を暗号化できます。
+// https://github.com/tevador/polyseed
+use std_shims::{vec::Vec, string::String};
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use rand_core::{RngCore, CryptoRng};
+
+use sha2::{Digest, Sha256};
+
+use crate::{ed25519::Scalar, difficulty, address::Network, ViewPair, WalletKeys};
+
+mod english;
+
+/// The length of a Polyseed, in words, including its checksum word.
+pub const POLYSEED_LENGTH: usize = 16;
+
+/// The time birthdays are relative to (November 1st, 2021, 12:00 UTC), as a UNIX timestamp.
+pub const POLYSEED_EPOCH: u64 = 1_635_768_000;
+/// The duration each increment of a birthday represents (a twelfth of a Gregorian year), in
+/// seconds.
+pub const POLYSEED_TIME_STEP: u64 = 2_629_746;
+
+// The secret is 150 bits, stored MSB-first within 19 bytes, with the last byte using its low six
+// bits
+const SECRET_SIZE: usize = 19;
+const LAST_BYTE_BITS: usize = 6;
+const LAST_BYTE_MASK: u8 = (1 << LAST_BYTE_BITS) - 1;
+
+// Each word after the checksum word has 10 bits of the secret and one bit of the birthday/features
+const SECRET_BITS_PER_WORD: usize = 10;
+const DATE_BITS: usize = 10;
+const DATE_MASK: u16 = (1 << DATE_BITS) - 1;
+const FEATURE_BITS: usize = 5;
+const EXTRA_BITS: usize = DATE_BITS + FEATURE_BITS;
+
+// The only feature supported is the encryption of the secret, with the rest reserved
+const ENCRYPTED_MASK: u8 = 1 << 4;
+
+const KDF_ITERATIONS: u32 = 10_000;
+// The coin keys are derived for
+const COIN_MONERO: u32 = 0;
+
+/// A language with a word list for Polyseeds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Zeroize)]
+pub enum Language {
+  /// English.
+  English,
+}
+
+impl Language {
+  fn words(self) -> &'static [&'static str] {
+    match self {
+      Language::English => &english::ENGLISH,
+    }
+  }
+
+  // The amount of characters a word is uniquely identified by
+  fn unique_prefix_length(self) -> usize {
+    match self {
+      Language::English => 4,
+    }
+  }
+}
+
+/// An error while decoding a Polyseed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum PolyseedError {
+  /// The seed had an invalid amount of words.
+  #[error("invalid amount of words ({0})")]
+  InvalidLength(usize),
+  /// The seed had a word not present within the language's word list.
+  #[error("word {0} wasn't present within the word list")]
+  UnknownWord(usize),
+  /// The seed's checksum was invalid.
+  #[error("invalid checksum")]
+  InvalidChecksum,
+  /// The seed used features which aren't supported.
+  #[error("seed used unsupported features")]
+  UnsupportedFeatures,
+  /// The seed was encrypted yet no passphrase was provided.
+  #[error("seed was encrypted yet no passphrase was provided")]
+  MissingPassphrase,
+  /// A passphrase was provided yet the seed wasn't encrypted.
+  #[error("passphrase was provided yet the seed wasn't encrypted")]
+  UnexpectedPassphrase,
+}
+
+// The index of a word within a word list, matching on the full word or a prefix of at least the
+// unique prefix length
+fn index(language: Language, word: &str) -> Option<usize> {
+  let words = language.words();
+  words.iter().position(|candidate| *candidate == word).or_else(|| {
+    if word.chars().count() < language.unique_prefix_length() {
+      return None;
+    }
+    words.iter().position(|candidate| candidate.starts_with(word))
+  })
+}
+
+// The byte, and the shift within it, of the `i`th bit of the secret
+fn secret_bit(i: usize) -> (usize, usize) {
+  let byte = i / 8;
+  let bits = if byte == (SECRET_SIZE - 1) { LAST_BYTE_BITS } else { 8 };
+  (byte, bits - 1 - (i % 8))
+}
+
+// Multiply an element of GF(2048) by two
+fn mul2(x: u16) -> u16 {
+  const MUL2_TABLE: [u16; 8] = [5, 7, 1, 3, 13, 15, 9, 11];
+  if x < 1024 {
+    return 2 * x;
+  }
+  MUL2_TABLE[usize::from(x % 8)] + (16 * ((x - 1024) / 8))
+}
+
+// Evaluate the polynomial, with the words as its coefficients, at two
+//
+// This is zero for a seed with a valid checksum.
+fn evaluate(coefficients: &[u16; POLYSEED_LENGTH]) -> u16 {
+  let mut res = coefficients[POLYSEED_LENGTH - 1];
+  for coefficient in coefficients[.. (POLYSEED_LENGTH - 1)].iter().rev() {
+    res = mul2(res) ^ coefficient;
+  }
+  res
+}
+
+// PBKDF2-HMAC-SHA256, producing a single 32-byte block
+fn pbkdf2_sha256(password: &[u8], salt: &[u8]) -> Zeroizing<[u8; 32]> {
+  // HMAC hashes keys longer than the block size
+  let mut key = Zeroizing::new([0; 64]);
+  if password.len() > key.len() {
+    key[.. 32].copy_from_slice(&Sha256::digest(password));
+  } else {
+    key[.. password.len()].copy_from_slice(password);
+  }
+  let pad = |byte: u8| {
+    let mut pad = Zeroizing::new(*key);
+    for pad in pad.iter_mut() {
+      *pad ^= byte;
+    }
+    Sha256::new_with_prefix(pad.as_slice())
+  };
+  let (inner, outer) = (pad(0x36), pad(0x5c));
+  let hmac = |message: &[u8]| {
+    let inner = Zeroizing::new(<[u8; 32]>::from(inner.clone().chain_update(message).finalize()));
+    Zeroizing::new(<[u8; 32]>::from(outer.clone().chain_update(inner.as_slice()).finalize()))
+  };
+
+  let mut u = hmac(&[salt, &1u32.to_be_bytes()].concat());
+  let mut res = u.clone();
+  for _ in 1 .. KDF_ITERATIONS {
+    u = hmac(u.as_slice());
+    for (res, u) in res.iter_mut().zip(u.iter()) {
+      *res ^= u;
+    }
+  }
+  res
+}
+
+// Encrypt, or decrypt, a secret with a passphrase
+// https://github.com/tevador/polyseed/blob/master/src/polyseed.c
+fn crypt(secret: &mut [u8; 32], passphrase: &str) {
+  let mask = pbkdf2_sha256(passphrase.as_bytes(), b"POLYSEED mask\0\xff\xff");
+  for (secret, mask) in secret[.. SECRET_SIZE].iter_mut().zip(mask.iter()) {
+    *secret ^= mask;
+  }
+  secret[SECRET_SIZE - 1] &= LAST_BYTE_MASK;
+}
+
+/// A Polyseed.
+///
+/// This is a 16-word seed encoding a 150-bit secret, which the wallet's keys are derived from, and
+/// the month the seed was created in (its birthday), from which a height to restore the wallet
+/// from is estimated. A Polyseed may be encrypted with a passphrase, in which case the passphrase
+/// is required to decode it.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Polyseed {
+  language: Language,
+  birthday: u16,
+  secret: Zeroizing<[u8; 32]>,
+}
+
+impl core::fmt::Debug for Polyseed {
+  fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    fmt
+      .debug_struct("Polyseed")
+      .field("language", &self.language)
+      .field("birthday", &self.birthday)
+      .finish_non_exhaustive()
+  }
+}
+
+impl Polyseed {
+  /// Generate a new seed.
+  ///
+  /// `time` is the current time, as a UNIX timestamp, which the seed's birthday is set from.
+  pub fn new(rng: &mut (impl RngCore + CryptoRng), language: Language, time: u64) -> Self {
+    let mut secret = Zeroizing::new([0; 32]);
+    rng.fill_bytes(&mut secret[.. SECRET_SIZE]);
+    secret[SECRET_SIZE - 1] &= LAST_BYTE_MASK;
+    let birthday = u16::try_from(
+      (time.saturating_sub(POLYSEED_EPOCH) / POLYSEED_TIME_STEP) & u64::from(DATE_MASK),
+    )
+    .expect("masked birthday exceeded u16::MAX");
+    Polyseed { language, birthday, secret }
+  }
+
+  /// Decode a seed from its words.
+  ///
+  /// Words may be abbreviated to a prefix of at least their unique prefix length. The passphrase
+  /// MUST be provided if and only if the seed is encrypted. Passphrases are expected to be
+  /// normalized per Unicode's NFKD form, as done by Polyseed's reference implementation (which
+  /// leaves ASCII passphrases unchanged).
+  // https://github.com/tevador/polyseed/blob/master/src/polyseed.c
+  pub fn from_string(
+    language: Language,
+    words: &str,
+    passphrase: Option<&str>,
+  ) -> Result<Self, PolyseedError> {
+    let mut indexes = Zeroizing::new(Vec::with_capacity(POLYSEED_LENGTH));
+    for (i, word) in words.split_whitespace().enumerate() {
+      indexes.push(index(language, word).ok_or(PolyseedError::UnknownWord(i))?);
+    }
+    if indexes.len() != POLYSEED_LENGTH {
+      Err(PolyseedError::InvalidLength(indexes.len()))?;
+    }
+
+    let mut coefficients = Zeroizing::new([0; POLYSEED_LENGTH]);
+    for (coefficient, index) in coefficients.iter_mut().zip(indexes.iter()) {
+      *coefficient = u16::try_from(*index).expect("index exceeded the length of the word list");
+    }
+    if evaluate(&coefficients) != 0 {
+      Err(PolyseedError::InvalidChecksum)?;
+    }
+
+    let mut secret = Zeroizing::new([0; 32]);
+    let mut extra = 0;
+    for (i, coefficient) in coefficients[1 ..].iter().enumerate() {
+      for j in 0 .. SECRET_BITS_PER_WORD {
+        let (byte, shift) = secret_bit((i * SECRET_BITS_PER_WORD) + j);
+        let bit = u8::try_from((coefficient >> (SECRET_BITS_PER_WORD - j)) & 1)
+          .expect("single bit exceeded u8::MAX");
+        secret[byte] |= bit << shift;
+      }
+      extra = (extra << 1) | (coefficient & 1);
+    }
+
+    let birthday = extra & DATE_MASK;
+    let features = u8::try_from(extra >> DATE_BITS).expect("features exceeded u8::MAX");
+    if (features & !ENCRYPTED_MASK) != 0 {
+      Err(PolyseedError::UnsupportedFeatures)?;
+    }
+    match ((features & ENCRYPTED_MASK) != 0, passphrase) {
+      (true, Some(passphrase)) => crypt(&mut secret, passphrase),
+      (true, None) => Err(PolyseedError::MissingPassphrase)?,
+      (false, Some(_)) => Err(PolyseedError::UnexpectedPassphrase)?,
+      (false, None) => {}
+    }
+
+    Ok(Polyseed { language, birthday, secret })
+  }
+
+  /// Encode this seed as its words, including its checksum word.
+  ///
+  /// If a passphrase is provided, the seed will be encrypted with it. Passphrases are expected to
+  /// be normalized per Unicode's NFKD form.
+  // https://github.com/tevador/polyseed/blob/master/src/gf.c
+  pub fn to_string(&self, passphrase: Option<&str>) -> Zeroizing<String> {
+    let mut secret = self.secret.clone();
+    let mut features = 0;
+    if let Some(passphrase) = passphrase {
+      crypt(&mut secret, passphrase);
+      features |= ENCRYPTED_MASK;
+    }
+    let extra = (u16::from(features) << DATE_BITS) | self.birthday;
+
+    let mut coefficients = Zeroizing::new([0; POLYSEED_LENGTH]);
+    for (i, coefficient) in coefficients[1 ..].iter_mut().enumerate() {
+      for j in 0 .. SECRET_BITS_PER_WORD {
+        let (byte, shift) = secret_bit((i * SECRET_BITS_PER_WORD) + j);
+        *coefficient = (*coefficient << 1) | u16::from((secret[byte] >> shift) & 1);
+      }
+      *coefficient = (*coefficient << 1) | ((extra >> (EXTRA_BITS - 1 - i)) & 1);
+    }
+    coefficients[0] = evaluate(&coefficients);
+
+    let words = self.language.words();
+    let mut res = Zeroizing::new(String::new());
+    for (i, coefficient) in coefficients.iter().enumerate() {
+      if i != 0 {
+        res.push(' ');
+      }
+      res.push_str(words[usize::from(*coefficient)]);
+    }
+    res
+  }
+
+  /// The language of this seed.
+  pub fn language(&self) -> Language {
+    self.language
+  }
+
+  /// The birthday of this seed, as a UNIX timestamp.
+  ///
+  /// This is the start of the period (roughly a month long) the seed was created within.
+  pub fn birthday(&self) -> u64 {
+    POLYSEED_EPOCH + (u64::from(self.birthday) * POLYSEED_TIME_STEP)
+  }
+
+  /// The height to start scanning from when restoring the wallet for this seed.
+  ///
+  /// This estimates the height of the blockchain as of the seed's birthday, as wallet2 estimates
+  /// heights from timestamps, less a month of blocks to account for variance in the block time
+  /// (as wallet2 does when deciding a new wallet's restore height).
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/wallet/wallet2.cpp
+  pub fn restore_height(&self, network: Network) -> usize {
+    const BLOCKS_PER_MONTH: u64 = (60 * 60 * 24 * 30) / difficulty::DIFFICULTY_TARGET_V2;
+
+    // The timestamp and height of the second hard fork, from which blocks target two minutes, and
+    // the amount of blocks the test networks have had rolled back
+    let (fork_time, fork_height, rolled_back) = match network {
+      Network::Mainnet => (1_458_748_658, 1_009_827, 0),
+      Network::Stagenet => (1_520_937_818, 32_000, 30_000),
+      Network::Testnet => (1_448_285_909, 624_634, 342_100),
+    };
+    let height =
+      fork_height + (self.birthday().saturating_sub(fork_time) / difficulty::DIFFICULTY_TARGET_V2);
+    let height = height.saturating_sub(rolled_back).saturating_sub(BLOCKS_PER_MONTH);
+    usize::try_from(height).expect("estimated height exceeded usize::MAX")
+  }
+
+  // The key derived from this seed's secret, domain-separated by the coin, birthday, and features
+  // https://github.com/tevador/polyseed/blob/master/src/polyseed.c
+  fn key(&self) -> Zeroizing<[u8; 32]> {
+    let mut salt = Zeroizing::new([0; 32]);
+    salt[.. 13].copy_from_slice(b"POLYSEED key\0");
+    salt[13 .. 16].copy_from_slice(&[0xff; 3]);
+    salt[16 .. 20].copy_from_slice(&COIN_MONERO.to_le_bytes());
+    salt[20 .. 24].copy_from_slice(&u32::from(self.birthday).to_le_bytes());
+    // The features, which are zero as the secret is decrypted and no other features are supported
+    salt[24 .. 28].copy_from_slice(&0u32.to_le_bytes());
+    pbkdf2_sha256(self.secret.as_slice(), salt.as_slice())
+  }
+
+  /// The private spend key for this seed.
+  ///
+  /// This is the key derived from the seed's secret, reduced.
+  pub fn spend_key(&self) -> Zeroizing<Scalar> {
+    Zeroizing::new(Scalar::from(curve25519_dalek::Scalar::from_bytes_mod_order(*self.key())))
+  }
+
+  /// The keys of the wallet for this seed.
+  pub fn wallet_keys(&self) -> WalletKeys {
+    WalletKeys::new(self.spend_key())
+  }
+
+  /// The private view key for this seed, `Hs(spend_key)`.
+  pub fn view_key(&self) -> Zeroizing<Scalar> {
+    self.wallet_keys().view_key().clone()
+  }
+
+  /// The ViewPair for this seed.
+  pub fn view_pair(&self) -> ViewPair {
+    self.wallet_keys().view_pair().clone()
+  }
+}
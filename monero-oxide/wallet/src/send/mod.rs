@@ -29,6 +29,7 @@ use crate::{
     RctType, RctPrunable, RctProofs,
   },
   transaction::{TransactionPrefix, Transaction},
+  hardfork::HardFork,
   address::{Network, SubaddressIndex, MoneroAddress},
   extra::{MAX_ARBITRARY_DATA_SIZE, MAX_EXTRA_SIZE_BY_RELAY_RULE},
   rpc::FeeRate,
@@ -180,6 +181,9 @@ pub enum SendError {
   /// The RingCT type to produce proofs for this transaction with weren't supported.
   #[error("this library doesn't yet support that RctType")]
   UnsupportedRctType,
+  /// The RingCT type to produce proofs for this transaction with wasn't allowed by the hard fork.
+  #[error("RctType wasn't allowed by the hard fork")]
+  DisallowedRctType,
   /// The transaction had no inputs specified.
   #[error("no inputs")]
   NoInputs,
@@ -427,12 +431,17 @@ impl SignableTransaction {
   /// `data` represents arbitrary data which will be embedded into the transaction's `extra` field.
   /// Please see `Extra::arbitrary_data` for the full impacts of this.
   ///
-  /// This will attempt to sign a transaction as constructed, even if the arguments are
-  /// inconsistent or invalid for some view of the Monero network. It is the caller's
+  /// `hardfork` is the hard fork the transaction is intended to be included under, as can be
+  /// determined with `HardFork::at_height`. The `rct_type` must be allowed by it.
+  ///
+  /// Beyond the `rct_type`, this will attempt to sign a transaction as constructed, even if the
+  /// arguments are inconsistent or invalid for some view of the Monero network. It is the caller's
   /// responsibility to ensure their sanity.
   ///
   /// This function runs in time variable to the validity of the arguments and the public data.
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
+    hardfork: HardFork,
     rct_type: RctType,
     outgoing_view_key: Zeroizing<[u8; 32]>,
     inputs: Vec<OutputWithDecoys>,
@@ -441,6 +450,10 @@ impl SignableTransaction {
     data: Vec<Vec<u8>>,
     fee_rate: FeeRate,
   ) -> Result<SignableTransaction, SendError> {
+    if !hardfork.rct_type_allowed(rct_type) {
+      Err(SendError::DisallowedRctType)?;
+    }
+
     // Re-format the payments and change into a consolidated payments list
     let mut payments = payments
       .into_iter()
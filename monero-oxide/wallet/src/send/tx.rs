@@ -28,7 +28,8 @@ impl SignableTransaction {
     let mut res = Vec::with_capacity(self.inputs.len());
     for (input, key_image) in self.inputs.iter().zip(key_images) {
       res.push(Input::ToKey {
-        amount: None,
+        // Pre-RingCT outputs are spent from the pool for their amount, which must be specified
+        amount: input.pre_ringct().then(|| input.commitment().amount),
         key_offsets: input.decoys().offsets().to_vec(),
         key_image: *key_image,
       });
@@ -0,0 +1,149 @@
+use zeroize::Zeroizing;
+
+use rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::*,
+  block::{BlockHeader, Block},
+  address::Network,
+  miner::MinerTransactionBuilder,
+  aux_pow::*,
+  ViewPair,
+};
+
+fn merge_mined_block(tree: &MergeMiningTree, transactions: usize) -> Block {
+  let mut rng = ChaCha20Rng::from_seed([0xaa; 32]);
+  let spend = Point::from(&Scalar::random(&mut rng).into() * ED25519_BASEPOINT_TABLE);
+  let pair = ViewPair::new(spend, Zeroizing::new(Scalar::random(&mut rng))).unwrap();
+  let miner_transaction = MinerTransactionBuilder::new(3_000_000, 16, 600_000_000_000)
+    .payout(pair.legacy_address(Network::Mainnet), 1)
+    .merge_mining(tree.depth(), tree.root())
+    .build(&mut rng)
+    .unwrap();
+  Block::new(
+    BlockHeader {
+      hardfork_version: 16,
+      hardfork_signal: 16,
+      timestamp: 1_700_000_000,
+      previous: [0xbb; 32],
+      nonce: 0x1234_5678,
+    },
+    miner_transaction,
+    (0 .. transactions).map(|i| [u8::try_from(i).unwrap(); 32]).collect(),
+  )
+  .unwrap()
+}
+
+#[test]
+fn depth() {
+  // Values from Monero's `encode_mm_depth`
+  assert_eq!(encode_depth(1, 0), Some(0));
+  assert_eq!(encode_depth(2, 0), Some(8));
+  assert_eq!(encode_depth(3, 0), Some(17));
+  assert_eq!(encode_depth(3, 1), Some(17 | (1 << 5)));
+  assert_eq!(encode_depth(0, 0), None);
+  assert_eq!(encode_depth(MAX_AUX_CHAINS + 1, 0), None);
+
+  for aux_chains in 1 ..= MAX_AUX_CHAINS {
+    for nonce in [0, 1, 0xdead_beef, u32::MAX] {
+      assert_eq!(decode_depth(encode_depth(aux_chains, nonce).unwrap()), Some((aux_chains, nonce)));
+    }
+  }
+  // The nonce exceeds 32 bits
+  assert_eq!(decode_depth(u64::MAX), None);
+}
+
+#[test]
+fn merge_mining_tree() {
+  let aux_blocks = (0 .. 5u8).map(|i| ([i; 32], [0x80 | i; 32])).collect::<Vec<_>>();
+  let tree = MergeMiningTree::new(&aux_blocks, u32::MAX).unwrap();
+  let (aux_chains, nonce) = decode_depth(tree.depth()).unwrap();
+  assert_eq!(aux_chains, 5);
+
+  let mut slots = aux_blocks
+    .iter()
+    .map(|(chain_id, _)| aux_slot(chain_id, nonce, aux_chains))
+    .collect::<Vec<_>>();
+  slots.sort_unstable();
+  slots.dedup();
+  assert_eq!(slots.len(), 5);
+
+  for (chain_id, _) in &aux_blocks {
+    assert!(tree.branch(chain_id).is_some());
+  }
+  assert!(tree.branch(&[0xff; 32]).is_none());
+
+  assert!(MergeMiningTree::new(&[], u32::MAX).is_none());
+  assert!(MergeMiningTree::new(&[([0; 32], [0; 32]), ([0; 32], [1; 32])], 100).is_none());
+}
+
+#[test]
+fn aux_pow() {
+  let aux_blocks = (0 .. 3u8).map(|i| ([i; 32], [0x80 | i; 32])).collect::<Vec<_>>();
+  let tree = MergeMiningTree::new(&aux_blocks, u32::MAX).unwrap();
+
+  for transactions in [0, 1, 2, 7] {
+    let block = merge_mined_block(&tree, transactions);
+    for (chain_id, aux_block_hash) in &aux_blocks {
+      let proof = AuxPow::new(&block, tree.branch(chain_id).unwrap());
+      assert_eq!(proof.hashing_blob().unwrap(), block.serialize_pow_hash());
+      assert_eq!(AuxPow::read(&mut proof.serialize().as_slice()).unwrap(), proof);
+
+      let expected_blob = block.serialize_pow_hash();
+      assert_eq!(
+        proof.verify(chain_id, *aux_block_hash, u128::MAX, |blob| {
+          assert_eq!(blob, expected_blob);
+          [0; 32]
+        }),
+        Ok(())
+      );
+
+      // The proof of work must meet the difficulty
+      assert_eq!(
+        proof.verify(chain_id, *aux_block_hash, 2, |_| [0xff; 32]),
+        Err(AuxPowError::InsufficientWork)
+      );
+      // The proof only commits to this chain's block
+      assert_eq!(
+        proof.verify(chain_id, [0xff; 32], 1, |_| [0; 32]),
+        Err(AuxPowError::InvalidAuxBranch)
+      );
+      let other = aux_blocks.iter().find(|(id, _)| id != chain_id).unwrap();
+      assert_eq!(
+        proof.verify(&other.0, other.1, 1, |_| [0; 32]),
+        Err(AuxPowError::InvalidAuxBranch)
+      );
+
+      let mut tampered = proof.clone();
+      tampered.transaction_count += 1;
+      if transactions != 0 {
+        tampered.miner_transaction_branch.pop();
+      }
+      assert_eq!(
+        tampered.verify(chain_id, *aux_block_hash, 1, |_| [0; 32]),
+        Err(AuxPowError::InvalidMinerTransactionBranch)
+      );
+    }
+  }
+
+  // A miner transaction without a merge-mining tag doesn't commit to any auxiliary blocks
+  let block = merge_mined_block(&tree, 0);
+  let mut proof = AuxPow::new(&block, tree.branch(&aux_blocks[0].0).unwrap());
+  let mut rng = ChaCha20Rng::from_seed([0xcc; 32]);
+  let spend = Point::from(&Scalar::random(&mut rng).into() * ED25519_BASEPOINT_TABLE);
+  let pair = ViewPair::new(spend, Zeroizing::new(Scalar::random(&mut rng))).unwrap();
+  proof.miner_transaction = MinerTransactionBuilder::new(3_000_000, 16, 600_000_000_000)
+    .payout(pair.legacy_address(Network::Mainnet), 1)
+    .build(&mut rng)
+    .unwrap();
+  assert_eq!(
+    proof.verify(&aux_blocks[0].0, aux_blocks[0].1, 1, |_| [0; 32]),
+    Err(AuxPowError::MissingMergeMiningTag)
+  );
+}
@@ -0,0 +1,393 @@
+use zeroize::Zeroizing;
+
+use rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::*,
+  transaction::{Input, Output, Timelock, TransactionPrefix, Transaction},
+  block::{BlockHeader, Block},
+  consensus::{base_reward, block_reward},
+  address::{Network, AddressType, SubaddressIndex, MoneroAddress},
+  extra::{ExtraField, Extra},
+  rpc::ScannableBlock,
+  miner::*,
+  ViewPair, GuaranteedViewPair, Scanner, GuaranteedScanner,
+};
+
+fn view_pair(rng: &mut ChaCha20Rng) -> ViewPair {
+  let spend = Point::from(&Scalar::random(rng).into() * ED25519_BASEPOINT_TABLE);
+  ViewPair::new(spend, Zeroizing::new(Scalar::random(rng))).unwrap()
+}
+
+fn scannable_block(hardfork_version: u8, miner_transaction: Transaction) -> ScannableBlock {
+  ScannableBlock {
+    block: Block::new(
+      BlockHeader {
+        hardfork_version,
+        hardfork_signal: hardfork_version,
+        timestamp: 0,
+        previous: [0; 32],
+        nonce: 0,
+      },
+      miner_transaction,
+      vec![],
+    )
+    .unwrap(),
+    transactions: vec![],
+    output_index_for_first_ringct_output: Some(0),
+    pre_ringct_output_indexes: vec![],
+  }
+}
+
+#[test]
+fn miner_transaction() {
+  let mut rng = ChaCha20Rng::from_seed([0xff; 32]);
+  let pair = view_pair(&mut rng);
+  let address = pair.legacy_address(Network::Mainnet);
+
+  for hardfork_version in [MINER_TRANSACTION_V2_HARDFORK_VERSION, VIEW_TAGS_HARDFORK_VERSION, 16] {
+    let tx = MinerTransactionBuilder::new(100, hardfork_version, 600_000_000_000)
+      .payout(address, 1)
+      .build(&mut rng)
+      .unwrap();
+
+    let Transaction::V2 { prefix, proofs: None } = &tx else {
+      panic!("miner transaction wasn't v2")
+    };
+    assert_eq!(prefix.inputs, vec![Input::Gen(100)]);
+    assert_eq!(prefix.additional_timelock, Timelock::Block(160));
+    assert_eq!(prefix.outputs.len(), 1);
+    assert_eq!(prefix.outputs[0].amount, Some(600_000_000_000));
+    assert_eq!(
+      prefix.outputs[0].view_tag.is_some(),
+      hardfork_version >= VIEW_TAGS_HARDFORK_VERSION
+    );
+
+    // The transaction should be readable and scannable
+    assert_eq!(Transaction::read(&mut tx.serialize().as_slice()).unwrap(), tx);
+    let outputs = Scanner::new(pair.clone())
+      .scan(scannable_block(hardfork_version, tx))
+      .unwrap()
+      .ignore_additional_timelock();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].commitment().amount, 600_000_000_000);
+    assert_eq!(outputs[0].additional_timelock(), Timelock::Block(160));
+  }
+}
+
+#[test]
+fn reward_split() {
+  let mut rng = ChaCha20Rng::from_seed([0xfe; 32]);
+  let pairs = [view_pair(&mut rng), view_pair(&mut rng), view_pair(&mut rng)];
+
+  let mut builder = MinerTransactionBuilder::new(5, 16, 1000);
+  for (pair, share) in pairs.iter().zip([1, 1, 1]) {
+    builder = builder.payout(pair.legacy_address(Network::Mainnet), share);
+  }
+  let tx = builder.build(&mut rng).unwrap();
+  // The remainder is paid to the first payout
+  assert_eq!(
+    tx.prefix().outputs.iter().map(|output| output.amount.unwrap()).collect::<Vec<_>>(),
+    vec![334, 333, 333]
+  );
+
+  // Each recipient should only scan their own output
+  for (i, pair) in pairs.iter().enumerate() {
+    let outputs = Scanner::new(pair.clone())
+      .scan(scannable_block(16, tx.clone()))
+      .unwrap()
+      .ignore_additional_timelock();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].index_in_transaction(), u64::try_from(i).unwrap());
+  }
+
+  let tx = MinerTransactionBuilder::new(5, 16, u64::MAX)
+    .payout(pairs[0].legacy_address(Network::Mainnet), u64::MAX - 1)
+    .payout(pairs[1].legacy_address(Network::Mainnet), 1)
+    .build(&mut rng)
+    .unwrap();
+  assert_eq!(
+    tx.prefix().outputs.iter().map(|output| output.amount.unwrap()).collect::<Vec<_>>(),
+    vec![u64::MAX - 1, 1]
+  );
+}
+
+#[test]
+fn guaranteed_payout() {
+  let mut rng = ChaCha20Rng::from_seed([0xfd; 32]);
+  let pair = view_pair(&mut rng);
+  let pair = GuaranteedViewPair::new(pair.spend(), Zeroizing::new(*pair.view)).unwrap();
+
+  let tx = MinerTransactionBuilder::new(5, 16, 1000)
+    .payout(pair.address(Network::Mainnet, None, None), 1)
+    .build(&mut rng)
+    .unwrap();
+  let outputs = GuaranteedScanner::new(pair)
+    .scan(scannable_block(16, tx))
+    .unwrap()
+    .ignore_additional_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].commitment().amount, 1000);
+}
+
+#[test]
+fn extra() {
+  let mut rng = ChaCha20Rng::from_seed([0xfc; 32]);
+  let address = view_pair(&mut rng).legacy_address(Network::Mainnet);
+
+  let tx = MinerTransactionBuilder::new(5, 16, 1000)
+    .payout(address, 1)
+    .nonce(vec![0; 8])
+    .merge_mining(2, [0xaa; 32])
+    .build(&mut rng)
+    .unwrap();
+  let extra = Extra::read(&mut tx.prefix().extra.as_slice()).unwrap();
+  assert_eq!(extra.0.len(), 3);
+  assert!(matches!(extra.0[0], ExtraField::PublicKey(_)));
+  assert_eq!(extra.0[1], ExtraField::Nonce(vec![0; 8]));
+  assert_eq!(extra.0[2], ExtraField::MergeMining(2, [0xaa; 32]));
+}
+
+#[test]
+fn errors() {
+  let mut rng = ChaCha20Rng::from_seed([0xfb; 32]);
+  let pair = view_pair(&mut rng);
+  let address = pair.legacy_address(Network::Mainnet);
+
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 3, 1000).payout(address, 1).build(&mut rng),
+    Err(MinerTransactionError::UnsupportedHardFork(3))
+  );
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 16, 1000).build(&mut rng),
+    Err(MinerTransactionError::NoPayouts)
+  );
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 16, 1000).payout(address, 0).build(&mut rng),
+    Err(MinerTransactionError::InvalidShares)
+  );
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 16, 1000)
+      .payout(address, u64::MAX)
+      .payout(address, 1)
+      .build(&mut rng),
+    Err(MinerTransactionError::InvalidShares)
+  );
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 16, 1000)
+      .payout(pair.subaddress(Network::Mainnet, SubaddressIndex::new(0, 1).unwrap()), 1)
+      .build(&mut rng),
+    Err(MinerTransactionError::SubaddressPayout)
+  );
+  assert_eq!(
+    MinerTransactionBuilder::new(5, 16, 1000)
+      .payout(address, 1)
+      .nonce(vec![0; 256])
+      .build(&mut rng),
+    Err(MinerTransactionError::NonceTooLarge(256))
+  );
+
+  // Payment IDs are ignored, not rejected
+  let integrated = MoneroAddress::new(
+    Network::Mainnet,
+    AddressType::LegacyIntegrated([1; 8]),
+    address.spend(),
+    address.view(),
+  );
+  assert!(MinerTransactionBuilder::new(5, 16, 1000).payout(integrated, 1).build(&mut rng).is_ok());
+}
+
+// A transaction spending the specified key image, paying the specified fee
+fn candidate(key_image: u8, fee: u64, extra: usize) -> Transaction {
+  Transaction::V1 {
+    prefix: TransactionPrefix {
+      additional_timelock: Timelock::None,
+      inputs: vec![Input::ToKey {
+        amount: Some(1_000_000 + fee),
+        key_offsets: vec![0],
+        key_image: CompressedPoint::from([key_image; 32]),
+      }],
+      outputs: vec![Output { amount: Some(1_000_000), key: CompressedPoint::G, view_tag: None }],
+      extra: vec![0; extra],
+    },
+    signatures: vec![],
+  }
+}
+
+fn build_template(
+  median_weight: usize,
+  transactions: Vec<Transaction>,
+) -> (ViewPair, BlockTemplate) {
+  let mut rng = ChaCha20Rng::from_seed([0xfa; 32]);
+  let pair = view_pair(&mut rng);
+  let template = BlockTemplateBuilder::new(
+    [0xab; 32],
+    1_000,
+    16,
+    1_700_000_000,
+    median_weight,
+    1 << 62,
+    pair.legacy_address(Network::Mainnet),
+  )
+  .reserve_size(8)
+  .transactions(transactions)
+  .build(&mut rng)
+  .unwrap();
+  (pair, template)
+}
+
+#[test]
+fn block_template() {
+  let (pair, template) = build_template(0, vec![]);
+  let block = template.block();
+  assert_eq!(block.header.previous, [0xab; 32]);
+  assert_eq!(block.header.timestamp, 1_700_000_000);
+  assert_eq!(block.number(), 1_000);
+  assert!(block.transactions.is_empty());
+  assert_eq!(template.fee(), 0);
+  assert_eq!(template.reward(), base_reward(1 << 62, 16));
+  assert_eq!(template.weight(), block.miner_transaction().weight());
+  assert_eq!(Block::read(&mut template.blob().as_slice()).unwrap(), *block);
+  assert_eq!(template.hashing_blob(), block.serialize_pow_hash());
+
+  let outputs = Scanner::new(pair)
+    .scan(ScannableBlock {
+      block: block.clone(),
+      transactions: vec![],
+      output_index_for_first_ringct_output: Some(0),
+      pre_ringct_output_indexes: vec![],
+    })
+    .unwrap()
+    .ignore_additional_timelock();
+  assert_eq!(outputs.len(), 1);
+  assert_eq!(outputs[0].commitment().amount, template.reward());
+
+  // The reserved space may be filled in
+  let blob = template.blob();
+  let reserved = template.reserved_offset() .. (template.reserved_offset() + 8);
+  assert_eq!(template.reserve_size(), 8);
+  assert_eq!(blob[reserved.clone()], [0; 8]);
+  let filled = template.block_with_extra_nonce(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+  assert_eq!(filled.serialize()[reserved], [1, 2, 3, 4, 5, 6, 7, 8]);
+  let extra = Extra::read(&mut filled.miner_transaction().prefix().extra.as_slice()).unwrap();
+  assert_eq!(extra.0[1], ExtraField::Nonce(vec![1, 2, 3, 4, 5, 6, 7, 8]));
+  assert!(filled.serialize_pow_hash() != template.hashing_blob());
+  assert!(template.block_with_extra_nonce(&[0; 9]).is_none());
+
+  // As may the nonce
+  let mut hashing_blob = template.hashing_blob();
+  let nonce = template.nonce_offset() .. (template.nonce_offset() + 4);
+  assert_eq!(hashing_blob[nonce.clone()], [0; 4]);
+  let mut block = block.clone();
+  block.header.nonce = 0x01020304;
+  hashing_blob[nonce].copy_from_slice(&0x01020304u32.to_le_bytes());
+  assert_eq!(block.serialize_pow_hash(), hashing_blob);
+}
+
+#[test]
+fn block_template_selection() {
+  let miner_transaction = MinerTransactionBuilder::new(5, 16, 1000)
+    .payout(view_pair(&mut ChaCha20Rng::from_seed([0xf9; 32])).legacy_address(Network::Mainnet), 1)
+    .build(&mut ChaCha20Rng::from_seed([0xf8; 32]))
+    .unwrap();
+  let candidates = vec![
+    candidate(1, 1_000, 0),
+    candidate(2, 5_000, 0),
+    // Conflicts with the prior candidate, yet pays a lower fee per weight
+    candidate(2, 5_000, 100),
+    candidate(3, 3_000, 0),
+    miner_transaction,
+  ];
+  let (_, template) = build_template(0, candidates.clone());
+  assert_eq!(
+    template.block().transactions,
+    vec![candidates[1].hash(), candidates[3].hash(), candidates[0].hash()]
+  );
+  assert_eq!(template.fee(), 9_000);
+  let weight = template.block().miner_transaction().weight() +
+    [&candidates[0], &candidates[1], &candidates[3]].iter().map(|tx| tx.weight()).sum::<usize>();
+  assert_eq!(template.weight(), weight);
+  assert_eq!(template.reward(), base_reward(1 << 62, 16) + 9_000);
+
+  // A transaction whose fee doesn't cover the penalty it incurs isn't included
+  let heavy = candidate(4, 1_000, 350_000);
+  let (_, template) = build_template(300_000, vec![heavy.clone()]);
+  assert!(template.block().transactions.is_empty());
+
+  // Yet one whose fee does is
+  let penalty = base_reward(1 << 62, 16) - block_reward(300_000, 400_000, 1 << 62, 16).unwrap();
+  let heavy = candidate(4, penalty, 350_000);
+  let (_, template) = build_template(300_000, vec![heavy.clone()]);
+  assert_eq!(template.block().transactions, vec![heavy.hash()]);
+  assert_eq!(
+    template.reward(),
+    block_reward(300_000, template.weight(), 1 << 62, 16).unwrap() + penalty
+  );
+
+  // And transactions exceeding the weight limit are never included
+  let (_, template) = build_template(300_000, vec![candidate(5, u64::MAX >> 8, 600_000)]);
+  assert!(template.block().transactions.is_empty());
+}
+
+#[test]
+fn block_template_exact_reward() {
+  // A base reward of 2**42, the smallest amount whose VarInt is seven bytes, so penalties shrink
+  // the miner transaction
+  let already_generated_coins = u64::MAX - (1 << 61);
+  assert_eq!(base_reward(already_generated_coins, 16), 1 << 42);
+
+  let mut rng = ChaCha20Rng::from_seed([0xf7; 32]);
+  let address = view_pair(&mut rng).legacy_address(Network::Mainnet);
+  let mut padded = false;
+  for extra in [350_000, 400_000, 450_000] {
+    for excess in [0, 100_000_000, 1_000_000_000] {
+      // A transaction within the penalty zone, whose fee slightly exceeds the penalty it incurs
+      let weight = candidate(1, 1 << 40, extra).weight();
+      let penalty = (1 << 42) - block_reward(300_000, weight, already_generated_coins, 16).unwrap();
+      let tx = candidate(1, penalty + excess, extra);
+      assert_eq!(tx.weight(), weight);
+
+      let template = BlockTemplateBuilder::new(
+        [0xab; 32],
+        1_000,
+        16,
+        1_700_000_000,
+        300_000,
+        already_generated_coins,
+        address,
+      )
+      .reserve_size(8)
+      .transactions(vec![tx.clone()])
+      .build(&mut rng)
+      .unwrap();
+      assert_eq!(template.block().transactions, vec![tx.hash()]);
+
+      // The reward is exactly the reward for the block's actual weight, plus the fee
+      let miner_transaction = template.block().miner_transaction();
+      let actual_weight = miner_transaction.weight() + weight;
+      assert_eq!(template.weight(), actual_weight);
+      assert_eq!(
+        template.reward(),
+        block_reward(300_000, actual_weight, already_generated_coins, 16).unwrap() + template.fee()
+      );
+      assert_eq!(
+        miner_transaction.prefix().outputs.iter().map(|output| output.amount.unwrap()).sum::<u64>(),
+        template.reward()
+      );
+
+      // The reserved space remains writable when the extra is padded
+      let extra = Extra::read(&mut miner_transaction.prefix().extra.as_slice()).unwrap();
+      padded |= extra.0.iter().any(|field| matches!(field, ExtraField::Padding(_)));
+      let filled = template.block_with_extra_nonce(&[0xff; 8]).unwrap();
+      let extra = Extra::read(&mut filled.miner_transaction().prefix().extra.as_slice()).unwrap();
+      assert_eq!(extra.0[1], ExtraField::Nonce(vec![0xff; 8]));
+    }
+  }
+  assert!(padded);
+}
@@ -1,2 +1,8 @@
 mod extra;
 mod scan;
+mod miner;
+mod aux_pow;
+mod pre_ringct;
+mod output_indexer;
+mod seed;
+mod wallet_keys;
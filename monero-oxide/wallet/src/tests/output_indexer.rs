@@ -0,0 +1,161 @@
+use crate::{
+  ed25519::CompressedPoint,
+  ring_signatures::RingSignature,
+  transaction::{Input, Output, Timelock, TransactionPrefix, Transaction},
+  block::{BlockHeader, Block},
+  OutputIndexerError, OutputIndexer,
+};
+
+fn transaction(v2: bool, input: Input, amounts: &[u64]) -> Transaction {
+  // RingCT transactions don't have their amounts in the clear, unless they're miner transactions
+  let clear_amounts = !v2 || matches!(input, Input::Gen(_));
+  let prefix = TransactionPrefix {
+    additional_timelock: Timelock::None,
+    inputs: vec![input],
+    outputs: amounts
+      .iter()
+      .map(|amount| Output {
+        key: CompressedPoint::G,
+        amount: clear_amounts.then_some(*amount),
+        view_tag: None,
+      })
+      .collect(),
+    extra: vec![],
+  };
+  if v2 {
+    Transaction::V2 { prefix, proofs: None }
+  } else {
+    Transaction::V1 { prefix, signatures: vec![] }
+  }
+}
+
+fn spend() -> Input {
+  Input::ToKey { amount: None, key_offsets: vec![0], key_image: CompressedPoint::G }
+}
+
+fn block(
+  previous: [u8; 32],
+  number: usize,
+  v2_miner: bool,
+  transactions: &[Transaction],
+) -> (Block, Vec<Transaction>) {
+  let block = Block::new(
+    BlockHeader { hardfork_version: 1, hardfork_signal: 1, timestamp: 0, previous, nonce: 0 },
+    transaction(v2_miner, Input::Gen(number), &[10, 20]),
+    transactions.iter().map(Transaction::hash).collect(),
+  )
+  .unwrap();
+  (block, transactions.to_vec())
+}
+
+#[test]
+fn output_indexer() {
+  let mut indexer = OutputIndexer::new();
+
+  // A block with only a version 1 miner transaction
+  let (genesis, transactions) = block([0; 32], 0, false, &[]);
+  let genesis_hash = genesis.hash();
+  let scannable = indexer.index(genesis, transactions).unwrap();
+  assert_eq!(scannable.output_index_for_first_ringct_output, None);
+  assert_eq!(scannable.pre_ringct_output_indexes, vec![vec![0, 0]]);
+
+  // A block with version 1 transactions, whose outputs are indexed by their amount
+  let (block_1, transactions) = block(
+    genesis_hash,
+    1,
+    false,
+    &[transaction(false, spend(), &[10, 10, 30]), transaction(false, spend(), &[20])],
+  );
+  let block_1_hash = block_1.hash();
+  let scannable = indexer.index(block_1, transactions).unwrap();
+  assert_eq!(scannable.output_index_for_first_ringct_output, None);
+  assert_eq!(scannable.pre_ringct_output_indexes, vec![vec![1, 1], vec![2, 3, 0], vec![2]]);
+  assert_eq!(indexer.outputs(10), 4);
+  assert_eq!(indexer.outputs(20), 3);
+  assert_eq!(indexer.outputs(30), 1);
+  assert_eq!(indexer.outputs(0), 0);
+
+  // Resuming from the written state should produce the same results
+  let mut resumed = OutputIndexer::read(&mut indexer.serialize().as_slice()).unwrap();
+  assert_eq!(resumed, indexer);
+
+  // A block with version 2 transactions, whose outputs (including the miner transaction's) are
+  // all RingCT outputs, alongside a version 1 transaction
+  let (block_2, transactions) = block(
+    block_1_hash,
+    2,
+    true,
+    &[
+      transaction(true, spend(), &[]),
+      transaction(false, spend(), &[10]),
+      transaction(true, spend(), &[0, 0, 0]),
+    ],
+  );
+
+  // The block must follow the last indexed block
+  let (unrelated, unrelated_transactions) = block([0xff; 32], 2, true, &[]);
+  assert_eq!(
+    indexer.index(unrelated, unrelated_transactions),
+    Err(OutputIndexerError::NonConsecutiveBlock)
+  );
+  let (skipped, skipped_transactions) = block(block_1_hash, 3, true, &[]);
+  assert_eq!(
+    indexer.index(skipped, skipped_transactions),
+    Err(OutputIndexerError::NonConsecutiveBlock)
+  );
+  assert_eq!(
+    indexer.index(block_2.clone(), transactions[.. 2].to_vec()),
+    Err(OutputIndexerError::TransactionsMismatch)
+  );
+  // The transactions must be in the order the block lists them
+  let mut reordered = transactions.clone();
+  reordered.swap(0, 2);
+  assert_eq!(
+    indexer.index(block_2.clone(), reordered),
+    Err(OutputIndexerError::TransactionsMismatch)
+  );
+  // And must be the transactions the block lists, including their (prunable) signatures
+  let mut modified = transactions.clone();
+  if let Transaction::V1 { signatures, .. } = &mut modified[1] {
+    signatures.push(RingSignature::read(1, &mut [0; 64].as_slice()).unwrap());
+  } else {
+    panic!("transaction wasn't version 1");
+  }
+  assert_eq!(
+    indexer.index(block_2.clone(), modified),
+    Err(OutputIndexerError::TransactionsMismatch)
+  );
+  assert_eq!(resumed, indexer);
+
+  let scannable = indexer.index(block_2.clone(), transactions.clone()).unwrap();
+  assert_eq!(scannable.output_index_for_first_ringct_output, Some(0));
+  assert_eq!(scannable.pre_ringct_output_indexes, vec![vec![4]]);
+  assert_eq!(indexer.outputs(0), 5);
+  assert_eq!(indexer.next_block_number(), 3);
+  assert_eq!(resumed.index(block_2, transactions).unwrap(), scannable);
+  assert_eq!(resumed, indexer);
+
+  let (block_3, transactions) = block(scannable.block.hash(), 3, true, &[]);
+  let scannable = indexer.index(block_3, transactions).unwrap();
+  assert_eq!(scannable.output_index_for_first_ringct_output, Some(5));
+  assert!(scannable.pre_ringct_output_indexes.is_empty());
+  assert_eq!(indexer.outputs(0), 7);
+}
+
+#[test]
+fn output_indexer_serialization() {
+  let indexer = OutputIndexer::new();
+  assert_eq!(OutputIndexer::read(&mut indexer.serialize().as_slice()).unwrap(), indexer);
+
+  // A hash for the last block must be present if, and only if, a block has been indexed
+  let mut serialized = indexer.serialize();
+  serialized[0] = 1;
+  assert!(OutputIndexer::read(&mut serialized.as_slice()).is_err());
+
+  // Pools may not be duplicated
+  let mut serialized = indexer.serialize();
+  serialized.truncate(serialized.len() - 8);
+  serialized.extend(2u64.to_le_bytes());
+  serialized.extend([10u64, 1, 10, 2].iter().flat_map(|value| value.to_le_bytes()));
+  assert!(OutputIndexer::read(&mut serialized.as_slice()).is_err());
+}
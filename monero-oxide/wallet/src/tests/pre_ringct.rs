@@ -0,0 +1,357 @@
+use core::ops::{Deref, RangeBounds};
+
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::*,
+  ringct::{clsag::Decoys, RctType},
+  transaction::{Input, Output, Timelock, TransactionPrefix, Pruned, Transaction},
+  block::{BlockHeader, Block},
+  hardfork::HardFork,
+  address::Network,
+  rpc::{RpcError, FeeRate, OutputHistogramEntry, OutputInformation, ScannableBlock, DecoyRpc},
+  extra::Extra,
+  send::{Change, SignableTransaction},
+  ViewPair, Scanner, ScanError, SharedKeyDerivations, WalletOutput, OutputWithDecoys,
+};
+
+const AMOUNT: u64 = 1_000_000_000_000;
+
+fn keys(rng: &mut ChaCha20Rng) -> (Zeroizing<Scalar>, ViewPair) {
+  let spend = Zeroizing::new(Scalar::random(rng));
+  let spend_pub = Point::from(&(*spend).into() * ED25519_BASEPOINT_TABLE);
+  (spend, ViewPair::new(spend_pub, Zeroizing::new(Scalar::random(rng))).unwrap())
+}
+
+// A version 1 transaction paying the specified amounts to the view pair
+fn v1_transaction(
+  rng: &mut ChaCha20Rng,
+  pair: &ViewPair,
+  input: Input,
+  amounts: &[u64],
+) -> Transaction {
+  let tx_key = Zeroizing::new(Scalar::random(rng).into());
+  let tx_key_pub = Point::from(tx_key.deref() * ED25519_BASEPOINT_TABLE);
+
+  let mut outputs = vec![];
+  for (o, amount) in amounts.iter().enumerate() {
+    let ecdh = Zeroizing::new(Point::from(tx_key.deref() * pair.view().into()));
+    let derivations = SharedKeyDerivations::output_derivations(None, ecdh, o);
+    let key = (&derivations.shared_key.into() * ED25519_BASEPOINT_TABLE) + pair.spend().into();
+    outputs.push(Output {
+      key: Point::from(key).compress(),
+      amount: Some(*amount),
+      view_tag: None,
+    });
+  }
+
+  Transaction::V1 {
+    prefix: TransactionPrefix {
+      additional_timelock: Timelock::None,
+      inputs: vec![input],
+      outputs,
+      extra: Extra::new(tx_key_pub.compress(), vec![]).serialize(),
+    },
+    signatures: vec![],
+  }
+}
+
+fn scannable_block(rng: &mut ChaCha20Rng, pair: &ViewPair) -> ScannableBlock {
+  let miner_transaction = v1_transaction(rng, pair, Input::Gen(5), &[AMOUNT, 2 * AMOUNT]);
+  let transaction = v1_transaction(
+    rng,
+    pair,
+    Input::ToKey { amount: Some(AMOUNT), key_offsets: vec![0], key_image: CompressedPoint::G },
+    &[AMOUNT / 2],
+  );
+  ScannableBlock {
+    block: Block::new(
+      BlockHeader {
+        hardfork_version: 1,
+        hardfork_signal: 1,
+        timestamp: 0,
+        previous: [0; 32],
+        nonce: 0,
+      },
+      miner_transaction,
+      vec![transaction.hash()],
+    )
+    .unwrap(),
+    transactions: vec![Transaction::<Pruned>::from(transaction)],
+    output_index_for_first_ringct_output: None,
+    pre_ringct_output_indexes: vec![vec![7, 8], vec![3]],
+  }
+}
+
+#[test]
+fn scan_pre_ringct() {
+  let mut rng = ChaCha20Rng::from_seed([0xf0; 32]);
+  let (_, pair) = keys(&mut rng);
+  let block = scannable_block(&mut rng, &pair);
+
+  let outputs = Scanner::new(pair.clone()).scan(block.clone()).unwrap().not_additionally_locked();
+  assert_eq!(outputs.len(), 3);
+  for (output, (index, amount)) in
+    outputs.iter().zip([(7, AMOUNT), (8, 2 * AMOUNT), (3, AMOUNT / 2)])
+  {
+    assert!(output.pre_ringct());
+    assert_eq!(output.index_on_blockchain(), index);
+    assert_eq!(output.commitment().amount, amount);
+    // Pre-RingCT outputs are committed to with a mask of 1
+    let mut commitment = Commitment::zero();
+    commitment.amount = amount;
+    assert!(bool::from(output.commitment().ct_eq(&commitment)));
+    assert_eq!(&WalletOutput::read(&mut output.serialize().as_slice()).unwrap(), output);
+
+    // Pre-RingCT outputs are marked within their serialization. Without the marker, this is a
+    // serialization from before pre-RingCT outputs were supported, which is of a RingCT output
+    let mut legacy = output.serialize();
+    let marker = legacy.windows(32).position(|window| window == [0xff; 32]).unwrap();
+    legacy.drain(marker .. (marker + 32));
+    let legacy = WalletOutput::read(&mut legacy.as_slice()).unwrap();
+    assert!(!legacy.pre_ringct());
+    assert_eq!(legacy.key(), output.key());
+  }
+
+  // The indexes for each pre-RingCT transaction must be present and complete
+  let mut missing = block.clone();
+  missing.pre_ringct_output_indexes.pop();
+  assert!(matches!(
+    Scanner::new(pair.clone()).scan(missing),
+    Err(ScanError::InvalidScannableBlock(_))
+  ));
+  let mut incomplete = block.clone();
+  incomplete.pre_ringct_output_indexes[0].pop();
+  assert!(matches!(
+    Scanner::new(pair.clone()).scan(incomplete),
+    Err(ScanError::InvalidScannableBlock(_))
+  ));
+  let mut extra = block;
+  extra.pre_ringct_output_indexes.push(vec![]);
+  assert!(matches!(Scanner::new(pair).scan(extra), Err(ScanError::InvalidScannableBlock(_))));
+}
+
+// Form a ring of outputs from the same pool as the output, whose commitments all have a mask of 1
+fn ring(rng: &mut ChaCha20Rng, output: &WalletOutput) -> (Vec<[Point; 2]>, Decoys) {
+  let signer_index = 5;
+  let mut ring = vec![];
+  for i in 0 .. 16 {
+    let key = if i == signer_index {
+      output.key()
+    } else {
+      Point::from(&Scalar::random(rng).into() * ED25519_BASEPOINT_TABLE)
+    };
+    ring.push([key, output.commitment().commit()]);
+  }
+  let mut offsets = vec![rng.next_u64() % 1000];
+  offsets.extend((1 .. 16).map(|_| 1 + (rng.next_u64() % 1000)));
+  let decoys = Decoys::new(offsets, u8::try_from(signer_index).unwrap(), ring.clone()).unwrap();
+  (ring, decoys)
+}
+
+#[test]
+fn spend_pre_ringct() {
+  let mut rng = ChaCha20Rng::from_seed([0xef; 32]);
+  let (spend, pair) = keys(&mut rng);
+  let output = Scanner::new(pair.clone())
+    .scan(scannable_block(&mut rng, &pair))
+    .unwrap()
+    .not_additionally_locked()
+    .swap_remove(1);
+
+  let (ring, decoys) = ring(&mut rng, &output);
+  let input = {
+    let mut serialized = vec![];
+    output.data.write(&mut serialized).unwrap();
+    decoys.write(&mut serialized).unwrap();
+    OutputWithDecoys::read(&mut serialized.as_slice()).unwrap()
+  };
+  assert!(input.pre_ringct());
+
+  let tx = SignableTransaction::new(
+    HardFork::LATEST,
+    RctType::ClsagBulletproofPlus,
+    Zeroizing::new([0xee; 32]),
+    vec![input],
+    vec![(pair.legacy_address(Network::Mainnet), AMOUNT)],
+    Change::new(pair.clone(), None),
+    vec![],
+    FeeRate::new(1, 1).unwrap(),
+  )
+  .unwrap()
+  .sign(&mut rng, &spend)
+  .unwrap();
+
+  // The input specifies the pool it spends from
+  let Input::ToKey { amount, .. } = &tx.prefix().inputs[0] else { panic!("input wasn't ToKey") };
+  assert_eq!(*amount, Some(2 * AMOUNT));
+
+  // And the transaction verifies against the ring, as serialized and read back
+  let tx = Transaction::read(&mut tx.serialize().as_slice()).unwrap();
+  let ring = ring.iter().map(|member| member.map(Point::compress)).collect::<Vec<_>>();
+  tx.verify(&mut rng, &[ring]).unwrap();
+}
+
+#[test]
+fn read_legacy_signable_transaction() {
+  let mut rng = ChaCha20Rng::from_seed([0xee; 32]);
+  let (_, pair) = keys(&mut rng);
+  let outputs = Scanner::new(pair.clone())
+    .scan(scannable_block(&mut rng, &pair))
+    .unwrap()
+    .not_additionally_locked();
+
+  let signable = |inputs| {
+    SignableTransaction::new(
+      HardFork::LATEST,
+      RctType::ClsagBulletproofPlus,
+      Zeroizing::new([0xee; 32]),
+      inputs,
+      vec![(pair.legacy_address(Network::Mainnet), AMOUNT)],
+      Change::new(pair.clone(), None),
+      vec![],
+      FeeRate::new(1, 1).unwrap(),
+    )
+    .unwrap()
+  };
+
+  // Inputs as serialized before pre-RingCT outputs were supported, with the RingCT outputs'
+  // serialization unchanged
+  let mut inputs = vec![];
+  for mut output in outputs.clone() {
+    output.data.pre_ringct = false;
+    let (_, decoys) = ring(&mut rng, &output);
+    let mut legacy = vec![];
+    output.key().compress().write(&mut legacy).unwrap();
+    output.data.key_offset.write(&mut legacy).unwrap();
+    output.data.commitment.write(&mut legacy).unwrap();
+    decoys.write(&mut legacy).unwrap();
+    let input = OutputWithDecoys::read(&mut legacy.as_slice()).unwrap();
+    assert!(!input.pre_ringct());
+    assert_eq!(input.serialize(), legacy);
+    inputs.push(input);
+  }
+
+  // A transaction with multiple such inputs is read without any input consuming the next's bytes
+  let tx = signable(inputs.clone());
+  assert_eq!(SignableTransaction::read(&mut tx.serialize().as_slice()).unwrap(), tx);
+
+  // As is a transaction mixing pre-RingCT and RingCT inputs, with each input's kind preserved
+  inputs[1] = {
+    let (_, decoys) = ring(&mut rng, &outputs[1]);
+    let mut serialized = vec![];
+    outputs[1].data.write(&mut serialized).unwrap();
+    decoys.write(&mut serialized).unwrap();
+    OutputWithDecoys::read(&mut serialized.as_slice()).unwrap()
+  };
+  assert!(inputs[1].pre_ringct());
+  let tx = signable(inputs);
+  assert_eq!(SignableTransaction::read(&mut tx.serialize().as_slice()).unwrap(), tx);
+}
+
+// A DecoyRpc which, as a restricted node does, refuses the output distributions of pre-RingCT pools
+struct RestrictedRpc {
+  output: WalletOutput,
+  pool: u64,
+}
+
+impl DecoyRpc for RestrictedRpc {
+  async fn get_output_distribution_end_height(&self) -> Result<usize, RpcError> {
+    Ok(1000)
+  }
+
+  async fn get_output_histogram(
+    &self,
+    amounts: &[u64],
+  ) -> Result<Vec<OutputHistogramEntry>, RpcError> {
+    Ok(
+      amounts
+        .iter()
+        .map(|amount| OutputHistogramEntry {
+          amount: *amount,
+          total_instances: self.pool,
+          unlocked_instances: self.pool,
+        })
+        .collect(),
+    )
+  }
+
+  async fn get_output_distributions(
+    &self,
+    _amounts: &[u64],
+    _range: impl Send + RangeBounds<usize>,
+  ) -> Result<Vec<Vec<u64>>, RpcError> {
+    Err(RpcError::InvalidNode(
+      "Restricted RPC can only get output distribution for rct outputs".to_string(),
+    ))
+  }
+
+  async fn get_outs_from_pools(
+    &self,
+    _outputs: &[(u64, u64)],
+  ) -> Result<Vec<OutputInformation>, RpcError> {
+    Err(RpcError::InternalError(
+      "decoys are selected with get_unlocked_outputs_for_amount".to_string(),
+    ))
+  }
+
+  async fn get_unlocked_outputs_for_amount(
+    &self,
+    amount: u64,
+    indexes: &[u64],
+    _height: usize,
+    _fingerprintable_deterministic: bool,
+  ) -> Result<Vec<Option<[Point; 2]>>, RpcError> {
+    assert_eq!(Some(amount), self.output.data.pre_ringct_amount());
+    let commitment = self.output.commitment().commit();
+    Ok(
+      indexes
+        .iter()
+        .map(|index| {
+          assert!(*index < self.pool);
+          if *index == self.output.relative_id.index_on_blockchain {
+            return Some([self.output.key(), commitment]);
+          }
+          let key = Scalar::hash(index.to_le_bytes());
+          Some([Point::from(&key.into() * ED25519_BASEPOINT_TABLE), commitment])
+        })
+        .collect(),
+    )
+  }
+}
+
+#[tokio::test]
+async fn select_pre_ringct_decoys() {
+  let mut rng = ChaCha20Rng::from_seed([0xef; 32]);
+  let (_, pair) = keys(&mut rng);
+  let output = Scanner::new(pair.clone())
+    .scan(scannable_block(&mut rng, &pair))
+    .unwrap()
+    .not_additionally_locked()
+    .swap_remove(1);
+
+  // Decoys are selected from the pool, as sized by its histogram, without its distribution
+  let rpc = RestrictedRpc { output: output.clone(), pool: 100 };
+  let input = OutputWithDecoys::new(&mut rng, &rpc, 16, 500, output.clone()).await.unwrap();
+  assert!(input.pre_ringct());
+  let positions = input.decoys().positions();
+  assert_eq!(positions.len(), 16);
+  assert!(positions.iter().all(|position| *position < rpc.pool));
+  assert_eq!(
+    positions[usize::from(input.decoys().signer_index())],
+    output.relative_id.index_on_blockchain
+  );
+
+  // A pool without enough unlocked outputs to form a ring is rejected
+  let rpc = RestrictedRpc { output: output.clone(), pool: 15 };
+  assert!(OutputWithDecoys::new(&mut rng, &rpc, 16, 500, output).await.is_err());
+}
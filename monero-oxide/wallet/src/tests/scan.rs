@@ -1,5 +1,9 @@
+use core::ops::Deref;
+
 use zeroize::Zeroizing;
 
+use rand_core::OsRng;
+
 #[cfg(feature = "compile-time-generators")]
 use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
 #[cfg(not(feature = "compile-time-generators"))]
@@ -8,12 +12,13 @@ use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TA
 use crate::{
   ed25519::*,
   ringct::EncryptedAmount,
-  transaction::{Timelock, Pruned, Transaction},
-  block::Block,
+  transaction::{Timelock, Input, Output, TransactionPrefix, Pruned, Transaction},
+  block::{BlockHeader, Block},
+  address::{Network, SubaddressIndex, MoneroAddress},
   rpc::ScannableBlock,
-  PaymentId,
+  PaymentId, Extra, SharedKeyDerivations,
   output::{AbsoluteId, RelativeId, OutputData, Metadata},
-  WalletOutput, ViewPair, Scanner,
+  WalletOutput, ViewPair, GuaranteedViewPair, Scanner, GuaranteedScanner,
 };
 
 const SPEND_KEY: &str = "ccf0ea10e1ea64354f42fa710c2b318e581969cf49046d809d1f0aadb3fc7a02";
@@ -61,6 +66,7 @@ fn wallet_output0() -> WalletOutput {
         )
         .unwrap(),
       },
+      pre_ringct: false,
     },
     metadata: Metadata {
       additional_timelock: Timelock::None,
@@ -105,6 +111,7 @@ fn wallet_output1() -> WalletOutput {
         )
         .unwrap(),
       },
+      pre_ringct: false,
     },
     metadata: Metadata {
       additional_timelock: Timelock::None,
@@ -155,6 +162,8 @@ fn scan_long_encrypted_amount() {
     block,
     transactions: txs,
     output_index_for_first_ringct_output: Some(OUTPUT_INDEX_FOR_FIRST_RINGCT_OUTPUT),
+    // The miner transaction's outputs, which aren't ours, so their indexes are irrelevant
+    pre_ringct_output_indexes: vec![vec![0; 5]],
   };
 
   // Scan the block
@@ -164,3 +173,175 @@ fn scan_long_encrypted_amount() {
   assert_eq!(outputs[0], wallet_output0());
   assert_eq!(outputs[1], wallet_output1());
 }
+
+// A block whose miner transaction pays the specified address
+//
+// This is built manually as `MinerTransactionBuilder` doesn't support paying to subaddresses.
+fn block(address: MoneroAddress) -> ScannableBlock {
+  let inputs = vec![Input::Gen(100)];
+  let tx_key = Zeroizing::new(Scalar::random(&mut OsRng).into());
+  let tx_key_pub = if address.is_subaddress() {
+    Point::from(tx_key.deref() * address.spend().into())
+  } else {
+    Point::from(tx_key.deref() * ED25519_BASEPOINT_TABLE)
+  };
+  let derivations = SharedKeyDerivations::output_derivations(
+    address.is_guaranteed().then(|| SharedKeyDerivations::uniqueness(&inputs)),
+    Zeroizing::new(Point::from(tx_key.deref() * address.view().into())),
+    0,
+  );
+  let key = (&derivations.shared_key.into() * ED25519_BASEPOINT_TABLE) + address.spend().into();
+  let miner_transaction = Transaction::V2 {
+    prefix: TransactionPrefix {
+      additional_timelock: Timelock::None,
+      inputs,
+      outputs: vec![Output {
+        key: Point::from(key).compress(),
+        amount: Some(1),
+        view_tag: Some(derivations.view_tag),
+      }],
+      extra: Extra::new(tx_key_pub.compress(), vec![]).serialize(),
+    },
+    proofs: None,
+  };
+
+  ScannableBlock {
+    block: Block::new(
+      BlockHeader {
+        hardfork_version: 16,
+        hardfork_signal: 16,
+        timestamp: 0,
+        previous: [0; 32],
+        nonce: 0,
+      },
+      miner_transaction,
+      vec![],
+    )
+    .unwrap(),
+    transactions: vec![],
+    output_index_for_first_ringct_output: Some(0),
+    pre_ringct_output_indexes: vec![],
+  }
+}
+
+#[test]
+fn subaddress_lookahead() {
+  let spend = Point::from(&Scalar::random(&mut OsRng).into() * ED25519_BASEPOINT_TABLE);
+  let pair = ViewPair::new(spend, Zeroizing::new(Scalar::random(&mut OsRng))).unwrap();
+
+  let indexes = |indexes: &[(u32, u32)]| {
+    indexes
+      .iter()
+      .map(|(account, address)| SubaddressIndex::new(*account, *address).unwrap())
+      .collect::<Vec<_>>()
+  };
+  let subaddress = |account, address| {
+    pair.subaddress(Network::Mainnet, SubaddressIndex::new(account, address).unwrap())
+  };
+
+  let mut scanner = Scanner::new(pair.clone());
+  scanner.set_subaddress_lookahead(2, 3);
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&[(0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]));
+  assert!(scanner.take_new_subaddresses().is_empty());
+
+  // Subaddresses beyond the lookahead aren't scanned
+  let scan = |scanner: &mut Scanner, address| {
+    scanner.scan(block(address)).unwrap().ignore_additional_timelock().len()
+  };
+  assert_eq!(scan(&mut scanner, subaddress(1, 3)), 0);
+  assert_eq!(scan(&mut scanner, subaddress(2, 0)), 0);
+  assert!(scanner.take_new_subaddresses().is_empty());
+
+  // Receiving to a subaddress expands the lookahead past it
+  assert_eq!(scan(&mut scanner, subaddress(1, 2)), 1);
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&[(1, 3), (1, 4), (2, 0), (2, 1), (2, 2)]));
+  assert_eq!(scan(&mut scanner, subaddress(1, 4)), 1);
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&[(1, 5), (1, 6)]));
+  assert_eq!(scan(&mut scanner, subaddress(2, 2)), 1);
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&[(2, 3), (2, 4), (3, 0), (3, 1), (3, 2)]));
+
+  // Receiving to the primary address, or within the lookahead, doesn't register anything new
+  assert_eq!(scan(&mut scanner, pair.legacy_address(Network::Mainnet)), 1);
+  assert_eq!(scan(&mut scanner, subaddress(1, 1)), 1);
+  assert!(scanner.take_new_subaddresses().is_empty());
+
+  // Explicitly registered subaddresses aren't reported by the lookahead
+  let mut scanner = Scanner::new(pair.clone());
+  scanner.register_subaddress(SubaddressIndex::new(0, 1).unwrap());
+  scanner.set_subaddress_lookahead(1, 3);
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&[(0, 2)]));
+
+  // Receiving to an explicitly registered subaddress at a high account only expands the accounts
+  // from its own, not every account before it
+  let mut scanner = Scanner::new(pair.clone());
+  scanner.set_subaddress_lookahead(2, 3);
+  scanner.take_new_subaddresses();
+  let account = 1_000_000_000;
+  scanner.register_subaddress(SubaddressIndex::new(account, 7).unwrap());
+  assert_eq!(scan(&mut scanner, subaddress(account, 7)), 1);
+  let expected = (0 .. 10)
+    .filter(|address| *address != 7)
+    .map(|address| (account, address))
+    .chain((0 .. 3).map(|address| (account + 1, address)))
+    .collect::<Vec<_>>();
+  assert_eq!(scanner.take_new_subaddresses(), indexes(&expected));
+  // Accounts already expanded are only extended past their prior extent
+  assert_eq!(scan(&mut scanner, subaddress(account + 1, 2)), 1);
+  assert_eq!(
+    scanner.take_new_subaddresses(),
+    indexes(&[
+      (account + 1, 3),
+      (account + 1, 4),
+      (account + 2, 0),
+      (account + 2, 1),
+      (account + 2, 2)
+    ])
+  );
+
+  let pair = GuaranteedViewPair::new(pair.spend(), pair.view.clone()).unwrap();
+  let mut scanner = GuaranteedScanner::new(pair.clone());
+  scanner.set_subaddress_lookahead(1, 1);
+  assert!(scanner.take_new_subaddresses().is_empty());
+  let address = pair.address(Network::Mainnet, SubaddressIndex::new(0, 1), None);
+  assert_eq!(scanner.scan(block(address)).unwrap().ignore_additional_timelock().len(), 0);
+  let address = pair.address(Network::Mainnet, None, None);
+  assert_eq!(scanner.scan(block(address)).unwrap().ignore_additional_timelock().len(), 1);
+  assert!(scanner.take_new_subaddresses().is_empty());
+}
+
+#[test]
+fn bulk_subaddresses() {
+  let spend = Point::from(&Scalar::random(&mut OsRng).into() * ED25519_BASEPOINT_TABLE);
+  let pair = ViewPair::new(spend, Zeroizing::new(Scalar::random(&mut OsRng))).unwrap();
+
+  let indexes = (0 .. 4)
+    .flat_map(|account| (0 .. 8).filter_map(move |address| SubaddressIndex::new(account, address)))
+    .collect::<Vec<_>>();
+  let addresses = pair.subaddresses(Network::Mainnet, &indexes);
+  assert_eq!(addresses.len(), indexes.len());
+  for (index, address) in indexes.iter().zip(&addresses) {
+    assert_eq!(*address, pair.subaddress(Network::Mainnet, *index));
+  }
+  assert!(pair.subaddresses(Network::Mainnet, &[]).is_empty());
+
+  // Registering the subaddresses returns the same addresses
+  let mut scanner = Scanner::new(pair.clone());
+  assert_eq!(scanner.register_subaddresses(Network::Mainnet, &indexes), addresses);
+  for (index, address) in indexes.iter().zip(addresses) {
+    let outputs = scanner.scan(block(address)).unwrap().ignore_additional_timelock();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].subaddress(), Some(*index));
+  }
+  let unregistered = pair.subaddress(Network::Mainnet, SubaddressIndex::new(4, 0).unwrap());
+  assert!(scanner.scan(block(unregistered)).unwrap().ignore_additional_timelock().is_empty());
+
+  let pair = GuaranteedViewPair::new(pair.spend(), pair.view.clone()).unwrap();
+  let addresses = pair.subaddresses(Network::Mainnet, &indexes);
+  for (index, address) in indexes.iter().zip(&addresses) {
+    assert_eq!(*address, pair.address(Network::Mainnet, Some(*index), None));
+  }
+  let mut scanner = GuaranteedScanner::new(pair.clone());
+  assert_eq!(scanner.register_subaddresses(Network::Mainnet, &indexes), addresses);
+  let address = addresses[addresses.len() - 1];
+  assert_eq!(scanner.scan(block(address)).unwrap().ignore_additional_timelock().len(), 1);
+}
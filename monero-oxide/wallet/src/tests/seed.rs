@@ -0,0 +1,307 @@
+use rand_core::OsRng;
+
+use crate::{
+  ed25519::Scalar,
+  address::Network,
+  seed::{
+    classic::{CLASSIC_SEED_LENGTH, LEGACY_SEED_LENGTH, Language, SeedError, ClassicSeed},
+    polyseed::{
+      self, POLYSEED_LENGTH, POLYSEED_EPOCH, POLYSEED_TIME_STEP, PolyseedError, Polyseed,
+    },
+  },
+};
+
+const SEED: &str = "washing thirsty occur lectures tuesday fainted toxic adapt abnormal memoir \
+  nylon mostly building shrugged online ember northern ruby woes dauntless boil family illness \
+  inroads northern";
+
+fn scalar(hex: &str) -> Scalar {
+  Scalar::read(&mut hex::decode(hex).unwrap().as_slice()).unwrap()
+}
+
+#[test]
+fn classic_vectors() {
+  let seed = ClassicSeed::from_string(Language::English, SEED).unwrap();
+  assert!(!seed.is_legacy());
+  assert_eq!(
+    *seed.spend_key(),
+    scalar("c0af65c0dd837e666b9d0dfed62745f4df35aed7ea619b2798a709f0fe545403")
+  );
+  assert_eq!(
+    *seed.view_key(),
+    scalar("513ba91c538a5a9069e0094de90e927c0cd147fa10428ce3ac1afd49f63e3b01")
+  );
+  assert_eq!(
+    seed.to_string().split_whitespace().collect::<Vec<_>>(),
+    SEED.split_whitespace().collect::<Vec<_>>()
+  );
+  assert_eq!(ClassicSeed::from_spend_key(Language::English, &seed.spend_key()), seed);
+
+  // The wallets used by monerod's functional tests
+  for (words, address) in [
+    (
+      "velvet lymph giddy number token physics poetry unquoted nibs useful sabotage limits benches \
+        lifestyle eden nitrogen anvil fewest avoid batch vials washing fences goat unquoted",
+      concat!(
+        "42ey1afDFnn4886T7196doS9GPMzexD9gXpsZJDwVjeRVdFCSoHnv7KPbBeGp",
+        "zJBzHRCAs9UxqeoyFQMYbqSWYTfJJQAWDm",
+      ),
+    ),
+    (
+      "peeled mixture ionic radar utopia puddle buying illness nuns gadget river spout cavernous \
+        bounced paradise drunk looking cottage jump tequila melting went winter adjust spout",
+      concat!(
+        "44Kbx4sJ7JDRDV5aAhLJzQCjDz2ViLRduE3ijDZu3osWKBjMGkV1XPk4pfDUM",
+        "qt1Aiezvephdqm6YD19GKFD9ZcXVUTp6BW",
+      ),
+    ),
+  ] {
+    let seed = ClassicSeed::from_string(Language::English, words).unwrap();
+    assert_eq!(seed.view_pair().legacy_address(Network::Mainnet).to_string(), address);
+  }
+}
+
+#[test]
+fn classic_legacy() {
+  // A MyMonero seed, from mymonero-core-js's tests
+  let words = "foxes selfish humid nexus juvenile dodge pepper ember biscuit elapse jeans vibrate \
+    jeans";
+  let seed = ClassicSeed::from_string(Language::English, words).unwrap();
+  assert!(seed.is_legacy());
+  assert_eq!(seed.to_string().split_whitespace().count(), LEGACY_SEED_LENGTH);
+  assert_eq!(
+    seed.to_string().split_whitespace().collect::<Vec<_>>(),
+    words.split_whitespace().collect::<Vec<_>>()
+  );
+  assert_eq!(
+    *seed.spend_key(),
+    scalar("4e6d43cd03812b803c6f3206689f5fcc910005fc7e91d50d79b0776dbefcd803")
+  );
+  // The private view key is derived from the hash of the seed, not the reduced private spend key
+  assert_eq!(
+    *seed.view_key(),
+    scalar("7bea1907940afdd480eff7c4bcadb478a0fbb626df9e3ed74ae801e18f53e104")
+  );
+  assert!(*seed.view_key() != Scalar::hash(<[u8; 32]>::from(*seed.spend_key())));
+  assert_eq!(
+    seed.view_pair().legacy_address(Network::Mainnet).to_string(),
+    concat!(
+      "43zxvpcj5Xv9SEkNXbMCG7LPQStHMpFCQCmkmR4u5nzjWwq5Xkv5VmGgYEsHXg4ja2FGRD5wMWbBVMijDTqmmVqm9",
+      "3wHGkg",
+    )
+  );
+  assert_eq!(ClassicSeed::from_string(Language::English, &seed.to_string()).unwrap(), seed);
+}
+
+#[test]
+fn classic_round_trip() {
+  for _ in 0 .. 10 {
+    let seed = ClassicSeed::new(&mut OsRng, Language::English);
+    let words = seed.to_string();
+    assert_eq!(words.split_whitespace().count(), CLASSIC_SEED_LENGTH);
+    let decoded = ClassicSeed::from_string(Language::English, &words).unwrap();
+    assert_eq!(decoded, seed);
+    assert_eq!(
+      decoded.view_pair().legacy_address(Network::Mainnet),
+      seed.view_pair().legacy_address(Network::Mainnet)
+    );
+  }
+
+  // Non-reduced spend keys are reduced, as wallet2 does
+  let unreduced = ["foamy solved soggy"; 8].join(" ");
+  let seed = ClassicSeed::from_string(Language::English, &format!("{unreduced} soggy")).unwrap();
+  assert_eq!(
+    *seed.spend_key(),
+    Scalar::from(curve25519_dalek::Scalar::from_bytes_mod_order([0xff; 32]))
+  );
+}
+
+#[test]
+fn classic_errors() {
+  // Words may be abbreviated to their unique prefix
+  let abbreviated = SEED.split_whitespace().map(|word| &word[.. 3]).collect::<Vec<_>>().join(" ");
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &abbreviated).unwrap(),
+    ClassicSeed::from_string(Language::English, SEED).unwrap()
+  );
+
+  let mut words = SEED.split_whitespace().collect::<Vec<_>>();
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &words[.. 24].join(" ")),
+    Err(SeedError::InvalidLength(24))
+  );
+
+  words[3] = "xylophone";
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &words.join(" ")),
+    Err(SeedError::UnknownWord(3))
+  );
+  words[3] = "ab";
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &words.join(" ")),
+    Err(SeedError::UnknownWord(3))
+  );
+
+  words[3] = "lectures";
+  words[24] = "washing";
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &words.join(" ")),
+    Err(SeedError::InvalidChecksum)
+  );
+
+  // "zoom zones zombie" encodes a value exceeding u32::MAX
+  let overflowing = ["zoom zones zombie"; 8].join(" ");
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &format!("{overflowing} zombie")),
+    Err(SeedError::InvalidSeed)
+  );
+}
+
+#[test]
+fn classic_language_detection() {
+  assert_eq!(Language::detect(SEED), Some(Language::English));
+  // Abbreviated words are detected by their unique prefix
+  let abbreviated = SEED.split_whitespace().map(|word| &word[.. 3]).collect::<Vec<_>>().join(" ");
+  assert_eq!(Language::detect(&abbreviated), Some(Language::English));
+
+  let mut words = SEED.split_whitespace().collect::<Vec<_>>();
+  words[3] = "xylophone";
+  assert_eq!(Language::detect(&words.join(" ")), None);
+  // Words which aren't of any language are unknown, not of the wrong language
+  assert_eq!(
+    ClassicSeed::from_string(Language::English, &words.join(" ")),
+    Err(SeedError::UnknownWord(3))
+  );
+}
+
+const POLYSEED: &str = "raven tail swear infant grief assist regular lamp duck valid someone \
+  little harsh puppy airport language";
+
+#[test]
+fn polyseed_vectors() {
+  let seed = Polyseed::from_string(polyseed::Language::English, POLYSEED, None).unwrap();
+  assert_eq!(seed.birthday(), POLYSEED_EPOCH + POLYSEED_TIME_STEP);
+  assert_eq!(
+    seed.to_string(None).split_whitespace().collect::<Vec<_>>(),
+    POLYSEED.split_whitespace().collect::<Vec<_>>()
+  );
+  assert_eq!(
+    *seed.spend_key(),
+    scalar("6dd6b2029bfdf1c44a36ce8b229f35dcaa5800b8d858da9facf4b0a778dc2800")
+  );
+  assert_eq!(
+    *seed.view_key(),
+    scalar("3c56a3cc3e7f94dc428ffe3b856adb6054552dfa14360d4cdec3f7730b999107")
+  );
+  assert_eq!(
+    seed.view_pair().legacy_address(Network::Mainnet).to_string(),
+    concat!(
+      "47AjPj7DVPQVGGXJXbbTMZWcKQDejGHYZChVkeujy8qPLjKkgdsxge4DzvkRMgU4sDUig",
+      "GLuBN9stKBMowhuXH2HJHWAuRf",
+    )
+  );
+
+  // The estimated height as of the birthday, less a month of blocks
+  assert_eq!(seed.restore_height(Network::Mainnet), 2_485_302);
+  assert!(seed.restore_height(Network::Stagenet) < seed.restore_height(Network::Mainnet));
+}
+
+#[test]
+fn polyseed_passphrase() {
+  let encrypted = "soup festival edge attend unusual hawk slush grocery lady still knock way \
+    bubble shallow receive admit";
+  let seed = Polyseed::from_string(polyseed::Language::English, POLYSEED, None).unwrap();
+  assert_eq!(
+    seed.to_string(Some("password")).split_whitespace().collect::<Vec<_>>(),
+    encrypted.split_whitespace().collect::<Vec<_>>()
+  );
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, encrypted, Some("password")).unwrap(),
+    seed
+  );
+
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, encrypted, None),
+    Err(PolyseedError::MissingPassphrase)
+  );
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, POLYSEED, Some("password")),
+    Err(PolyseedError::UnexpectedPassphrase)
+  );
+
+  // An incorrect passphrase yields a distinct seed, with the same birthday
+  let incorrect =
+    Polyseed::from_string(polyseed::Language::English, encrypted, Some("passphrase")).unwrap();
+  assert!(incorrect != seed);
+  assert_eq!(incorrect.birthday(), seed.birthday());
+}
+
+#[test]
+fn polyseed_round_trip() {
+  for time in [0, POLYSEED_EPOCH, 1_700_000_000, u64::MAX] {
+    let seed = Polyseed::new(&mut OsRng, polyseed::Language::English, time);
+    assert!(seed.birthday() <= time.max(POLYSEED_EPOCH));
+    let words = seed.to_string(None);
+    assert_eq!(words.split_whitespace().count(), POLYSEED_LENGTH);
+    let decoded = Polyseed::from_string(polyseed::Language::English, &words, None).unwrap();
+    assert_eq!(decoded, seed);
+    assert_eq!(
+      decoded.view_pair().legacy_address(Network::Mainnet),
+      seed.view_pair().legacy_address(Network::Mainnet)
+    );
+
+    let words = seed.to_string(Some("passphrase"));
+    assert_eq!(
+      Polyseed::from_string(polyseed::Language::English, &words, Some("passphrase")).unwrap(),
+      seed
+    );
+  }
+
+  // The birthday is the start of the period containing the time
+  let time = POLYSEED_EPOCH + (3 * POLYSEED_TIME_STEP) + 1;
+  let seed = Polyseed::new(&mut OsRng, polyseed::Language::English, time);
+  assert_eq!(seed.birthday(), time - 1);
+}
+
+#[test]
+fn polyseed_errors() {
+  // Words may be abbreviated to a prefix of at least four characters
+  let abbreviated =
+    POLYSEED.split_whitespace().map(|word| &word[.. 4.min(word.len())]).collect::<Vec<_>>();
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, &abbreviated.join(" "), None).unwrap(),
+    Polyseed::from_string(polyseed::Language::English, POLYSEED, None).unwrap()
+  );
+
+  let mut words = POLYSEED.split_whitespace().collect::<Vec<_>>();
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, &words[.. 15].join(" "), None),
+    Err(PolyseedError::InvalidLength(15))
+  );
+
+  words[3] = "xylophone";
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, &words.join(" "), None),
+    Err(PolyseedError::UnknownWord(3))
+  );
+  words[3] = "inf";
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, &words.join(" "), None),
+    Err(PolyseedError::UnknownWord(3))
+  );
+
+  words[3] = "infant";
+  words[4] = "grid";
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, &words.join(" "), None),
+    Err(PolyseedError::InvalidChecksum)
+  );
+
+  // A seed with a reserved feature set
+  let reserved = "real tail sweet infant grief assist regular lamp duck valid someone little \
+    harsh puppy airport language";
+  assert_eq!(
+    Polyseed::from_string(polyseed::Language::English, reserved, None),
+    Err(PolyseedError::UnsupportedFeatures)
+  );
+}
@@ -0,0 +1,51 @@
+use zeroize::Zeroizing;
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::{Scalar, Point},
+  address::{Network, SubaddressIndex},
+  WalletKeys,
+};
+
+fn scalar(hex: &str) -> Zeroizing<Scalar> {
+  Zeroizing::new(Scalar::read(&mut hex::decode(hex).unwrap().as_slice()).unwrap())
+}
+
+#[test]
+fn wallet_keys() {
+  let spend = scalar("c0af65c0dd837e666b9d0dfed62745f4df35aed7ea619b2798a709f0fe545403");
+  let keys = WalletKeys::new(spend.clone());
+  assert_eq!(keys.spend_key(), &spend);
+  assert_eq!(
+    keys.view_key(),
+    &scalar("513ba91c538a5a9069e0094de90e927c0cd147fa10428ce3ac1afd49f63e3b01")
+  );
+
+  let view_pair = keys.view_pair();
+  assert_eq!(view_pair.spend(), Point::from(&(*spend).into() * ED25519_BASEPOINT_TABLE));
+  assert_eq!(view_pair.view(), Point::from(&(**keys.view_key()).into() * ED25519_BASEPOINT_TABLE));
+  assert_eq!(keys.legacy_address(Network::Mainnet), view_pair.legacy_address(Network::Mainnet));
+  assert_eq!(
+    keys.legacy_integrated_address(Network::Mainnet, [0xff; 8]),
+    view_pair.legacy_integrated_address(Network::Mainnet, [0xff; 8])
+  );
+}
+
+#[test]
+fn subaddress_spend_keys() {
+  let keys = WalletKeys::new(Zeroizing::new(Scalar::random(&mut rand_core::OsRng)));
+  for (account, address) in [(0, 1), (1, 0), (3, 7), (u32::MAX, u32::MAX)] {
+    let index = SubaddressIndex::new(account, address).unwrap();
+    let subaddress = keys.subaddress(Network::Mainnet, index);
+    assert_eq!(subaddress, keys.view_pair().subaddress(Network::Mainnet, index));
+    assert!(subaddress.is_subaddress());
+
+    let spend_key = keys.subaddress_spend_key(index);
+    assert!(*spend_key != **keys.spend_key());
+    assert_eq!(subaddress.spend(), Point::from(&(*spend_key).into() * ED25519_BASEPOINT_TABLE));
+  }
+}
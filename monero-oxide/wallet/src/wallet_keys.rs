@@ -0,0 +1,97 @@
+// 秘密スペンドキーから、wallet2 と同様にウォレットの鍵階層を導出します。
+// 秘密ビューキーは Hs(秘密スペンドキー)、サブアドレスの秘密スペンドキーは
+// 秘密スペンドキー + Hs("SubAddr\0" || 秘密ビューキー || アカウント || アドレス) です。
+use core::ops::Deref;
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+#[cfg(feature = "compile-time-generators")]
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+#[cfg(not(feature = "compile-time-generators"))]
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT as ED25519_BASEPOINT_TABLE;
+
+use crate::{
+  ed25519::{Scalar, Point},
+  address::{Network, SubaddressIndex, MoneroAddress},
+  ViewPair,
+};
+
+/// The keys of a wallet, derived from its private spend key.
+///
+/// The private view key is derived as wallet2 does for deterministic wallets, as `Hs(spend_key)`.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct WalletKeys {
+  spend: Zeroizing<Scalar>,
+  view_pair: ViewPair,
+}
+
+impl WalletKeys {
+  /// Create the keys for a wallet from its private spend key.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/cryptonote_basic/account.cpp
+  pub fn new(spend: Zeroizing<Scalar>) -> Self {
+    let view = Zeroizing::new(Scalar::hash(Zeroizing::new(<[u8; 32]>::from(*spend)).as_slice()));
+    Self::from_keys(spend, view)
+  }
+
+  // Create the keys for a wallet whose private view key isn't derived from its private spend key
+  pub(crate) fn from_keys(spend: Zeroizing<Scalar>, view: Zeroizing<Scalar>) -> Self {
+    let spend_point = {
+      let spend = Zeroizing::new((*spend).into());
+      Point::from(spend.deref() * ED25519_BASEPOINT_TABLE)
+    };
+    let view_pair =
+      ViewPair::new(spend_point, view).expect("spend key derived from a scalar was torsioned");
+    WalletKeys { spend, view_pair }
+  }
+
+  /// The private spend key.
+  pub fn spend_key(&self) -> &Zeroizing<Scalar> {
+    &self.spend
+  }
+
+  /// The private view key.
+  pub fn view_key(&self) -> &Zeroizing<Scalar> {
+    &self.view_pair.view
+  }
+
+  /// The ViewPair for this wallet.
+  ///
+  /// This is sufficient to scan for outputs received by this wallet, without the ability to spend
+  /// them, and is the export for a view-only wallet.
+  pub fn view_pair(&self) -> &ViewPair {
+    &self.view_pair
+  }
+
+  /// The private spend key for a subaddress.
+  ///
+  /// Transactions are signed with the wallet's private spend key, with scanned outputs'
+  /// [`key_offset`](crate::WalletOutput::key_offset) already including the subaddress's
+  /// derivation. This is for when the private spend key of a subaddress is itself needed.
+  // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
+  //   /src/device/device_default.cpp
+  pub fn subaddress_spend_key(&self, index: SubaddressIndex) -> Zeroizing<Scalar> {
+    let spend = Zeroizing::new((*self.spend).into());
+    let derivation = Zeroizing::new(self.view_pair.subaddress_derivation(index).into());
+    Zeroizing::new(Scalar::from(spend.deref() + derivation.deref()))
+  }
+
+  /// Derive a legacy address for this wallet.
+  ///
+  /// Subaddresses SHOULD be used instead.
+  pub fn legacy_address(&self, network: Network) -> MoneroAddress {
+    self.view_pair.legacy_address(network)
+  }
+
+  /// Derive a legacy integrated address for this wallet.
+  ///
+  /// Subaddresses SHOULD be used instead.
+  pub fn legacy_integrated_address(&self, network: Network, payment_id: [u8; 8]) -> MoneroAddress {
+    self.view_pair.legacy_integrated_address(network, payment_id)
+  }
+
+  /// Derive a subaddress for this wallet.
+  pub fn subaddress(&self, network: Network, subaddress: SubaddressIndex) -> MoneroAddress {
+    self.view_pair.subaddress(network, subaddress)
+  }
+}
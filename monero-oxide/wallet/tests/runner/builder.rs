@@ -5,6 +5,7 @@ use zeroize::{Zeroize, Zeroizing};
 
 use monero_wallet::{
   ringct::RctType,
+  hardfork::HardFork,
   rpc::FeeRate,
   address::MoneroAddress,
   OutputWithDecoys,
@@ -73,7 +74,14 @@ impl SignableTransactionBuilder {
   }
 
   pub fn build(self) -> Result<SignableTransaction, SendError> {
+    // RctType が許可されるハードフォークを前提に組み立てる
+    let hardfork = match self.rct_type {
+      RctType::ClsagBulletproof => HardFork::new(14),
+      _ => Some(HardFork::LATEST),
+    }
+    .unwrap();
     SignableTransaction::new(
+      hardfork,
       self.rct_type,
       self.outgoing_view_key,
       self.inputs,
@@ -5,4 +5,6 @@ pub use monero_epee;
 #[cfg(feature = "alloc")]
 pub mod alloc {
   pub use monero_wallet;
+  pub use monero_cryptonight;
+  pub use monero_randomx;
 }
@@ -18,10 +18,8 @@ use serde_json::json;
 use monero_oxide::{
   // ed25519 関連: スカラー、圧縮点、コミットメント
   ed25519::{Scalar, CompressedPoint, Commitment},
-  // ringct 関連: 可プルナブル部分の列挙型とバッチ検証器
-  ringct::{RctPrunable, bulletproofs::BatchVerifier},
   // トランザクションと入力型
-  transaction::{Input, Transaction},
+  transaction::Input,
   // ブロック型
   block::Block,
 };
@@ -35,6 +33,74 @@ use monero_simple_request_rpc::SimpleRequestRpc;
 // Tokio のタスクハンドル型をインポート（非同期タスクを表す）
 use tokio::task::JoinHandle;
 
+// RPC から出力情報（鍵とマスク）を取得して圧縮点に変換する
+// 関数シグネチャの読み方:
+// - `rpc: &impl Rpc` は参照を受け取り、トレイト境界を満たす任意の実装を許す
+// - `indexes: &[u64]` は u64 のスライス参照を受け取る
+// - 戻り値は `Vec<[CompressedPoint; 2]>`（圧縮点ペアのベクタ）
+async fn get_outs(rpc: &impl Rpc, amount: u64, indexes: &[u64]) -> Vec<[CompressedPoint; 2]> {
+  // RPC 応答の個別アウト構造体
+  #[derive(Deserialize, Debug)]
+  struct Out {
+    key: String,
+    mask: String,
+  }
+
+  // 複数アウトを包む構造体
+  #[derive(Deserialize, Debug)]
+  struct Outs {
+    outs: Vec<Out>,
+  }
+
+  // 指定したインデックス群で get_outs を呼ぶ（接続エラー時はリトライ）
+  let outs: Outs = loop {
+    match rpc
+      .rpc_call(
+        "get_outs",
+        Some(json!({
+          "get_txid": true,
+          // outputs は amount と index の配列を期待する
+          "outputs": indexes.iter().map(|o| json!({
+            "amount": amount,
+            "index": o
+          })).collect::<Vec<_>>()
+        })),
+      )
+      .await
+    {
+      Ok(outs) => break outs,
+      Err(RpcError::ConnectionError(e)) => {
+        println!("get_outs ConnectionError: {e}");
+        continue;
+      }
+      Err(e) => panic!("couldn't connect to RPC to get outs: {e:?}"),
+    }
+  };
+
+  // 16 進文字列を圧縮点に変換するクロージャ
+  // `let name = |args| expr;` はクロージャ（無名関数）の定義
+  let rpc_point = |point: &str| {
+    CompressedPoint::from(
+      <[u8; 32]>::try_from(hex::decode(point).expect("invalid hex for ring member"))
+        .expect("invalid point len for ring member"),
+    )
+  };
+
+  // 取得した outs を圧縮点の配列に変換して返す
+  outs
+    .outs
+    .iter()
+    .map(|out| {
+      let mask = rpc_point(&out.mask);
+      // amount が 0 でない場合はマスクが期待されるコミットメントと一致するか検算
+      if amount != 0 {
+        assert_eq!(mask, Commitment::new(Scalar::ONE, amount).commit().compress());
+      }
+      [rpc_point(&out.key), mask]
+    })
+    .collect()
+}
+
 // `async fn` は非同期関数を定義するための構文です。
 // 戻り値は `impl Future<Output=...>` 相当になり、`await` で待てます。
 // この関数は一つのブロックを検証するための主要ロジックを持ちます。
@@ -125,161 +191,35 @@ async fn check_block(rpc: impl Rpc, block_i: usize) {
       }
     };
 
-    // バレットプルーフ等をバッチ検証するための検証器インスタンスを生成
-    let mut batch = BatchVerifier::new();
-    // 取得した各トランザクションを順に検査
-    for tx in txs {
-      match tx {
-        // V1 トランザクション: prefix と署名が存在するはず
-        Transaction::V1 { prefix: _, signatures } => {
-          // 署名が空でないことを確認
-          assert!(!signatures.is_empty());
-          continue;
-        }
-        // V2 トランザクションで proofs が None（マイナーによる特殊ケース）ならパニック
-        Transaction::V2 { prefix: _, proofs: None } => {
-          panic!("proofs were empty in non-miner v2 transaction");
-        }
-        // V2 トランザクションで proofs が存在する通常ケース
-        Transaction::V2 { ref prefix, proofs: Some(ref proofs) } => {
-          // 署名ハッシュを取得（なければパニック）
-          let sig_hash = tx.signature_hash().expect("no signature hash for TX with proofs");
-          // 以下はサポートされる各種証明の検証処理
-          // 一部のデバッグアサーションや CLSAG のマルチシグ実装で verify を呼ぶため、
-          // signature_hash のアルゴリズムと verify の正当性を確認する目的がある
-          match &proofs.prunable {
-            // Borromean/MLSAG 系はここでは明示的な検証処理なし
-            RctPrunable::AggregateMlsagBorromean { .. } | RctPrunable::MlsagBorromean { .. } => {}
-            // Bulletproofs 系はバッチ検証に追加
-            RctPrunable::MlsagBulletproofs { bulletproof, .. } |
-            RctPrunable::MlsagBulletproofsCompactAmount { bulletproof, .. } => {
-              assert!(bulletproof.batch_verify(
-                &mut rand_core::OsRng,
-                &mut batch,
-                &proofs.base.commitments
-              ));
-            }
-            // CLSAG 型: バレットプルーフの検証と CLSAG 検証を行う
-            RctPrunable::Clsag { bulletproof, clsags, pseudo_outs } => {
-              // バレットプルーフをバッチに追加
-              assert!(bulletproof.batch_verify(
-                &mut rand_core::OsRng,
-                &mut batch,
-                &proofs.base.commitments
-              ));
-
-              // 各 CLSAG に対して論理的に対応する出力群を取得して検証
-              for (i, clsag) in clsags.iter().enumerate() {
-                // トランザクションの対応する入力から amount, key_offsets, key_image を取得
-                let (amount, key_offsets, image) = match &prefix.inputs[i] {
-                  // coinbase 入力はここでは無効（存在するならパニック）
-                  Input::Gen(_) => panic!("Input::Gen"),
-                  // ToKey 型の入力の場合はフィールドを取得
-                  Input::ToKey { amount, key_offsets, key_image } => {
-                    (amount, key_offsets, key_image)
-                  }
-                };
-
-                // オフセット（差分から実際のインデックスを計算）
-                let mut running_sum = 0;
-                let mut actual_indexes = vec![];
-                for offset in key_offsets {
-                  running_sum += offset;
-                  actual_indexes.push(running_sum);
-                }
-
-                            // 内部非同期関数: RPC から出力情報（鍵とマスク）を取得して圧縮点に変換する
-                            // 関数シグネチャの読み方:
-                            // - `rpc: &impl Rpc` は参照を受け取り、トレイト境界を満たす任意の実装を許す
-                            // - `indexes: &[u64]` は u64 のスライス参照を受け取る
-                            // - 戻り値は `Vec<[CompressedPoint; 2]>`（圧縮点ペアのベクタ）
-                            async fn get_outs(
-                              rpc: &impl Rpc,
-                              amount: u64,
-                              indexes: &[u64],
-                            ) -> Vec<[CompressedPoint; 2]> {
-                  // RPC 応答の個別アウト構造体
-                  #[derive(Deserialize, Debug)]
-                  struct Out {
-                    key: String,
-                    mask: String,
-                  }
+    // 各トランザクションの各入力のリングを RPC から解決する（リングメンバーは [出力鍵, コミットメント]）
+    let mut rings = vec![];
+    for tx in &txs {
+      let mut tx_rings = vec![];
+      for input in &tx.prefix().inputs {
+        // ToKey 型の入力の場合はフィールドを取得（coinbase 入力はここでは無効）
+        let (amount, key_offsets) = match input {
+          Input::Gen(_) => panic!("Input::Gen"),
+          Input::ToKey { amount, key_offsets, .. } => (amount, key_offsets),
+        };
 
-                  // 複数アウトを包む構造体
-                  #[derive(Deserialize, Debug)]
-                  struct Outs {
-                    outs: Vec<Out>,
-                  }
-
-                  // 指定したインデックス群で get_outs を呼ぶ（接続エラー時はリトライ）
-                  let outs: Outs = loop {
-                    match rpc
-                      .rpc_call(
-                        "get_outs",
-                        Some(json!({
-                          "get_txid": true,
-                          // outputs は amount と index の配列を期待する
-                          "outputs": indexes.iter().map(|o| json!({
-                            "amount": amount,
-                            "index": o
-                          })).collect::<Vec<_>>()
-                        })),
-                      )
-                      .await
-                    {
-                      Ok(outs) => break outs,
-                      Err(RpcError::ConnectionError(e)) => {
-                        println!("get_outs ConnectionError: {e}");
-                        continue;
-                      }
-                      Err(e) => panic!("couldn't connect to RPC to get outs: {e:?}"),
-                    }
-                  };
-
-                  // 16 進文字列を圧縮点に変換するクロージャ
-                  // `let name = |args| expr;` はクロージャ（無名関数）の定義
-                  let rpc_point = |point: &str| {
-                    CompressedPoint::from(
-                      <[u8; 32]>::try_from(
-                        hex::decode(point).expect("invalid hex for ring member"),
-                      )
-                      .expect("invalid point len for ring member"),
-                    )
-                  };
-
-                  // 取得した outs を圧縮点の配列に変換して返す
-                  outs
-                    .outs
-                    .iter()
-                    .map(|out| {
-                      let mask = rpc_point(&out.mask);
-                      // amount が 0 でない場合はマスクが期待されるコミットメントと一致するか検算
-                      if amount != 0 {
-                        assert_eq!(mask, Commitment::new(Scalar::ONE, amount).commit().compress());
-                      }
-                      [rpc_point(&out.key), mask]
-                    })
-                    .collect()
-                }
-
-                // CLSAG の検証を実行: RPC で得た出力群、イメージ、疑似アウト、署名ハッシュを渡す
-                // 非同期関数の呼び出しは `.await` で待ち、戻り値を得る点に注意
-                clsag
-                  .verify(
-                    get_outs(&rpc, amount.unwrap_or(0), &actual_indexes).await,
-                    image,
-                    &pseudo_outs[i],
-                    &sig_hash,
-                  )
-                  .unwrap(); // `unwrap()` は Result の Ok 値を取り出し、Err の場合は panic する
-              }
-            }
-          }
+        // オフセット（差分から実際のインデックスを計算）
+        let mut running_sum = 0;
+        let mut actual_indexes = vec![];
+        for offset in key_offsets {
+          running_sum += offset;
+          actual_indexes.push(running_sum);
         }
+
+        tx_rings.push(get_outs(&rpc, amount.unwrap_or(0), &actual_indexes).await);
       }
+      rings.push(tx_rings);
     }
-    // バッチに追加された全ての検証を実行
-    assert!(batch.verify());
+
+    // 署名・範囲証明・残高・キーイメージをブロック単位でバッチ検証する
+    // バッチが失敗した場合は、どのトランザクションが不正だったかを含むエラーが返る
+    block
+      .verify_transactions(&mut rand_core::OsRng, &txs, &rings)
+      .unwrap_or_else(|e| panic!("block {block_i} failed verification: {e}"));
   }
 
   // ログ出力: ブロック番号とトランザクション数を表示